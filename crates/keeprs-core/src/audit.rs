@@ -0,0 +1,109 @@
+//! Password hygiene audit of database entries.
+
+use std::collections::hash_map::{Entry as Slot, HashMap};
+use std::fmt;
+
+use crate::models::{normalize_url, Entry, Group};
+
+/// A weak password pattern found by [`audit_entry`] or [`audit`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditIssue {
+    /// The password is the same as the entry's username.
+    PasswordMatchesUsername { entry_uuid: String, title: String },
+    /// The password is the same as the entry's title.
+    PasswordMatchesTitle { entry_uuid: String, title: String },
+}
+
+impl AuditIssue {
+    /// UUID of the entry the issue is about.
+    pub fn entry_uuid(&self) -> &str {
+        match self {
+            AuditIssue::PasswordMatchesUsername { entry_uuid, .. }
+            | AuditIssue::PasswordMatchesTitle { entry_uuid, .. } => entry_uuid,
+        }
+    }
+
+    /// Short description without the entry name, for showing next to the entry.
+    pub fn summary(&self) -> &'static str {
+        match self {
+            AuditIssue::PasswordMatchesUsername { .. } => "Password is the same as the username",
+            AuditIssue::PasswordMatchesTitle { .. } => "Password is the same as the title",
+        }
+    }
+}
+
+impl fmt::Display for AuditIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditIssue::PasswordMatchesUsername { title, .. } => {
+                write!(f, "Entry '{}': password is the same as the username", title)
+            }
+            AuditIssue::PasswordMatchesTitle { title, .. } => {
+                write!(f, "Entry '{}': password is the same as the title", title)
+            }
+        }
+    }
+}
+
+/// Check one entry for weak password patterns.
+///
+/// Comparisons ignore case and surrounding whitespace; entries without a
+/// password are never flagged.
+pub fn audit_entry(entry: &Entry) -> Vec<AuditIssue> {
+    let password = entry.password.trim();
+    if password.is_empty() {
+        return Vec::new();
+    }
+
+    let same = |other: &str| !other.trim().is_empty() && other.trim().to_lowercase() == password.to_lowercase();
+    let mut issues = Vec::new();
+    if same(&entry.username) {
+        issues.push(AuditIssue::PasswordMatchesUsername { entry_uuid: entry.uuid.clone(), title: entry.title.clone() });
+    }
+    if same(&entry.title) {
+        issues.push(AuditIssue::PasswordMatchesTitle { entry_uuid: entry.uuid.clone(), title: entry.title.clone() });
+    }
+    issues
+}
+
+/// Check every entry under `root`, sorted by entry title.
+///
+/// Entries in the recycle bin are ignored.
+pub fn audit(root: &Group) -> Vec<AuditIssue> {
+    let issues: Vec<AuditIssue> = root.all_entries().iter().flat_map(audit_entry).collect();
+    if issues.is_empty() {
+        tracing::info!("Password audit found no problems");
+    } else {
+        tracing::warn!("Password audit found {} problem(s)", issues.len());
+    }
+    issues
+}
+
+/// Pairs of entries that look like the same login: same title, username and
+/// URL, ignoring case and URL normalization.
+///
+/// Each later match is paired with the first entry found, so three copies give
+/// two pairs. Entries with neither username nor URL, and entries in the
+/// recycle bin, are ignored.
+pub fn find_duplicates(root: &Group) -> Vec<(Entry, Entry)> {
+    let mut first_seen: HashMap<(String, String, String), Entry> = HashMap::new();
+    let mut pairs = Vec::new();
+    for entry in root.all_entries() {
+        if entry.username.trim().is_empty() && entry.url.trim().is_empty() {
+            continue;
+        }
+        let key = (
+            entry.title.trim().to_lowercase(),
+            entry.username.trim().to_lowercase(),
+            normalize_url(&entry.url).to_lowercase(),
+        );
+        match first_seen.entry(key) {
+            Slot::Occupied(first) => pairs.push((first.get().clone(), entry)),
+            Slot::Vacant(slot) => {
+                slot.insert(entry);
+            }
+        }
+    }
+    tracing::info!("Found {} duplicate entry pair(s)", pairs.len());
+    pairs
+}
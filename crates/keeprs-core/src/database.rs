@@ -1,9 +1,22 @@
 //! KeePass database operations wrapper.
 
-use crate::models::{Attachment, Entry, Group};
+use crate::export::{ExportEntry, ExportFormat, ExportGroup, ImportReport};
+use crate::integrity::IntegrityIssue;
+use crate::models::{Attachment, Entry, FieldMask, Group, SearchMatch, DELETED_AT_FIELD, ORIGINAL_PARENT_FIELD, PASSWORD_POLICY_FIELD};
 use anyhow::{Context, Result};
 use keepass::{Database, DatabaseKey};
 use std::path::Path;
+use zeroize::Zeroize;
+
+/// Attachment size limit used unless the frontend configures another one.
+pub const DEFAULT_MAX_ATTACHMENT_BYTES: u64 = 10 * 1024 * 1024;
+
+/// History limits KeePass applies to databases that don't set their own.
+const DEFAULT_HISTORY_MAX_ITEMS: usize = 10;
+const DEFAULT_HISTORY_MAX_SIZE: usize = 6 * 1024 * 1024;
+
+/// Format of the timestamp stored in [`DELETED_AT_FIELD`].
+const DELETED_AT_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
 
 /// Wrapper around the KeePass database for easier operations.
 #[derive(Clone)]
@@ -11,25 +24,191 @@ pub struct KeepassDatabase {
     db: Database,
     path: std::path::PathBuf,
     key: DatabaseKey,
+    /// Key file contents, kept so passwords can be verified against `key`.
+    keyfile: Option<Vec<u8>>,
+    /// Largest attachment `add_attachment` accepts (`None` for no limit).
+    max_attachment_bytes: Option<u64>,
+    /// Refuse to write the database back to disk.
+    read_only: bool,
+    /// Timestamped copies of the previous file kept by `save` (0 = none).
+    backup_count: usize,
+}
+
+/// Argon2 key derivation parameters for [`KeepassDatabase::save_as`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfSettings {
+    /// Number of passes over the memory.
+    pub iterations: u64,
+    /// Memory to use, in bytes.
+    pub memory: u64,
+    /// Number of parallel lanes.
+    pub parallelism: u32,
+}
+
+impl Default for KdfSettings {
+    /// The parameters keepass uses for new databases.
+    fn default() -> Self {
+        Self::from_config(&keepass::config::DatabaseConfig::default().kdf_config)
+            .expect("new databases use Argon2")
+    }
+}
+
+impl KdfSettings {
+    fn from_config(config: &keepass::config::KdfConfig) -> Option<Self> {
+        match *config {
+            keepass::config::KdfConfig::Argon2 { iterations, memory, parallelism, .. }
+            | keepass::config::KdfConfig::Argon2id { iterations, memory, parallelism, .. } => {
+                Some(Self { iterations, memory, parallelism })
+            }
+            keepass::config::KdfConfig::Aes { .. } => None,
+        }
+    }
+
+    /// Use these parameters in `config`, switching AES-KDF databases to Argon2.
+    fn apply(&self, config: &mut keepass::config::KdfConfig) {
+        if matches!(config, keepass::config::KdfConfig::Aes { .. }) {
+            *config = keepass::config::DatabaseConfig::default().kdf_config;
+        }
+        if let keepass::config::KdfConfig::Argon2 { iterations, memory, parallelism, .. }
+        | keepass::config::KdfConfig::Argon2id { iterations, memory, parallelism, .. } = config
+        {
+            *iterations = self.iterations;
+            *memory = self.memory;
+            *parallelism = self.parallelism;
+        }
+    }
 }
 
 impl KeepassDatabase {
     /// Open and unlock a KeePass database.
+    ///
+    /// The password is only borrowed; callers keep it in a `Zeroizing` buffer
+    /// so it is wiped once unlocking is done.
     pub fn unlock(path: impl AsRef<Path>, password: &str) -> Result<Self> {
+        Self::unlock_with_keyfile(path, Some(password), None)
+    }
+
+    /// Open and unlock a database protected by a password, a key file, or both.
+    ///
+    /// Pass `None` as the password for databases protected by a key file only.
+    #[tracing::instrument(name = "unlock", skip_all, fields(path = %path.as_ref().display(), keyfile = keyfile.is_some()))]
+    pub fn unlock_with_keyfile(path: impl AsRef<Path>, password: Option<&str>, keyfile: Option<&Path>) -> Result<Self> {
+        let start = std::time::Instant::now();
         let path = path.as_ref();
 
-        let key = DatabaseKey::new().with_password(password);
+        let keyfile = keyfile
+            .map(|keyfile_path| {
+                std::fs::read(keyfile_path)
+                    .with_context(|| format!("Failed to read key file: {}", keyfile_path.display()))
+            })
+            .transpose()?;
+        let key = Self::build_key(password, keyfile.as_deref())?;
 
-        let db = Database::open(&mut std::fs::File::open(path)?, key.clone())
+        let mut file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to read database file: {}", path.display()))?;
+        let db = Database::open(&mut file, key.clone())
             .with_context(|| format!("Failed to open database: {}", path.display()))?;
+        tracing::info!(elapsed = ?start.elapsed(), "Database unlocked");
 
         Ok(Self {
             db,
             path: path.to_path_buf(),
             key,
+            keyfile,
+            max_attachment_bytes: Some(DEFAULT_MAX_ATTACHMENT_BYTES),
+            read_only: false,
+            backup_count: 0,
         })
     }
 
+    /// Combine a password and key file contents into a database key.
+    fn build_key(password: Option<&str>, keyfile: Option<&[u8]>) -> Result<DatabaseKey> {
+        if password.is_none() && keyfile.is_none() {
+            anyhow::bail!("A password or a key file is required");
+        }
+
+        let mut key = DatabaseKey::new();
+        if let Some(password) = password {
+            key = key.with_password(password);
+        }
+        if let Some(mut keyfile) = keyfile {
+            key = key.with_keyfile(&mut keyfile).context("Failed to load key file")?;
+        }
+        Ok(key)
+    }
+
+    /// Check that a database file exists and can be read, before asking for a password.
+    pub fn check_file(path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+
+        let metadata = match std::fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                anyhow::bail!("Database file not found: {}", path.display())
+            }
+            Err(e) => return Err(e).with_context(|| format!("Cannot access database file: {}", path.display())),
+        };
+        if !metadata.is_file() {
+            anyhow::bail!("Database path is not a file: {}", path.display());
+        }
+
+        std::fs::File::open(path).with_context(|| format!("Cannot read database file: {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Create a new, empty database at `path` protected by `password`.
+    ///
+    /// Fails if a file already exists there, so nothing gets overwritten.
+    pub fn create(path: impl AsRef<Path>, password: &str) -> Result<Self> {
+        let path = path.as_ref();
+        if path.exists() {
+            anyhow::bail!("A file already exists at {}", path.display());
+        }
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+
+        let db = Self {
+            db: Database::new(Default::default()),
+            path: path.to_path_buf(),
+            key: DatabaseKey::new().with_password(password),
+            keyfile: None,
+            max_attachment_bytes: Some(DEFAULT_MAX_ATTACHMENT_BYTES),
+            read_only: false,
+            backup_count: 0,
+        };
+        db.save()?;
+        Ok(db)
+    }
+
+    /// Check a password against the key the database was unlocked with.
+    ///
+    /// The comparison happens in memory: the file isn't re-read and the KDF isn't run.
+    /// With a key file, an empty password stands for "key file only".
+    pub fn verify_password(&self, password: &str) -> bool {
+        let password = (!password.is_empty() || self.keyfile.is_none()).then_some(password);
+        Self::build_key(password, self.keyfile.as_deref()).is_ok_and(|key| key == self.key)
+    }
+
+    /// Replace the master password, keeping the key file if there is one.
+    ///
+    /// Only the in-memory key changes; the next `save()` writes the database
+    /// encrypted with the new key.
+    pub fn change_master_password(&mut self, new_password: &str) -> Result<()> {
+        if new_password.is_empty() {
+            anyhow::bail!("The new password can't be empty");
+        }
+        self.key = Self::build_key(Some(new_password), self.keyfile.as_deref())?;
+        Ok(())
+    }
+
+    /// Entries whose title, username, URL, notes or tags contain every word of
+    /// `query` (ignoring case), outside the recycle bin.
+    pub fn search(&self, query: &str) -> Vec<SearchMatch> {
+        self.root_group().search(query, FieldMask::ALL)
+    }
+
     /// Get the root group of the database.
     pub fn root_group(&self) -> Group {
         self.convert_group(&self.db.root)
@@ -63,12 +242,18 @@ impl KeepassDatabase {
                 })
                 .collect(),
             is_recycle_bin: self.is_recycle_bin(&kg.uuid.to_string()),
+            location_changed: kg.times.get_location_changed().copied(),
+            password_policy: match kg.custom_data.items.get(PASSWORD_POLICY_FIELD).and_then(|item| item.value.as_ref()) {
+                Some(keepass::db::Value::Unprotected(policy)) if !policy.trim().is_empty() => Some(policy.clone()),
+                _ => None,
+            },
         }
     }
 
     /// Convert a keepass::Entry to our Entry model.
     fn convert_entry(&self, ke: &keepass::db::Entry) -> Entry {
         let mut custom_fields = std::collections::HashMap::new();
+        let mut protected_fields = std::collections::HashSet::new();
         let mut attachments = Vec::new();
 
         for (key, val) in &ke.fields {
@@ -86,22 +271,31 @@ impl KeepassDatabase {
                         filename: key.clone(),
                         _mime_type: None,
                         data: bytes.clone(),
+                        unresolved_ref: None,
                     });
                 }
                 keepass::db::Value::BinaryRef(ref_id) => {
-                    if let Ok(index) = ref_id.parse::<usize>() {
-                        if let Some(att) = self.db.header_attachments.get(index) {
-                            attachments.push(Attachment {
-                                filename: key.clone(),
-                                _mime_type: None,
-                                data: att.content.clone(),
-                            });
-                        } else {
-                            tracing::warn!("Attachment reference {} not found in header", index);
+                    let content = match ref_id.parse::<usize>() {
+                        Ok(index) => {
+                            let content = self.db.header_attachments.get(index).map(|att| att.content.clone());
+                            if content.is_none() {
+                                tracing::warn!("Attachment reference {} not found in header", index);
+                            }
+                            content
                         }
-                    } else {
-                        tracing::warn!("Invalid attachment reference format: {}", ref_id);
-                    }
+                        Err(_) => {
+                            tracing::warn!("Invalid attachment reference format: {}", ref_id);
+                            None
+                        }
+                    };
+
+                    // Keep unresolved references visible as placeholders instead of dropping them
+                    attachments.push(Attachment {
+                        filename: key.clone(),
+                        _mime_type: None,
+                        unresolved_ref: content.is_none().then(|| ref_id.clone()),
+                        data: content.unwrap_or_default(),
+                    });
                 }
                 keepass::db::Value::Unprotected(s) => {
                     custom_fields.insert(key.clone(), s.clone());
@@ -109,6 +303,7 @@ impl KeepassDatabase {
                 keepass::db::Value::Protected(_) => {
                     if let Some(s) = ke.get(key) {
                         custom_fields.insert(key.clone(), s.to_string());
+                        protected_fields.insert(key.clone());
                     }
                 }
             }
@@ -120,11 +315,369 @@ impl KeepassDatabase {
             username: ke.get_username().unwrap_or_default().to_string(),
             password: ke.get_password().unwrap_or_default().to_string(),
             url: ke.get_url().unwrap_or_default().to_string(),
+            override_url: ke.override_url.clone().filter(|u| !u.trim().is_empty()),
             notes: ke.get("Notes").unwrap_or_default().to_string(),
             custom_fields,
+            protected_fields,
             otp: ke.get_raw_otp_value().map(|s| s.to_string()),
             attachments,
+            tags: ke.tags.clone(),
+            foreground_color: ke.foreground_color.as_ref().map(|c| c.to_string()),
+            background_color: ke.background_color.as_ref().map(|c| c.to_string()),
+            location_changed: ke.times.get_location_changed().copied(),
+            last_modified: ke.times.get_last_modification().copied(),
+            expiry_time: Self::expiry(&ke.times),
+            history_len: ke.history.as_ref().map_or(0, |history| history.get_entries().len()),
+        }
+    }
+
+    /// Write every entry as CSV with the KeePassXC-compatible columns of
+    /// [`crate::export::CSV_HEADER`], one row per entry.
+    ///
+    /// The first column is the `Group/Subgroup` path. The recycle bin is left
+    /// out. The output holds all passwords in plain text.
+    pub fn export_csv(&self, mut writer: impl std::io::Write) -> Result<()> {
+        let mut root = self.root_group();
+        root.children.retain(|child| !child.is_recycle_bin);
+
+        let tree = ExportGroup::from_group(&root, &|_| true).unwrap_or_else(|| ExportGroup {
+            name: root.name.clone(),
+            ..Default::default()
+        });
+        let csv = ExportFormat::Csv.render(&tree)?;
+        writer.write_all(csv.as_bytes()).context("Failed to write CSV export")?;
+        writer.flush().context("Failed to write CSV export")?;
+        tracing::info!("Exported {} entries as CSV", tree.entry_count());
+        Ok(())
+    }
+
+    /// Write the whole group tree as JSON, in the format read by [`Self::import_json`].
+    ///
+    /// Entries keep their custom fields, tags and attachment filenames. The
+    /// attachment content (base64) is only included with `include_attachment_data`,
+    /// so large databases can be dumped metadata-only. The recycle bin is left
+    /// out. The output holds all passwords in plain text.
+    pub fn export_json(&self, mut writer: impl std::io::Write, include_attachment_data: bool) -> Result<()> {
+        let mut root = self.root_group();
+        root.children.retain(|child| !child.is_recycle_bin);
+
+        let mut tree = ExportGroup::from(&root);
+        if !include_attachment_data {
+            tree.strip_attachment_data();
+        }
+        serde_json::to_writer_pretty(&mut writer, &tree).context("Failed to write JSON export")?;
+        writer.flush().context("Failed to write JSON export")?;
+        tracing::info!("Exported {} entries as JSON", tree.entry_count());
+        Ok(())
+    }
+
+    /// Export the entries with the given UUIDs, keeping their group structure.
+    pub fn export_entries(&self, uuids: &[String], format: ExportFormat) -> Result<String> {
+        let wanted: std::collections::HashSet<&str> = uuids.iter().map(String::as_str).collect();
+        let Some(tree) = ExportGroup::from_group(&self.root_group(), &|e| wanted.contains(e.uuid.as_str())) else {
+            anyhow::bail!("None of the selected entries were found");
+        };
+
+        let exported = tree.entry_count();
+        if exported < wanted.len() {
+            tracing::warn!("{} selected entries were not found and were skipped", wanted.len() - exported);
+        }
+        tracing::info!("Exporting {} entries as {:?}", exported, format);
+        format.render(&tree)
+    }
+
+    /// Import a JSON export, recreating its groups and entries.
+    ///
+    /// The exported tree is merged into `target_group_uuid` (or the root group),
+    /// reusing existing groups with the same name at each level. Entries get fresh
+    /// UUIDs unless `preserve_uuids` is set and the UUID is not already in use.
+    /// Malformed records, and attachments [`Self::add_attachment`] would refuse,
+    /// are skipped and listed in the report.
+    /// [`Self::preview_import_json`] reports the same without importing.
+    pub fn import_json(
+        &mut self,
+        json: &str,
+        target_group_uuid: Option<&str>,
+        preserve_uuids: bool,
+    ) -> Result<ImportReport> {
+        let tree = Self::parse_json_import(json)?;
+        self.import_tree(&tree, target_group_uuid, preserve_uuids)
+    }
+
+    /// Report what [`Self::import_json`] would do with the same arguments,
+    /// without changing the database.
+    pub fn preview_import_json(
+        &self,
+        json: &str,
+        target_group_uuid: Option<&str>,
+        preserve_uuids: bool,
+    ) -> Result<ImportReport> {
+        let tree = Self::parse_json_import(json)?;
+        self.preview_import_tree(&tree, target_group_uuid, preserve_uuids)
+    }
+
+    /// Import KeePassXC-style CSV into the group with the given UUID and return
+    /// the number of entries added.
+    ///
+    /// Columns are matched by the header row (see [`ExportGroup::from_csv`]).
+    /// Subgroups named by the `Group` column are created when missing. Malformed
+    /// rows are logged and skipped; [`Self::preview_import_csv`] lists them.
+    pub fn import_csv(&mut self, mut reader: impl std::io::Read, parent_group_uuid: &str) -> Result<usize> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text).context("Failed to read CSV file")?;
+        let (tree, errors) = ExportGroup::from_csv(&text)?;
+        for error in &errors {
+            tracing::warn!("Skipped CSV record: {}", error);
         }
+
+        let tree = serde_json::to_value(&tree).context("Failed to convert CSV records")?;
+        let report = self.import_tree(&tree, Some(parent_group_uuid), false)?;
+        Ok(report.entries_imported)
+    }
+
+    /// Report what [`Self::import_csv`] would do with `text`, without changing
+    /// the database.
+    pub fn preview_import_csv(&self, text: &str, parent_group_uuid: &str) -> Result<ImportReport> {
+        let (tree, errors) = ExportGroup::from_csv(text)?;
+        let tree = serde_json::to_value(&tree).context("Failed to convert CSV records")?;
+        let mut report = self.preview_import_tree(&tree, Some(parent_group_uuid), false)?;
+        report.skipped.splice(0..0, errors);
+        Ok(report)
+    }
+
+    fn import_tree(
+        &mut self,
+        tree: &serde_json::Value,
+        target_group_uuid: Option<&str>,
+        preserve_uuids: bool,
+    ) -> Result<ImportReport> {
+        let mut existing_uuids = std::collections::HashSet::new();
+        Self::collect_uuids(&self.db.root, &mut existing_uuids);
+
+        let max_attachment_bytes = self.max_attachment_bytes;
+        let root_uuid = self.db.root.uuid.to_string();
+        let target = match target_group_uuid {
+            Some(uuid) if uuid != root_uuid => match Self::find_node_recursive_mut(&mut self.db.root, uuid) {
+                Some(keepass::db::Node::Group(g)) => g,
+                _ => anyhow::bail!("Target group with UUID {} not found", uuid),
+            },
+            _ => &mut self.db.root,
+        };
+
+        let mut report = ImportReport::default();
+        Self::import_group_contents(target, tree, "", preserve_uuids, max_attachment_bytes, &mut existing_uuids, &mut report);
+
+        tracing::info!(
+            "Imported {} entries ({} new groups, {} records skipped)",
+            report.entries_imported,
+            report.groups_created,
+            report.skipped.len()
+        );
+        Ok(report)
+    }
+
+    fn preview_import_tree(
+        &self,
+        tree: &serde_json::Value,
+        target_group_uuid: Option<&str>,
+        preserve_uuids: bool,
+    ) -> Result<ImportReport> {
+        let mut existing_uuids = std::collections::HashSet::new();
+        Self::collect_uuids(&self.db.root, &mut existing_uuids);
+
+        let target = match target_group_uuid {
+            Some(uuid) => match Self::find_group_recursive(&self.db.root, uuid) {
+                Some(g) => g,
+                None => anyhow::bail!("Target group with UUID {} not found", uuid),
+            },
+            None => &self.db.root,
+        };
+
+        // Merging only looks at existing groups, so a copy of the group tree is enough
+        let mut scratch = Self::group_skeleton(target);
+        let mut report = ImportReport::default();
+        Self::import_group_contents(
+            &mut scratch,
+            tree,
+            "",
+            preserve_uuids,
+            self.max_attachment_bytes,
+            &mut existing_uuids,
+            &mut report,
+        );
+        Ok(report)
+    }
+
+    fn parse_json_import(json: &str) -> Result<serde_json::Value> {
+        let tree: serde_json::Value = serde_json::from_str(json).context("Invalid JSON export")?;
+        if !tree.is_object() {
+            anyhow::bail!("Invalid JSON export: expected a group object at the top level");
+        }
+        Ok(tree)
+    }
+
+    /// Copy of `group` and its subgroups, without entries.
+    fn group_skeleton(group: &keepass::db::Group) -> keepass::db::Group {
+        let mut skeleton = keepass::db::Group::new(&group.name);
+        skeleton.uuid = group.uuid;
+        skeleton.children = group
+            .children
+            .iter()
+            .filter_map(|node| match node {
+                keepass::db::Node::Group(g) => Some(keepass::db::Node::Group(Self::group_skeleton(g))),
+                keepass::db::Node::Entry(_) => None,
+            })
+            .collect();
+        skeleton
+    }
+
+    fn find_group_recursive<'a>(group: &'a keepass::db::Group, uuid: &str) -> Option<&'a keepass::db::Group> {
+        if group.uuid.to_string() == uuid {
+            return Some(group);
+        }
+        group.children.iter().find_map(|node| match node {
+            keepass::db::Node::Group(g) => Self::find_group_recursive(g, uuid),
+            keepass::db::Node::Entry(_) => None,
+        })
+    }
+
+    fn import_group_contents(
+        group: &mut keepass::db::Group,
+        value: &serde_json::Value,
+        path: &str,
+        preserve_uuids: bool,
+        max_attachment_bytes: Option<u64>,
+        existing_uuids: &mut std::collections::HashSet<String>,
+        report: &mut ImportReport,
+    ) {
+        let records = |key: &str| value.get(key).and_then(|v| v.as_array()).cloned().unwrap_or_default();
+
+        for (i, record) in records("entries").into_iter().enumerate() {
+            match serde_json::from_value::<ExportEntry>(record) {
+                Ok(exported) => {
+                    let entry = Self::build_imported_entry(&exported, preserve_uuids, max_attachment_bytes, existing_uuids, report);
+                    group.children.push(keepass::db::Node::Entry(entry));
+                    report.entries_imported += 1;
+                }
+                Err(e) => report.skipped.push(format!("Entry #{} in '{}': {}", i + 1, group.name, e)),
+            }
+        }
+
+        for (i, record) in records("groups").into_iter().enumerate() {
+            let Some(name) = record.get("name").and_then(|n| n.as_str()) else {
+                report.skipped.push(format!("Group #{} in '{}': missing name", i + 1, group.name));
+                continue;
+            };
+
+            let child_path = if path.is_empty() { name.to_string() } else { format!("{}/{}", path, name) };
+            let existing = group.children.iter().position(|node| {
+                matches!(node, keepass::db::Node::Group(g) if g.name == name)
+            });
+            let index = existing.unwrap_or_else(|| {
+                let mut new_group = keepass::db::Group::new(name);
+                if let Some(uuid) = record.get("uuid").and_then(|u| u.as_str()) {
+                    if preserve_uuids && !existing_uuids.contains(uuid) {
+                        if let Ok(parsed) = uuid.parse() {
+                            new_group.uuid = parsed;
+                        }
+                    }
+                }
+                existing_uuids.insert(new_group.uuid.to_string());
+                group.children.push(keepass::db::Node::Group(new_group));
+                report.groups_created += 1;
+                report.new_groups.push(child_path.clone());
+                group.children.len() - 1
+            });
+
+            if let keepass::db::Node::Group(child) = &mut group.children[index] {
+                Self::import_group_contents(
+                    child,
+                    &record,
+                    &child_path,
+                    preserve_uuids,
+                    max_attachment_bytes,
+                    existing_uuids,
+                    report,
+                );
+            }
+        }
+    }
+
+    fn build_imported_entry(
+        exported: &ExportEntry,
+        preserve_uuids: bool,
+        max_attachment_bytes: Option<u64>,
+        existing_uuids: &mut std::collections::HashSet<String>,
+        report: &mut ImportReport,
+    ) -> keepass::db::Entry {
+        let mut entry = keepass::db::Entry::new();
+
+        if let Some(ref uuid) = exported.uuid {
+            if preserve_uuids && !existing_uuids.contains(uuid) {
+                if let Ok(parsed) = uuid.parse() {
+                    entry.uuid = parsed;
+                }
+            }
+        }
+        existing_uuids.insert(entry.uuid.to_string());
+
+        entry.fields.insert("Title".to_string(), keepass::db::Value::Unprotected(exported.title.clone()));
+        entry.fields.insert("UserName".to_string(), keepass::db::Value::Unprotected(exported.username.clone()));
+        entry.fields.insert("Password".to_string(), keepass::db::Value::Protected(exported.password.as_bytes().into()));
+        entry.fields.insert("URL".to_string(), keepass::db::Value::Unprotected(exported.url.clone()));
+        entry.fields.insert("Notes".to_string(), keepass::db::Value::Unprotected(exported.notes.clone()));
+
+        for (k, v) in &exported.custom_fields {
+            let value = if exported.protected_fields.contains(k) {
+                keepass::db::Value::Protected(v.as_bytes().into())
+            } else {
+                keepass::db::Value::Unprotected(v.clone())
+            };
+            entry.fields.insert(k.clone(), value);
+        }
+        if let Some(ref otp) = exported.otp {
+            entry.fields.entry("otp".to_string()).or_insert_with(|| keepass::db::Value::Unprotected(otp.clone()));
+        }
+
+        for attachment in &exported.attachments {
+            let checked = attachment.decode().and_then(|data| {
+                Self::check_attachment(&entry, &attachment.filename, data.len(), max_attachment_bytes)?;
+                Ok(data)
+            });
+            match checked {
+                Ok(data) => {
+                    entry.fields.insert(attachment.filename.clone(), keepass::db::Value::Bytes(data));
+                }
+                Err(e) => report.skipped.push(format!("Attachment in '{}': {:#}", exported.title, e)),
+            }
+        }
+
+        entry.tags = exported.tags.clone();
+        entry.override_url = exported.override_url.clone();
+        entry
+    }
+
+    fn collect_uuids(group: &keepass::db::Group, uuids: &mut std::collections::HashSet<String>) {
+        uuids.insert(group.uuid.to_string());
+        for node in &group.children {
+            match node {
+                keepass::db::Node::Group(g) => Self::collect_uuids(g, uuids),
+                keepass::db::Node::Entry(e) => {
+                    uuids.insert(e.uuid.to_string());
+                }
+            }
+        }
+    }
+
+    /// Expand `{USERNAME}`/`{PASSWORD}`/`{URL}` placeholders and `{REF:...}`
+    /// references in `raw`, a field value of the entry with the given UUID.
+    ///
+    /// See [`Group::resolve_field`]; values without placeholders are returned as is.
+    pub fn resolve_field(&self, entry_uuid: &str, raw: &str) -> String {
+        if !raw.contains('{') {
+            return raw.to_string();
+        }
+        self.root_group().resolve_field(entry_uuid, raw)
     }
 
     /// Find an entry by UUID.
@@ -150,9 +703,51 @@ impl KeepassDatabase {
     }
 
     /// Save the database to disk.
+    ///
+    /// Fails if the database was opened read-only.
+    #[tracing::instrument(skip_all, fields(path = %self.path.display()))]
     pub fn save(&self) -> Result<()> {
-        // Atomic save: write to temp file then rename
-        let mut temp_path = self.path.clone();
+        if self.read_only {
+            anyhow::bail!("The database is open read-only");
+        }
+        let start = std::time::Instant::now();
+        if self.backup_count > 0 && self.path.exists() {
+            self.back_up()?;
+        }
+        Self::write_atomically(&self.db, &self.path, self.key.clone())?;
+        tracing::info!(elapsed = ?start.elapsed(), "Database saved");
+        Ok(())
+    }
+
+    /// Write a copy of the database to `new_path`, protected by `new_password`
+    /// alone and derived with `kdf`.
+    ///
+    /// The open database keeps its own path, key and KDF settings.
+    #[tracing::instrument(skip_all, fields(path = %new_path.display()))]
+    pub fn save_as(&self, new_path: &Path, new_password: &str, kdf: KdfSettings) -> Result<()> {
+        let start = std::time::Instant::now();
+        if new_password.is_empty() {
+            anyhow::bail!("The copy needs a password");
+        }
+        if new_path == self.path {
+            anyhow::bail!("Choose a different file than the open database");
+        }
+
+        let mut copy = self.db.clone();
+        kdf.apply(&mut copy.config.kdf_config);
+        Self::write_atomically(&copy, new_path, DatabaseKey::new().with_password(new_password))?;
+        tracing::info!(elapsed = ?start.elapsed(), "Database copy saved");
+        Ok(())
+    }
+
+    /// Key derivation settings of the open database, if it uses Argon2.
+    pub fn kdf_settings(&self) -> Option<KdfSettings> {
+        KdfSettings::from_config(&self.db.config.kdf_config)
+    }
+
+    /// Write `db` to a temp file next to `path`, then move it into place.
+    fn write_atomically(db: &Database, path: &Path, key: DatabaseKey) -> Result<()> {
+        let mut temp_path = path.to_path_buf();
         if let Some(ext) = temp_path.extension() {
             let mut ext = ext.to_os_string();
             ext.push(".tmp");
@@ -165,16 +760,15 @@ impl KeepassDatabase {
             let mut file = std::fs::File::create(&temp_path)
                 .with_context(|| format!("Failed to create temp database file: {}", temp_path.display()))?;
 
-            self.db
-                .save(&mut file, self.key.clone())
+            db.save(&mut file, key)
                 .with_context(|| "Failed to save database to temp file")?;
             
             // Ensure data is flushed to disk
             file.sync_all().context("Failed to sync temp database file")?;
         }
 
-        std::fs::rename(&temp_path, &self.path)
-            .with_context(|| format!("Failed to replace database file: {}", self.path.display()))?;
+        std::fs::rename(&temp_path, path)
+            .with_context(|| format!("Failed to replace database file: {}", path.display()))?;
 
         Ok(())
     }
@@ -185,19 +779,28 @@ impl KeepassDatabase {
     }
 
     /// Update an entry in the database.
+    #[tracing::instrument(skip_all, fields(uuid = %entry.uuid))]
     pub fn update_entry(&mut self, entry: &Entry) -> Result<()> {
-        if Self::update_entry_recursive(&mut self.db.root, entry) {
+        let start = std::time::Instant::now();
+        if Self::update_entry_recursive(&mut self.db.root, &self.db.meta, entry) {
+            tracing::debug!(elapsed = ?start.elapsed(), "Entry updated");
             Ok(())
         } else {
             anyhow::bail!("Entry with UUID {} not found", entry.uuid)
         }
     }
 
-    fn update_entry_recursive(group: &mut keepass::db::Group, entry: &Entry) -> bool {
+    fn update_entry_recursive(group: &mut keepass::db::Group, meta: &keepass::db::Meta, entry: &Entry) -> bool {
         for node in &mut group.children {
             match node {
                 keepass::db::Node::Entry(e) => {
                     if e.uuid.to_string() == entry.uuid {
+                        if tracing::enabled!(tracing::Level::DEBUG) {
+                            Self::log_changed_fields(e, entry);
+                        }
+
+                        let before = e.clone();
+
                         // Update standard fields
                         e.fields.insert(
                             "Title".to_string(),
@@ -222,15 +825,29 @@ impl KeepassDatabase {
 
                         // Update custom fields (simple overwrite for now)
                         for (k, v) in &entry.custom_fields {
-                            e.fields
-                                .insert(k.clone(), keepass::db::Value::Unprotected(v.clone()));
+                            e.fields.insert(k.clone(), Self::custom_field_value(entry, k, v));
                         }
+                        // The model carries every text field, so one it no longer has was
+                        // deleted or renamed. Anything else (inline `Bytes` or header
+                        // `BinaryRef` attachments) is managed separately and kept.
+                        e.fields.retain(|k, v| {
+                            !matches!(v, keepass::db::Value::Unprotected(_) | keepass::db::Value::Protected(_))
+                                || matches!(k.as_str(), "Title" | "UserName" | "Password" | "URL" | "Notes")
+                                || entry.custom_fields.contains_key(k)
+                        });
 
+                        e.tags = entry.tags.clone();
+                        e.override_url = entry.override_url.clone();
+                        e.foreground_color = Self::parse_color(entry.foreground_color.as_deref());
+                        e.background_color = Self::parse_color(entry.background_color.as_deref());
+                        Self::set_expiry(&mut e.times, entry.expiry_time);
+
+                        Self::record_history(e, before, meta);
                         return true;
                     }
                 }
                 keepass::db::Node::Group(g) => {
-                    if Self::update_entry_recursive(g, entry) {
+                    if Self::update_entry_recursive(g, meta, entry) {
                         return true;
                     }
                 }
@@ -239,6 +856,351 @@ impl KeepassDatabase {
         false
     }
 
+    /// Open the database read-only: [`Self::save`] refuses to write it.
+    ///
+    /// Changes can still be made in memory, and [`Self::save_as`] still writes copies.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    /// Whether [`Self::save`] refuses to write the database.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Keep this many timestamped backups (`<path>.<timestamp>.bak`) of the
+    /// previous file when saving; 0 disables backups.
+    pub fn set_backup_count(&mut self, count: usize) {
+        self.backup_count = count;
+    }
+
+    /// Copy the file on disk to a new timestamped backup and delete the oldest
+    /// backups beyond `backup_count`.
+    ///
+    /// Timestamps are UTC with milliseconds; an existing backup is never
+    /// overwritten, a counter is added to the name instead.
+    fn back_up(&self) -> Result<()> {
+        let file_name = self
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .context("The database path has no file name")?;
+        let dir = self.path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+
+        let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%3fZ").to_string();
+        let (backup_path, mut backup) = Self::create_backup_file(dir, &file_name, &stamp)?;
+        let copied = std::fs::File::open(&self.path).and_then(|mut source| {
+            backup.set_permissions(source.metadata()?.permissions())?;
+            std::io::copy(&mut source, &mut backup)
+        });
+        if let Err(e) = copied {
+            let _ = std::fs::remove_file(&backup_path);
+            return Err(e).with_context(|| format!("Failed to back up the database to {}", backup_path.display()));
+        }
+        tracing::info!("Backed up the database to {}", backup_path.display());
+
+        let prefix = format!("{}.", file_name);
+        let mut backups: Vec<((String, u32), std::path::PathBuf)> = match std::fs::read_dir(dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter_map(|path| {
+                    let key = path.file_name().and_then(|n| n.to_str()).and_then(|name| Self::backup_key(name, &prefix))?;
+                    Some((key, path))
+                })
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Failed to list backups in {}: {}", dir.display(), e);
+                return Ok(());
+            }
+        };
+        // Timestamps (then counters) sort chronologically, so the oldest backups come first
+        backups.sort();
+        let backups: Vec<std::path::PathBuf> = backups.into_iter().map(|(_, path)| path).collect();
+        let excess = backups.len().saturating_sub(self.backup_count);
+        for old in &backups[..excess] {
+            match std::fs::remove_file(old) {
+                Ok(()) => tracing::info!("Removed old backup {}", old.display()),
+                Err(e) => tracing::warn!("Failed to remove old backup {}: {}", old.display(), e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Create `<file_name>.<stamp>.bak` in `dir`, or `<file_name>.<stamp>-<n>.bak`
+    /// with the first free `n` if that exists already.
+    fn create_backup_file(dir: &Path, file_name: &str, stamp: &str) -> Result<(std::path::PathBuf, std::fs::File)> {
+        for counter in 0u32.. {
+            let name = match counter {
+                0 => format!("{}.{}.bak", file_name, stamp),
+                n => format!("{}.{}-{}.bak", file_name, stamp, n),
+            };
+            let path = dir.join(name);
+            match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => return Ok((path, file)),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e).with_context(|| format!("Failed to create backup {}", path.display())),
+            }
+        }
+        anyhow::bail!("No free backup name for {} in {}", file_name, dir.display())
+    }
+
+    /// Timestamp and counter of a backup made by [`Self::back_up`], from its file
+    /// name, or `None` if `name` isn't one.
+    fn backup_key(name: &str, prefix: &str) -> Option<(String, u32)> {
+        let rest = name.strip_prefix(prefix)?.strip_suffix(".bak")?;
+        let fits = |stamp: &str, pattern: &str| {
+            stamp.len() == pattern.len()
+                && stamp.chars().zip(pattern.chars()).all(|(c, p)| if p == '9' { c.is_ascii_digit() } else { c == p })
+        };
+        let (stamp, counter) = match rest.split_once("Z-") {
+            Some((stamp, counter)) => (format!("{}Z", stamp), counter.parse().ok()?),
+            None => (rest.to_string(), 0),
+        };
+        fits(&stamp, "99999999T999999999Z").then_some((stamp, counter))
+    }
+
+    /// Set the largest attachment [`Self::add_attachment`] accepts (`None` for no limit).
+    pub fn set_max_attachment_bytes(&mut self, max: Option<u64>) {
+        self.max_attachment_bytes = max;
+    }
+
+    /// Attach a file to an entry.
+    ///
+    /// Every save re-encrypts all attachments, so files over the configured
+    /// size limit are refused.
+    pub fn add_attachment(&mut self, entry_uuid: &str, filename: &str, data: Vec<u8>) -> Result<()> {
+        let max_attachment_bytes = self.max_attachment_bytes;
+        let e = Self::find_keepass_entry_mut(&mut self.db.root, entry_uuid)
+            .with_context(|| format!("Entry with UUID {} not found", entry_uuid))?;
+        Self::check_attachment(e, filename, data.len(), max_attachment_bytes)?;
+
+        let before = e.clone();
+        e.fields.insert(filename.to_string(), keepass::db::Value::Bytes(data));
+        Self::record_history(e, before, &self.db.meta);
+        Ok(())
+    }
+
+    /// Refuse an attachment of `len` bytes named `filename` if it is over the
+    /// size limit, unnamed, or named like a field or attachment `e` already has.
+    fn check_attachment(e: &keepass::db::Entry, filename: &str, len: usize, max_bytes: Option<u64>) -> Result<()> {
+        if let Some(max) = max_bytes {
+            if len as u64 > max {
+                anyhow::bail!(
+                    "Attachment '{}' is {} bytes, more than the limit of {} bytes",
+                    filename,
+                    len,
+                    max
+                );
+            }
+        }
+        if filename.is_empty() {
+            anyhow::bail!("Attachment name can't be empty");
+        }
+        if matches!(filename, "Title" | "UserName" | "Password" | "URL" | "Notes") || e.fields.contains_key(filename) {
+            anyhow::bail!("Entry already has a field or attachment named '{}'", filename);
+        }
+        Ok(())
+    }
+
+    /// Add `before` to the entry's history if the entry's content changed since.
+    ///
+    /// The old version keeps its own modification time, so history versions can
+    /// be told apart; the live entry is stamped as modified now. The oldest
+    /// versions beyond the database's history limits are dropped.
+    fn record_history(e: &mut keepass::db::Entry, mut before: keepass::db::Entry, meta: &keepass::db::Meta) {
+        if Self::same_content(e, &before) {
+            return;
+        }
+        before.history = None;
+        let history = e.history.get_or_insert_with(Default::default);
+        history.add_entry(before);
+        Self::trim_history(history, meta);
+        e.times.set_last_modification(keepass::db::Times::now());
+    }
+
+    /// Keep at most `history_max_items` versions, newest first, and no more than
+    /// fit in `history_max_size` bytes (KeePass defaults: 10 versions, 6 MiB).
+    fn trim_history(history: &mut keepass::db::History, meta: &keepass::db::Meta) {
+        let max_items = meta.history_max_items.unwrap_or(DEFAULT_HISTORY_MAX_ITEMS);
+        let max_size = meta.history_max_size.unwrap_or(DEFAULT_HISTORY_MAX_SIZE);
+
+        let versions = history.get_entries_mut();
+        versions.truncate(max_items);
+        let mut total = 0;
+        let fitting = versions
+            .iter()
+            .take_while(|old| {
+                total += Self::entry_size(old);
+                total <= max_size
+            })
+            .count();
+        versions.truncate(fitting);
+    }
+
+    /// Approximate size of an entry version: its field names and values and its tags.
+    fn entry_size(e: &keepass::db::Entry) -> usize {
+        let fields: usize = e
+            .fields
+            .iter()
+            .map(|(key, value)| {
+                key.len()
+                    + match value {
+                        keepass::db::Value::Unprotected(text) => text.len(),
+                        keepass::db::Value::Protected(secret) => secret.unsecure().len(),
+                        keepass::db::Value::Bytes(bytes) => bytes.len(),
+                        keepass::db::Value::BinaryRef(index) => index.len(),
+                    }
+            })
+            .sum();
+        fields + e.tags.iter().map(String::len).sum::<usize>()
+    }
+
+    /// Compare the user-visible content of two entries.
+    ///
+    /// Toggling the protection of a custom field counts as a change. The standard
+    /// fields are compared by value alone: `update_entry` stores them with fixed
+    /// protection, which may differ from what another client wrote.
+    fn same_content(a: &keepass::db::Entry, b: &keepass::db::Entry) -> bool {
+        use keepass::db::Value;
+
+        let same_value = |key: &str, x: &Value, y: &Value| match (x, y) {
+            (Value::Protected(p), Value::Unprotected(u)) | (Value::Unprotected(u), Value::Protected(p))
+                if matches!(key, "Title" | "UserName" | "Password" | "URL" | "Notes") =>
+            {
+                p.unsecure() == u.as_bytes()
+            }
+            _ => x == y,
+        };
+
+        a.tags == b.tags
+            && a.override_url == b.override_url
+            && a.foreground_color == b.foreground_color
+            && a.background_color == b.background_color
+            && Self::expiry(&a.times) == Self::expiry(&b.times)
+            && a.fields.len() == b.fields.len()
+            && a.fields.iter().all(|(k, v)| b.fields.get(k).is_some_and(|other| same_value(k, v, other)))
+    }
+
+    /// Previous versions of an entry, newest first.
+    pub fn entry_history(&self, uuid: &str) -> Result<Vec<Entry>> {
+        let e = Self::find_keepass_entry(&self.db.root, uuid)
+            .with_context(|| format!("Entry with UUID {} not found", uuid))?;
+        Ok(e
+            .history
+            .as_ref()
+            .map(|history| history.get_entries().iter().map(|old| self.convert_entry(old)).collect())
+            .unwrap_or_default())
+    }
+
+    /// Replace an entry's fields with one of its previous versions.
+    ///
+    /// `index` refers to the list returned by [`Self::entry_history`]. The state
+    /// being replaced is added to the history first, so a restore can be undone.
+    pub fn restore_entry_version(&mut self, uuid: &str, index: usize) -> Result<()> {
+        let e = Self::find_keepass_entry_mut(&mut self.db.root, uuid)
+            .with_context(|| format!("Entry with UUID {} not found", uuid))?;
+        let Some(old) = e.history.as_ref().and_then(|history| history.get_entries().get(index)).cloned() else {
+            anyhow::bail!("Entry {} has no version #{}", uuid, index);
+        };
+
+        let before = e.clone();
+        e.fields = old.fields;
+        e.tags = old.tags;
+        e.foreground_color = old.foreground_color;
+        e.background_color = old.background_color;
+        e.override_url = old.override_url;
+        e.autotype = old.autotype;
+        Self::set_expiry(&mut e.times, Self::expiry(&old.times));
+
+        Self::record_history(e, before, &self.db.meta);
+        Ok(())
+    }
+
+    fn find_keepass_entry<'a>(group: &'a keepass::db::Group, uuid: &str) -> Option<&'a keepass::db::Entry> {
+        group.children.iter().find_map(|node| match node {
+            keepass::db::Node::Entry(e) if e.uuid.to_string() == uuid => Some(e),
+            keepass::db::Node::Group(g) => Self::find_keepass_entry(g, uuid),
+            _ => None,
+        })
+    }
+
+    fn find_keepass_entry_mut<'a>(group: &'a mut keepass::db::Group, uuid: &str) -> Option<&'a mut keepass::db::Entry> {
+        group.children.iter_mut().find_map(|node| match node {
+            keepass::db::Node::Entry(e) if e.uuid.to_string() == uuid => Some(e),
+            keepass::db::Node::Group(g) => Self::find_keepass_entry_mut(g, uuid),
+            _ => None,
+        })
+    }
+
+    /// Log which fields an update is about to change.
+    ///
+    /// Only field names are logged, never values, so this is safe for secrets.
+    fn log_changed_fields(old: &keepass::db::Entry, new: &Entry) {
+        let standard_fields = [
+            ("Title", new.title.as_str()),
+            ("UserName", new.username.as_str()),
+            ("Password", new.password.as_str()),
+            ("URL", new.url.as_str()),
+            ("Notes", new.notes.as_str()),
+        ];
+
+        let mut changed: Vec<&str> = standard_fields
+            .iter()
+            .filter(|(key, value)| old.get(key).unwrap_or_default() != *value)
+            .map(|(key, _)| *key)
+            .collect();
+
+        let mut custom: Vec<&str> = new
+            .custom_fields
+            .iter()
+            .filter(|(key, value)| old.get(key) != Some(value.as_str()))
+            .map(|(key, _)| key.as_str())
+            .collect();
+        custom.sort_unstable();
+        changed.extend(custom);
+
+        if old.tags != new.tags {
+            changed.push("Tags");
+        }
+        if old.override_url != new.override_url {
+            changed.push("OverrideURL");
+        }
+
+        let color = |c: &Option<keepass::db::Color>| c.as_ref().map(|c| c.to_string());
+        if color(&old.foreground_color) != new.foreground_color
+            || color(&old.background_color) != new.background_color
+        {
+            changed.push("Colors");
+        }
+        if Self::expiry(&old.times) != new.expiry_time {
+            changed.push("Expiry");
+        }
+
+        // Text fields present in the database but absent from the edited model
+        let mut missing: Vec<&str> = old
+            .fields
+            .iter()
+            .filter(|(key, value)| {
+                matches!(value, keepass::db::Value::Unprotected(_) | keepass::db::Value::Protected(_))
+                    && !standard_fields.iter().any(|(k, _)| k == key)
+                    && !new.custom_fields.contains_key(*key)
+            })
+            .map(|(key, _)| key.as_str())
+            .collect();
+        missing.sort_unstable();
+
+        if changed.is_empty() && missing.is_empty() {
+            tracing::debug!("Entry {} updated without field changes", new.uuid);
+            return;
+        }
+        if !changed.is_empty() {
+            tracing::debug!("Entry {} changed fields: {}", new.uuid, changed.join(", "));
+        }
+        if !missing.is_empty() {
+            tracing::debug!("Entry {} removed fields: {}", new.uuid, missing.join(", "));
+        }
+    }
+
     /// Add a new entry to the database under a specific group.
     pub fn add_entry(&mut self, parent_group_uuid: &str, entry: &Entry) -> Result<String> {
         let mut new_entry = keepass::db::Entry::new();
@@ -251,9 +1213,15 @@ impl KeepassDatabase {
         new_entry.fields.insert("Notes".to_string(), keepass::db::Value::Unprotected(entry.notes.clone()));
         
         for (k, v) in &entry.custom_fields {
-             new_entry.fields.insert(k.clone(), keepass::db::Value::Unprotected(v.clone()));
+             new_entry.fields.insert(k.clone(), Self::custom_field_value(entry, k, v));
         }
 
+        new_entry.tags = entry.tags.clone();
+        new_entry.override_url = entry.override_url.clone();
+        new_entry.foreground_color = Self::parse_color(entry.foreground_color.as_deref());
+        new_entry.background_color = Self::parse_color(entry.background_color.as_deref());
+        Self::set_expiry(&mut new_entry.times, entry.expiry_time);
+
         let uuid = new_entry.uuid.to_string();
 
         if Self::add_node_recursive(&mut self.db.root, parent_group_uuid, keepass::db::Node::Entry(new_entry)) {
@@ -263,9 +1231,78 @@ impl KeepassDatabase {
         }
     }
 
+    /// Copy an entry into the same group under a new UUID, with " (copy)"
+    /// appended to its title.
+    ///
+    /// Custom fields, attachments, tags and expiry are copied; the history is
+    /// not. Returns the UUID of the copy.
+    pub fn duplicate_entry(&mut self, uuid: &str) -> Result<String> {
+        let original = Self::find_keepass_entry(&self.db.root, uuid)
+            .with_context(|| format!("Entry with UUID {} not found", uuid))?;
+        if self.is_inside_recycle_bin(uuid) {
+            anyhow::bail!("Restore the entry from the Recycle Bin before duplicating it");
+        }
+        let parent_uuid = Self::find_parent_of_node(&self.db.root, uuid)
+            .map(|parent| parent.uuid.to_string())
+            .with_context(|| format!("Parent group of entry {} not found", uuid))?;
+
+        let fresh = keepass::db::Entry::new();
+        let mut copy = keepass::db::Entry {
+            uuid: fresh.uuid,
+            times: fresh.times,
+            history: None,
+            ..original.clone()
+        };
+        Self::set_expiry(&mut copy.times, Self::expiry(&original.times));
+        let title = format!("{} (copy)", original.get_title().unwrap_or_default());
+        copy.fields.insert("Title".to_string(), keepass::db::Value::Unprotected(title));
+
+        let new_uuid = copy.uuid.to_string();
+        if Self::add_node_recursive(&mut self.db.root, &parent_uuid, keepass::db::Node::Entry(copy)) {
+            Ok(new_uuid)
+        } else {
+            anyhow::bail!("Parent group with UUID {} not found", parent_uuid)
+        }
+    }
+
+    /// A custom field value, protected if the entry marks it so.
+    fn custom_field_value(entry: &Entry, key: &str, value: &str) -> keepass::db::Value {
+        if entry.protected_fields.contains(key) {
+            keepass::db::Value::Protected(value.as_bytes().into())
+        } else {
+            keepass::db::Value::Unprotected(value.to_string())
+        }
+    }
+
+    /// The expiry time, if the item is set to expire.
+    fn expiry(times: &keepass::db::Times) -> Option<chrono::NaiveDateTime> {
+        times.expires.then(|| times.get_expiry().copied()).flatten()
+    }
+
+    /// Set (or with `None`, turn off) the expiry time.
+    fn set_expiry(times: &mut keepass::db::Times, expiry: Option<chrono::NaiveDateTime>) {
+        times.expires = expiry.is_some();
+        if let Some(expiry) = expiry {
+            times.set_expiry(expiry);
+        }
+    }
+
+    /// Parse a `#rrggbb` color from the model, dropping invalid values.
+    fn parse_color(color: Option<&str>) -> Option<keepass::db::Color> {
+        let color = color?;
+        match color.parse() {
+            Ok(c) => Some(c),
+            Err(_) => {
+                tracing::warn!("Ignoring invalid entry color: {}", color);
+                None
+            }
+        }
+    }
+
     /// Add a new group to the database under a specific group.
     pub fn add_group(&mut self, parent_group_uuid: &str, group: &Group) -> Result<String> {
-        let new_group = keepass::db::Group::new(&group.name);
+        let mut new_group = keepass::db::Group::new(&group.name);
+        Self::set_group_password_policy(&mut new_group, group.password_policy.as_deref())?;
         let uuid = new_group.uuid.to_string();
         
         if Self::add_node_recursive(&mut self.db.root, parent_group_uuid, keepass::db::Node::Group(new_group)) {
@@ -275,6 +1312,59 @@ impl KeepassDatabase {
         }
     }
 
+    /// Update a group's name and default password policy.
+    pub fn update_group(&mut self, group: &Group) -> Result<()> {
+        let target = self.find_group_mut(&group.uuid)?;
+        Self::set_group_password_policy(target, group.password_policy.as_deref())?;
+        target.name = group.name.clone();
+        target.times.set_last_modification(keepass::db::Times::now());
+        Ok(())
+    }
+
+    /// Rename a group, keeping its UUID and everything in it.
+    pub fn rename_group(&mut self, uuid: &str, new_name: &str) -> Result<()> {
+        if new_name.trim().is_empty() {
+            anyhow::bail!("Group name can't be empty");
+        }
+        let target = self.find_group_mut(uuid)?;
+        target.name = new_name.to_string();
+        target.times.set_last_modification(keepass::db::Times::now());
+        Ok(())
+    }
+
+    /// The group with the given UUID, including the root.
+    fn find_group_mut(&mut self, uuid: &str) -> Result<&mut keepass::db::Group> {
+        if self.db.root.uuid.to_string() == uuid {
+            return Ok(&mut self.db.root);
+        }
+        match Self::find_node_recursive_mut(&mut self.db.root, uuid) {
+            Some(keepass::db::Node::Group(g)) => Ok(g),
+            _ => anyhow::bail!("Group with UUID {} not found", uuid),
+        }
+    }
+
+    /// Store (or with `None`, remove) a group's default password policy.
+    fn set_group_password_policy(group: &mut keepass::db::Group, policy: Option<&str>) -> Result<()> {
+        match policy.map(str::trim).filter(|p| !p.is_empty()) {
+            Some(policy) => {
+                policy
+                    .parse::<crate::password_generator::PasswordPolicy>()
+                    .with_context(|| format!("Invalid password policy '{}'", policy))?;
+                group.custom_data.items.insert(
+                    PASSWORD_POLICY_FIELD.to_string(),
+                    keepass::db::CustomDataItem {
+                        value: Some(keepass::db::Value::Unprotected(policy.to_string())),
+                        last_modification_time: Some(keepass::db::Times::now()),
+                    },
+                );
+            }
+            None => {
+                group.custom_data.items.remove(PASSWORD_POLICY_FIELD);
+            }
+        }
+        Ok(())
+    }
+
     pub fn get_recycle_bin_uuid(&self) -> Option<String> {
         self.db.meta.recyclebin_uuid.as_ref().map(|u| u.to_string())
     }
@@ -367,22 +1457,49 @@ impl KeepassDatabase {
             anyhow::bail!("Cannot delete the Recycle Bin itself");
         }
         
-        // 1. Find parent first to track original location (for Entries only)
-        // Groups don't support custom fields easily so we skip tracking for them.
-        let mut parent_uuid = None;
-        if !is_group {
-            if let Some(parent) = Self::find_parent_of_node(&self.db.root, uuid) {
-                parent_uuid = Some(parent.uuid.to_string());
-            }
-        }
+        // 1. Find parent first to track original location
+        let parent_uuid = Self::find_parent_of_node(&self.db.root, uuid).map(|parent| parent.uuid.to_string());
 
         // 2. Remove the node
         if let Some(mut node) = Self::delete_node_recursive(&mut self.db.root, uuid, is_group) {
-             // 3. Tag with original parent if it's an entry
+             // 3. Tag with original parent (a custom data item for groups)
              if let Some(p_uuid) = parent_uuid {
-                 if let keepass::db::Node::Entry(ref mut e) = node {
-                     e.fields.insert("keeprs_original_parent_uuid".to_string(), 
-                         keepass::db::Value::Unprotected(p_uuid));
+                 match node {
+                     keepass::db::Node::Entry(ref mut e) => {
+                         e.fields.insert(ORIGINAL_PARENT_FIELD.to_string(),
+                             keepass::db::Value::Unprotected(p_uuid));
+                     }
+                     keepass::db::Node::Group(ref mut g) => {
+                         g.custom_data.items.insert(
+                             ORIGINAL_PARENT_FIELD.to_string(),
+                             keepass::db::CustomDataItem {
+                                 value: Some(keepass::db::Value::Unprotected(p_uuid)),
+                                 last_modification_time: Some(keepass::db::Times::now()),
+                             },
+                         );
+                     }
+                 }
+             }
+
+             // Stamp the deletion time. KeePass uses LocationChanged for this; our own
+             // copy tells items we binned apart from ones other clients moved here.
+             let now = keepass::db::Times::now();
+             let deleted_at = now.format(DELETED_AT_FORMAT).to_string();
+             match node {
+                 keepass::db::Node::Entry(ref mut e) => {
+                     e.times.set_location_changed(now);
+                     e.fields.insert(DELETED_AT_FIELD.to_string(),
+                         keepass::db::Value::Unprotected(deleted_at));
+                 }
+                 keepass::db::Node::Group(ref mut g) => {
+                     g.times.set_location_changed(now);
+                     g.custom_data.items.insert(
+                         DELETED_AT_FIELD.to_string(),
+                         keepass::db::CustomDataItem {
+                             value: Some(keepass::db::Value::Unprotected(deleted_at)),
+                             last_modification_time: Some(now),
+                         },
+                     );
                  }
              }
 
@@ -432,6 +1549,53 @@ impl KeepassDatabase {
         Ok(())
     }
     
+    /// Permanently remove recycle bin items deleted more than `days` days ago.
+    ///
+    /// Only items keeprs stamped with [`DELETED_AT_FIELD`] when recycling them are
+    /// considered: `LocationChanged` on items binned by other clients (or older
+    /// keeprs builds) may be a creation or move time instead. Returns the number
+    /// of items purged.
+    pub fn purge_recycle_bin_older_than(&mut self, days: u64) -> Result<usize> {
+        let Some(bin_uuid) = self.db.meta.recyclebin_uuid.map(|u| u.to_string()) else {
+            return Ok(0);
+        };
+
+        let max_age = chrono::Duration::try_days(i64::try_from(days)?)
+            .with_context(|| format!("Purge age of {} days is out of range", days))?;
+        let Some(cutoff) = keepass::db::Times::now().checked_sub_signed(max_age) else {
+            return Ok(0);
+        };
+
+        let Some(keepass::db::Node::Group(bin)) = Self::find_node_recursive_mut(&mut self.db.root, &bin_uuid) else {
+            return Ok(0);
+        };
+
+        let before = bin.children.len();
+        bin.children.retain(|node| {
+            Self::deleted_at(node).is_none_or(|t| t > cutoff)
+        });
+        let purged = before - bin.children.len();
+
+        if purged > 0 {
+            tracing::info!("Purged {} item(s) older than {} days from the recycle bin", purged, days);
+        }
+        Ok(purged)
+    }
+
+    /// When keeprs moved a node to the recycle bin, if it recorded that.
+    fn deleted_at(node: &keepass::db::Node) -> Option<chrono::NaiveDateTime> {
+        let value = match node {
+            keepass::db::Node::Entry(e) => e.fields.get(DELETED_AT_FIELD),
+            keepass::db::Node::Group(g) => g.custom_data.items.get(DELETED_AT_FIELD).and_then(|item| item.value.as_ref()),
+        };
+        match value {
+            Some(keepass::db::Value::Unprotected(text)) => {
+                chrono::NaiveDateTime::parse_from_str(text, DELETED_AT_FORMAT).ok()
+            }
+            _ => None,
+        }
+    }
+
     // Improved helper to find ANY node by UUID to allow manipulation
     fn find_node_recursive_mut<'a>(group: &'a mut keepass::db::Group, target_uuid: &str) -> Option<&'a mut keepass::db::Node> {
          for node in &mut group.children {
@@ -472,43 +1636,331 @@ impl KeepassDatabase {
 
 
 
+    /// Move an entry out of the recycle bin, back into the group it was
+    /// deleted from (or the root group if that is gone).
     pub fn restore_entry(&mut self, uuid: &str) -> Result<()> {
-        let recycle_bin_uuid = self.get_recycle_bin_uuid().context("Recycle Bin not found")?;
-        
-        // 1. Locate the entry to find original parent
-        let mut original_parent_uuid = None;
-        if let Some(entry) = self.find_entry(uuid) {
-            if let Some(p_uuid) = entry.custom_fields.get("keeprs_original_parent_uuid") {
-                 original_parent_uuid = Some(p_uuid.clone());
+        let Some(entry) = self.find_entry(uuid) else {
+            anyhow::bail!("Entry with UUID {} not found", uuid);
+        };
+        if !self.is_inside_recycle_bin(uuid) {
+            anyhow::bail!("Entry '{}' is not in the Recycle Bin", entry.title);
+        }
+        let target_parent_uuid = self.restore_target(entry.original_parent_uuid());
+
+        let Some(mut node) = Self::delete_node_recursive(&mut self.db.root, uuid, false) else {
+            anyhow::bail!("Failed to remove node from its current location during restore");
+        };
+        if let keepass::db::Node::Entry(ref mut e) = node {
+            e.fields.remove(ORIGINAL_PARENT_FIELD);
+            e.fields.remove(DELETED_AT_FIELD);
+            e.times.set_location_changed(keepass::db::Times::now());
+        }
+
+        if Self::add_node_recursive(&mut self.db.root, &target_parent_uuid, node) {
+            Ok(())
+        } else {
+            anyhow::bail!("Failed to restore node to parent group {}", target_parent_uuid)
+        }
+    }
+
+    /// Move a group, with everything in it, out of the recycle bin and back
+    /// into the group it was deleted from (or the root group if that is gone).
+    pub fn restore_group(&mut self, uuid: &str) -> Result<()> {
+        if self.is_recycle_bin(uuid) {
+            anyhow::bail!("Cannot restore the Recycle Bin itself");
+        }
+        let Some(keepass::db::Node::Group(group)) = Self::find_node_recursive_mut(&mut self.db.root, uuid) else {
+            anyhow::bail!("Group with UUID {} not found", uuid);
+        };
+        let name = group.name.clone();
+        let original_parent = match group.custom_data.items.get(ORIGINAL_PARENT_FIELD).and_then(|item| item.value.as_ref()) {
+            Some(keepass::db::Value::Unprotected(parent)) => Some(parent.clone()),
+            _ => None,
+        };
+        if !self.is_inside_recycle_bin(uuid) {
+            anyhow::bail!("Group '{}' is not in the Recycle Bin", name);
+        }
+        let target_parent_uuid = self.restore_target(original_parent.as_deref());
+
+        let Some(mut node) = Self::delete_node_recursive(&mut self.db.root, uuid, true) else {
+            anyhow::bail!("Failed to remove group '{}' from the Recycle Bin", name);
+        };
+        if let keepass::db::Node::Group(ref mut g) = node {
+            g.custom_data.items.remove(ORIGINAL_PARENT_FIELD);
+            g.custom_data.items.remove(DELETED_AT_FIELD);
+            g.times.set_location_changed(keepass::db::Times::now());
+        }
+
+        if Self::add_node_recursive(&mut self.db.root, &target_parent_uuid, node) {
+            Ok(())
+        } else {
+            anyhow::bail!("Failed to restore group '{}' to parent group {}", name, target_parent_uuid)
+        }
+    }
+
+    /// Group a node restored from the recycle bin goes to: its original parent
+    /// if that still exists outside the bin, the root group otherwise.
+    fn restore_target(&self, original_parent: Option<&str>) -> String {
+        match original_parent {
+            Some(parent) if Self::contains_node(&self.db.root, parent) && !self.is_inside_recycle_bin(parent) => {
+                parent.to_string()
             }
+            _ => self.db.root.uuid.to_string(),
+        }
+    }
+
+    /// Restore every entry directly inside the recycle bin.
+    ///
+    /// Returns the number of restored entries.
+    pub fn restore_all_entries(&mut self) -> Result<usize> {
+        let Some(bin_uuid) = self.get_recycle_bin_uuid() else {
+            return Ok(0);
+        };
+        let Some(keepass::db::Node::Group(bin)) = Self::find_node_recursive_mut(&mut self.db.root, &bin_uuid) else {
+            anyhow::bail!("Recycle Bin not found");
+        };
+
+        let uuids: Vec<String> = bin
+            .children
+            .iter()
+            .filter_map(|node| match node {
+                keepass::db::Node::Entry(e) => Some(e.uuid.to_string()),
+                keepass::db::Node::Group(_) => None,
+            })
+            .collect();
+
+        for uuid in &uuids {
+            self.restore_entry(uuid)?;
+        }
+        Ok(uuids.len())
+    }
+
+    /// Passwords shared by more than one entry.
+    ///
+    /// Each item is the SHA-256 (hex) of a reused password with the UUIDs of
+    /// the entries using it, largest groups first. Only hashes are returned so
+    /// the result is safe to log. Empty passwords, `{REF:...}` references and
+    /// entries in the recycle bin are ignored.
+    pub fn reused_passwords(&self) -> Vec<(String, Vec<String>)> {
+        use sha2::{Digest, Sha256};
+
+        let mut by_hash: std::collections::HashMap<String, Vec<String>> = std::collections::HashMap::new();
+        for entry in self.root_group().all_entries() {
+            if entry.password.is_empty() || entry.password.to_ascii_uppercase().contains("{REF:") {
+                continue;
+            }
+            let hash = format!("{:x}", Sha256::digest(entry.password.as_bytes()));
+            by_hash.entry(hash).or_default().push(entry.uuid.clone());
+        }
+
+        let mut reused: Vec<(String, Vec<String>)> = by_hash.into_iter().filter(|(_, uuids)| uuids.len() > 1).collect();
+        reused.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+        tracing::info!("Found {} reused password(s)", reused.len());
+        reused
+    }
+
+    /// UUIDs of entries whose password zxcvbn scores below `min_score` (0–4).
+    ///
+    /// Empty passwords, `{REF:...}` references and entries in the recycle bin
+    /// are ignored. Scoring runs locally; nothing is sent over the network.
+    pub fn weak_entries(&self, min_score: u8) -> Vec<String> {
+        let weak: Vec<String> = self
+            .root_group()
+            .all_entries()
+            .into_iter()
+            .filter(|entry| !entry.password.is_empty() && !entry.password.to_ascii_uppercase().contains("{REF:"))
+            .filter(|entry| u8::from(zxcvbn::zxcvbn(&entry.password, &[]).score()) < min_score)
+            .map(|entry| entry.uuid.clone())
+            .collect();
+        tracing::info!("Found {} weak password(s)", weak.len());
+        weak
+    }
+
+    /// UUIDs of entries not modified in the last `max_age_days` days.
+    ///
+    /// Entries without a modification time and entries in the recycle bin are
+    /// ignored.
+    pub fn stale_entries(&self, max_age_days: u64) -> Vec<String> {
+        let Some(cutoff) = i64::try_from(max_age_days)
+            .ok()
+            .and_then(chrono::Duration::try_days)
+            .and_then(|max_age| keepass::db::Times::now().checked_sub_signed(max_age))
+        else {
+            return Vec::new();
+        };
+
+        let stale: Vec<String> = self
+            .root_group()
+            .all_entries()
+            .into_iter()
+            .filter(|entry| entry.last_modified.is_some_and(|modified| modified < cutoff))
+            .map(|entry| entry.uuid.clone())
+            .collect();
+        tracing::info!("Found {} password(s) older than {} days", stale.len(), max_age_days);
+        stale
+    }
+
+    /// Scan the database for structural problems.
+    ///
+    /// Reports duplicate UUIDs, groups nested inside themselves, broken or
+    /// unused attachment references and a dangling recycle bin reference.
+    pub fn check_integrity(&self) -> Vec<IntegrityIssue> {
+        let mut issues = Vec::new();
+        let mut uuid_counts = std::collections::HashMap::new();
+        let mut used_attachments = vec![false; self.db.header_attachments.len()];
+        Self::scan_group(&self.db.root, &mut Vec::new(), &mut uuid_counts, &mut used_attachments, &mut issues);
+
+        if let Some(bin_uuid) = self.db.meta.recyclebin_uuid.map(|u| u.to_string()) {
+            if !uuid_counts.contains_key(&bin_uuid) {
+                issues.push(IntegrityIssue::MissingRecycleBin { uuid: bin_uuid });
+            }
+        }
+
+        let mut duplicates: Vec<(String, usize)> = uuid_counts.into_iter().filter(|(_, count)| *count > 1).collect();
+        duplicates.sort();
+        issues.extend(duplicates.into_iter().map(|(uuid, count)| IntegrityIssue::DuplicateUuid { uuid, count }));
+
+        issues.extend(
+            used_attachments
+                .iter()
+                .enumerate()
+                .filter(|(_, used)| !**used)
+                .map(|(index, _)| IntegrityIssue::UnusedAttachment { index }),
+        );
+
+        if issues.is_empty() {
+            tracing::info!("Integrity check found no problems");
         } else {
-             anyhow::bail!("Entry with UUID {} not found", uuid);
+            tracing::warn!("Integrity check found {} problem(s)", issues.len());
         }
+        issues
+    }
 
-        let target_parent_uuid = if let Some(p_uuid) = original_parent_uuid {
-             // Check if parent still exists
-             if Self::find_node_recursive_mut(&mut self.db.root, &p_uuid).is_some() {
-                 p_uuid
-             } else {
-                 self.db.root.uuid.to_string()
-             }
+    fn scan_group(
+        group: &keepass::db::Group,
+        ancestors: &mut Vec<String>,
+        uuid_counts: &mut std::collections::HashMap<String, usize>,
+        used_attachments: &mut [bool],
+        issues: &mut Vec<IntegrityIssue>,
+    ) {
+        let uuid = group.uuid.to_string();
+        if ancestors.contains(&uuid) {
+            issues.push(IntegrityIssue::GroupIsOwnAncestor { uuid, name: group.name.clone() });
+            return;
+        }
+        *uuid_counts.entry(uuid.clone()).or_default() += 1;
+
+        ancestors.push(uuid);
+        for node in &group.children {
+            match node {
+                keepass::db::Node::Group(g) => Self::scan_group(g, ancestors, uuid_counts, used_attachments, issues),
+                keepass::db::Node::Entry(e) => {
+                    let entry_uuid = e.uuid.to_string();
+                    *uuid_counts.entry(entry_uuid.clone()).or_default() += 1;
+                    Self::scan_attachment_refs(e, &entry_uuid, used_attachments, issues);
+
+                    // History versions keep their own references to the shared binaries
+                    if let Some(ref history) = e.history {
+                        for old in history.get_entries() {
+                            Self::scan_attachment_refs(old, &entry_uuid, used_attachments, issues);
+                        }
+                    }
+                }
+            }
+        }
+        ancestors.pop();
+    }
+
+    fn scan_attachment_refs(
+        entry: &keepass::db::Entry,
+        entry_uuid: &str,
+        used_attachments: &mut [bool],
+        issues: &mut Vec<IntegrityIssue>,
+    ) {
+        for (field, value) in &entry.fields {
+            let keepass::db::Value::BinaryRef(reference) = value else {
+                continue;
+            };
+            let issue = match reference.parse::<usize>() {
+                Ok(index) if index < used_attachments.len() => {
+                    used_attachments[index] = true;
+                    continue;
+                }
+                Ok(index) => IntegrityIssue::MissingAttachment {
+                    entry_uuid: entry_uuid.to_string(),
+                    field: field.clone(),
+                    index,
+                },
+                Err(_) => IntegrityIssue::InvalidAttachmentRef {
+                    entry_uuid: entry_uuid.to_string(),
+                    field: field.clone(),
+                    reference: reference.clone(),
+                },
+            };
+            if !issues.contains(&issue) {
+                issues.push(issue);
+            }
+        }
+    }
+
+    /// Move an entry to position `new_index` among the entries of its group.
+    ///
+    /// Indices count entries only, so subgroups keep their places. An index
+    /// past the end moves the entry to the end.
+    pub fn reorder_entry(&mut self, group_uuid: &str, entry_uuid: &str, new_index: usize) -> Result<()> {
+        let group = self.find_group_mut(group_uuid)?;
+        let slots: Vec<usize> = group
+            .children
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| matches!(node, keepass::db::Node::Entry(_)))
+            .map(|(i, _)| i)
+            .collect();
+        let Some(current) = slots.iter().position(|&slot| {
+            matches!(&group.children[slot], keepass::db::Node::Entry(e) if e.uuid.to_string() == entry_uuid)
+        }) else {
+            anyhow::bail!("Entry {} is not in group {}", entry_uuid, group_uuid);
+        };
+
+        // Bubble the entry along the entry slots, leaving groups in place
+        let target = new_index.min(slots.len() - 1);
+        if target > current {
+            for i in current..target {
+                group.children.swap(slots[i], slots[i + 1]);
+            }
         } else {
-             self.db.root.uuid.to_string()
+            for i in (target..current).rev() {
+                group.children.swap(slots[i], slots[i + 1]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Move an entry into another group.
+    ///
+    /// The entry keeps its UUID, fields, attachments and history. Fails if
+    /// either UUID doesn't exist; nothing changes in that case.
+    pub fn move_entry(&mut self, uuid: &str, target_group_uuid: &str) -> Result<()> {
+        let target_exists = self.db.root.uuid.to_string() == target_group_uuid
+            || matches!(
+                Self::find_node_recursive_mut(&mut self.db.root, target_group_uuid),
+                Some(keepass::db::Node::Group(_))
+            );
+        if !target_exists {
+            anyhow::bail!("Group with UUID {} not found", target_group_uuid);
+        }
+
+        let Some(mut node) = Self::delete_node_recursive(&mut self.db.root, uuid, false) else {
+            anyhow::bail!("Entry with UUID {} not found", uuid);
         };
+        if let keepass::db::Node::Entry(ref mut e) = node {
+            e.times.set_location_changed(keepass::db::Times::now());
+        }
 
-        // 2. Remove from bin and restore
-        if let Some(mut node) = Self::delete_node_recursive(&mut self.db.root, uuid, false) {
-             if let keepass::db::Node::Entry(ref mut e) = node {
-                 e.fields.remove("keeprs_original_parent_uuid");
-             }
-             
-             if Self::add_node_recursive(&mut self.db.root, &target_parent_uuid, node) {
-                 Ok(())
-             } else {
-                 anyhow::bail!("Failed to restore node to parent group {}", target_parent_uuid)
-             }
+        if Self::add_node_recursive(&mut self.db.root, target_group_uuid, node) {
+            Ok(())
         } else {
-             anyhow::bail!("Failed to remove node from its current location during restore")
+            anyhow::bail!("Failed to move entry to group {}", target_group_uuid)
         }
     }
 
@@ -570,3 +2022,133 @@ impl KeepassDatabase {
          None
     }
 }
+
+/// Scrub decrypted data when the database is dropped (e.g. on lock).
+///
+/// Protected values (passwords) and the `DatabaseKey` zeroize themselves; this
+/// clears the plain copies: key file contents, unprotected and binary field
+/// values of entries and their history versions, and attachment contents.
+impl Drop for KeepassDatabase {
+    fn drop(&mut self) {
+        self.keyfile.zeroize();
+        Self::scrub_group(&mut self.db.root);
+        for attachment in &mut self.db.header_attachments {
+            attachment.content.zeroize();
+        }
+    }
+}
+
+impl KeepassDatabase {
+    fn scrub_group(group: &mut keepass::db::Group) {
+        for node in &mut group.children {
+            match node {
+                keepass::db::Node::Group(g) => Self::scrub_group(g),
+                keepass::db::Node::Entry(e) => Self::scrub_entry(e),
+            }
+        }
+    }
+
+    fn scrub_entry(e: &mut keepass::db::Entry) {
+        for value in e.fields.values_mut() {
+            match value {
+                keepass::db::Value::Unprotected(text) => text.zeroize(),
+                keepass::db::Value::Bytes(bytes) => bytes.zeroize(),
+                keepass::db::Value::Protected(_) | keepass::db::Value::BinaryRef(_) => {}
+            }
+        }
+        if let Some(ref mut history) = e.history {
+            for old in history.get_entries_mut() {
+                Self::scrub_entry(old);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An in-memory database with a single entry, and that entry's UUID.
+    fn database_with_entry() -> (KeepassDatabase, String) {
+        let mut db = Database::new(Default::default());
+        let mut entry = keepass::db::Entry::new();
+        entry.fields.insert("Title".to_string(), keepass::db::Value::Unprotected("Mail".to_string()));
+        let uuid = entry.uuid.to_string();
+        db.root.children.push(keepass::db::Node::Entry(entry));
+
+        let db = KeepassDatabase {
+            db,
+            path: "test.kdbx".into(),
+            key: DatabaseKey::new().with_password("test"),
+            keyfile: None,
+            max_attachment_bytes: Some(DEFAULT_MAX_ATTACHMENT_BYTES),
+            read_only: false,
+            backup_count: 0,
+        };
+        (db, uuid)
+    }
+
+    fn edit_notes(db: &mut KeepassDatabase, uuid: &str, notes: String) {
+        let mut entry = db.find_entry(uuid).unwrap();
+        entry.notes = notes;
+        db.update_entry(&entry).unwrap();
+    }
+
+    #[test]
+    fn history_keeps_the_newest_versions_up_to_max_items() {
+        let (mut db, uuid) = database_with_entry();
+        db.db.meta.history_max_items = Some(3);
+        for i in 0..5 {
+            edit_notes(&mut db, &uuid, format!("version {}", i));
+        }
+
+        let history = db.entry_history(&uuid).unwrap();
+        let notes: Vec<&str> = history.iter().map(|old| old.notes.as_str()).collect();
+        assert_eq!(notes, ["version 3", "version 2", "version 1"]);
+    }
+
+    #[test]
+    fn history_defaults_to_ten_versions() {
+        let (mut db, uuid) = database_with_entry();
+        db.db.meta.history_max_items = None;
+        for i in 0..15 {
+            edit_notes(&mut db, &uuid, format!("version {}", i));
+        }
+
+        let history = db.entry_history(&uuid).unwrap();
+        assert_eq!(history.len(), DEFAULT_HISTORY_MAX_ITEMS);
+        assert_eq!(history[0].notes, "version 13");
+    }
+
+    #[test]
+    fn history_drops_versions_beyond_max_size() {
+        let (mut db, uuid) = database_with_entry();
+        db.db.meta.history_max_size = Some(1500);
+        for i in 0..3 {
+            edit_notes(&mut db, &uuid, format!("{}{}", "x".repeat(1000), i));
+        }
+
+        let history = db.entry_history(&uuid).unwrap();
+        assert_eq!(history.len(), 1);
+        assert!(history[0].notes.ends_with('1'));
+    }
+
+    #[test]
+    fn protecting_a_custom_field_is_a_new_version() {
+        let (mut db, uuid) = database_with_entry();
+        let mut entry = db.find_entry(&uuid).unwrap();
+        entry.custom_fields.insert("PIN".to_string(), "1234".to_string());
+        db.update_entry(&entry).unwrap();
+        assert_eq!(db.entry_history(&uuid).unwrap().len(), 1);
+
+        let mut entry = db.find_entry(&uuid).unwrap();
+        entry.protected_fields.insert("PIN".to_string());
+        db.update_entry(&entry).unwrap();
+        assert_eq!(db.entry_history(&uuid).unwrap().len(), 2);
+
+        // Saving again without changes adds nothing
+        let entry = db.find_entry(&uuid).unwrap();
+        db.update_entry(&entry).unwrap();
+        assert_eq!(db.entry_history(&uuid).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,519 @@
+//! Export and import of entries in portable formats (CSV and JSON).
+//!
+//! The JSON format mirrors the group tree so it can be imported back,
+//! with attachments base64-encoded. CSV is flat, one row per entry, and
+//! carries the group path in the first column.
+
+use crate::models::{Attachment, Entry, Group};
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Column names of the CSV export.
+pub const CSV_HEADER: [&str; 8] = ["Group", "Title", "Username", "Password", "URL", "Notes", "TOTP", "Tags"];
+
+/// Output format for exports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// Pick the format from a file extension, defaulting to JSON.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => ExportFormat::Csv,
+            _ => ExportFormat::Json,
+        }
+    }
+
+    /// Render an export tree in this format.
+    pub fn render(self, tree: &ExportGroup) -> Result<String> {
+        match self {
+            ExportFormat::Json => serde_json::to_string_pretty(tree).context("Failed to serialize export"),
+            ExportFormat::Csv => Ok(render_csv(tree)),
+        }
+    }
+}
+
+/// A group in the JSON export tree.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportGroup {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub groups: Vec<ExportGroup>,
+    #[serde(default)]
+    pub entries: Vec<ExportEntry>,
+}
+
+/// An entry in the JSON export tree.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub override_url: Option<String>,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub custom_fields: BTreeMap<String, String>,
+    /// Custom fields KeePass keeps in memory-protected form.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub protected_fields: BTreeSet<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub otp: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub attachments: Vec<ExportAttachment>,
+}
+
+/// An attachment in the JSON export, with base64-encoded content.
+///
+/// Metadata-only exports leave out the content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportAttachment {
+    pub filename: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+}
+
+/// Summary of an import.
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    /// Groups that did not exist yet and were created.
+    pub groups_created: usize,
+    /// Paths (relative to the import target) of the created groups.
+    pub new_groups: Vec<String>,
+    /// Entries added to the database.
+    pub entries_imported: usize,
+    /// Records that could not be imported, with the reason.
+    pub skipped: Vec<String>,
+}
+
+impl ExportGroup {
+    /// Build an export tree from `group`, keeping only entries accepted by `keep`.
+    ///
+    /// Groups left without entries are pruned. Returns `None` if nothing is kept.
+    pub fn from_group(group: &Group, keep: &dyn Fn(&Entry) -> bool) -> Option<Self> {
+        let groups: Vec<ExportGroup> = group
+            .children
+            .iter()
+            .filter_map(|child| Self::from_group(child, keep))
+            .collect();
+        let entries: Vec<ExportEntry> = group
+            .entries
+            .iter()
+            .filter(|e| keep(e))
+            .map(ExportEntry::from)
+            .collect();
+
+        if groups.is_empty() && entries.is_empty() {
+            return None;
+        }
+
+        Some(ExportGroup {
+            uuid: Some(group.uuid.clone()),
+            name: group.name.clone(),
+            groups,
+            entries,
+        })
+    }
+
+    /// Build an import tree from KeePassXC-style CSV.
+    ///
+    /// Columns are picked by the header row (case-insensitive; see [`CSV_HEADER`],
+    /// `User Name`/`Login Name` and `OTP` are accepted too), so extra columns are
+    /// ignored. The first component of the `Group` path names the exported root
+    /// and is dropped. Malformed records are left out and described in the
+    /// returned list.
+    pub fn from_csv(text: &str) -> Result<(Self, Vec<String>)> {
+        let mut records = parse_csv_records(text).into_iter();
+        let header = match records.next() {
+            Some(CsvRecord { fields: Ok(fields), .. }) => fields,
+            _ => anyhow::bail!("The CSV file has no header row"),
+        };
+        let column = |names: &[&str]| {
+            header
+                .iter()
+                .position(|h| names.iter().any(|name| h.trim().eq_ignore_ascii_case(name)))
+        };
+        let Some(title_column) = column(&["Title"]) else {
+            anyhow::bail!("The CSV header has no Title column");
+        };
+        let group_column = column(&["Group"]);
+        let username_column = column(&["Username", "User Name", "Login Name"]);
+        let password_column = column(&["Password"]);
+        let url_column = column(&["URL"]);
+        let notes_column = column(&["Notes"]);
+        let otp_column = column(&["TOTP", "OTP"]);
+        let tags_column = column(&["Tags"]);
+
+        let mut root = ExportGroup::default();
+        let mut errors = Vec::new();
+        for record in records {
+            let fields = match record.fields {
+                Ok(fields) => fields,
+                Err(e) => {
+                    errors.push(format!("Record on line {}: {}", record.line, e));
+                    continue;
+                }
+            };
+            if fields.len() != header.len() {
+                errors.push(format!(
+                    "Record on line {}: expected {} fields, found {}",
+                    record.line,
+                    header.len(),
+                    fields.len()
+                ));
+                continue;
+            }
+
+            let field = |index: Option<usize>| index.map(|i| fields[i].clone()).unwrap_or_default();
+            let entry = ExportEntry {
+                title: fields[title_column].clone(),
+                username: field(username_column),
+                password: field(password_column),
+                url: field(url_column),
+                notes: field(notes_column),
+                otp: Some(field(otp_column)).filter(|otp| !otp.is_empty()),
+                tags: field(tags_column)
+                    .split([',', ';'])
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(String::from)
+                    .collect(),
+                ..Default::default()
+            };
+
+            let path = field(group_column);
+            let mut group = &mut root;
+            for name in path.split('/').skip(1).filter(|name| !name.is_empty()) {
+                let index = match group.groups.iter().position(|g| g.name == name) {
+                    Some(index) => index,
+                    None => {
+                        group.groups.push(ExportGroup {
+                            name: name.to_string(),
+                            ..Default::default()
+                        });
+                        group.groups.len() - 1
+                    }
+                };
+                group = &mut group.groups[index];
+            }
+            group.entries.push(entry);
+        }
+        Ok((root, errors))
+    }
+
+    /// Drop the content of all attachments, keeping their filenames.
+    pub fn strip_attachment_data(&mut self) {
+        for entry in &mut self.entries {
+            for attachment in &mut entry.attachments {
+                attachment.data = None;
+            }
+        }
+        for child in &mut self.groups {
+            child.strip_attachment_data();
+        }
+    }
+
+    /// Total number of entries in this group and its children.
+    pub fn entry_count(&self) -> usize {
+        self.entries.len() + self.groups.iter().map(ExportGroup::entry_count).sum::<usize>()
+    }
+}
+
+/// The whole tree below `group`, empty groups included.
+impl From<&Group> for ExportGroup {
+    fn from(group: &Group) -> Self {
+        ExportGroup {
+            uuid: Some(group.uuid.clone()),
+            name: group.name.clone(),
+            groups: group.children.iter().map(ExportGroup::from).collect(),
+            entries: group.entries.iter().map(ExportEntry::from).collect(),
+        }
+    }
+}
+
+impl From<&Entry> for ExportEntry {
+    fn from(entry: &Entry) -> Self {
+        ExportEntry {
+            uuid: Some(entry.uuid.clone()),
+            title: entry.title.clone(),
+            username: entry.username.clone(),
+            password: entry.password.clone(),
+            url: entry.url.clone(),
+            override_url: entry.override_url.clone(),
+            notes: entry.notes.clone(),
+            custom_fields: entry.custom_fields.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            protected_fields: entry.protected_fields.iter().cloned().collect(),
+            otp: entry.otp.clone(),
+            tags: entry.tags.clone(),
+            // Placeholders have no content to export
+            attachments: entry
+                .attachments
+                .iter()
+                .filter(|a| !a.is_missing())
+                .map(ExportAttachment::from)
+                .collect(),
+        }
+    }
+}
+
+/// Serialize an entry's fields as a JSON object, leaving out attachments.
+pub fn entry_to_json(entry: &Entry) -> Result<String> {
+    let export = ExportEntry {
+        attachments: Vec::new(),
+        ..ExportEntry::from(entry)
+    };
+    serde_json::to_string_pretty(&export).context("Failed to serialize entry")
+}
+
+/// Create (or truncate) a file for an export, readable by its owner only.
+///
+/// Exports hold passwords in plain text, so unlike `File::create` this doesn't
+/// go by the umask. An existing file gets its permissions tightened as well.
+pub fn create_export_file(path: &Path) -> Result<std::fs::File> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let file = options
+            .open(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("Failed to restrict permissions of {}", path.display()))?;
+        Ok(file)
+    }
+    #[cfg(not(unix))]
+    {
+        options
+            .open(path)
+            .with_context(|| format!("Failed to create {}", path.display()))
+    }
+}
+
+impl From<&Attachment> for ExportAttachment {
+    fn from(attachment: &Attachment) -> Self {
+        ExportAttachment {
+            filename: attachment.filename.clone(),
+            data: Some(base64::engine::general_purpose::STANDARD.encode(&attachment.data)),
+        }
+    }
+}
+
+impl ExportAttachment {
+    /// Decode the attachment content.
+    pub fn decode(&self) -> Result<Vec<u8>> {
+        let Some(ref data) = self.data else {
+            anyhow::bail!("Attachment '{}' was exported without its content", self.filename);
+        };
+        base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .with_context(|| format!("Invalid base64 data for attachment '{}'", self.filename))
+    }
+}
+
+fn render_csv(tree: &ExportGroup) -> String {
+    let mut out = csv_row(CSV_HEADER.iter().copied());
+    write_csv_group(tree, &tree.name, &mut out);
+    out
+}
+
+fn write_csv_group(group: &ExportGroup, path: &str, out: &mut String) {
+    for entry in &group.entries {
+        let tags = entry.tags.join(",");
+        out.push_str(&csv_row([
+            path,
+            entry.title.as_str(),
+            entry.username.as_str(),
+            entry.password.as_str(),
+            entry.url.as_str(),
+            entry.notes.as_str(),
+            entry.otp.as_deref().unwrap_or_default(),
+            tags.as_str(),
+        ]));
+    }
+    for child in &group.groups {
+        write_csv_group(child, &format!("{}/{}", path, child.name), out);
+    }
+}
+
+/// A CSV record and the line it starts on.
+struct CsvRecord {
+    line: usize,
+    fields: Result<Vec<String>, String>,
+}
+
+/// Split CSV text into records (RFC 4180: quoted fields may hold commas,
+/// doubled quotes and line breaks). Blank lines are skipped.
+fn parse_csv_records(text: &str) -> Vec<CsvRecord> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut start_line = 1;
+
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => fields.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                if fields.len() > 1 || !fields[0].is_empty() {
+                    records.push(CsvRecord { line: start_line, fields: Ok(std::mem::take(&mut fields)) });
+                }
+                fields.clear();
+                line += 1;
+                start_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        records.push(CsvRecord { line: start_line, fields: Err("unterminated quoted field".to_string()) });
+    } else if !fields.is_empty() || !field.is_empty() {
+        fields.push(field);
+        records.push(CsvRecord { line: start_line, fields: Ok(fields) });
+    }
+    records
+}
+
+/// Format one CSV record (RFC 4180 quoting, CRLF line ending).
+fn csv_row<'a>(fields: impl IntoIterator<Item = &'a str>) -> String {
+    let mut row = fields
+        .into_iter()
+        .map(|f| {
+            if f.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    row.push_str("\r\n");
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted_fields_keep_commas_quotes_and_line_breaks() {
+        let text = "Title,Username,Notes\r\n\"Mail, work\",\"say \"\"hi\"\"\",\"line one\r\nline two\"\r\n";
+        let (tree, errors) = ExportGroup::from_csv(text).unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+
+        let entry = &tree.entries[0];
+        assert_eq!(entry.title, "Mail, work");
+        assert_eq!(entry.username, "say \"hi\"");
+        assert_eq!(entry.notes, "line one\r\nline two");
+    }
+
+    #[test]
+    fn columns_are_picked_by_header() {
+        let text = "Notes,Extra,login name,TITLE,Group,Tags\n\
+                    note,ignored,alice,Mail,Root/Work/Mail,\"a, b;c\"\n";
+        let (tree, errors) = ExportGroup::from_csv(text).unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+
+        // The first path component is the exported root
+        assert_eq!(tree.groups[0].name, "Work");
+        let entry = &tree.groups[0].groups[0].entries[0];
+        assert_eq!(entry.title, "Mail");
+        assert_eq!(entry.username, "alice");
+        assert_eq!(entry.notes, "note");
+        assert_eq!(entry.password, "");
+        assert_eq!(entry.tags, ["a", "b", "c"]);
+        assert!(entry.otp.is_none());
+    }
+
+    #[test]
+    fn header_without_title_is_refused() {
+        assert!(ExportGroup::from_csv("Username,Password\nalice,secret\n").is_err());
+        assert!(ExportGroup::from_csv("").is_err());
+    }
+
+    #[test]
+    fn malformed_records_are_skipped_and_reported() {
+        let text = "Title,Username\nGood,alice\nToo,many,fields\n\nAlso good,bob\n\"Unterminated,carol\n";
+        let (tree, errors) = ExportGroup::from_csv(text).unwrap();
+
+        let titles: Vec<&str> = tree.entries.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, ["Good", "Also good"]);
+        assert_eq!(
+            errors,
+            [
+                "Record on line 3: expected 2 fields, found 3",
+                "Record on line 6: unterminated quoted field",
+            ]
+        );
+    }
+
+    #[test]
+    fn rendered_csv_reads_back() {
+        let entry = ExportEntry {
+            title: "Mail, \"work\"".to_string(),
+            username: "alice".to_string(),
+            password: "p,a\"ss".to_string(),
+            notes: "two\nlines".to_string(),
+            otp: Some("otpauth://totp/x?secret=ABC".to_string()),
+            tags: vec!["a".to_string(), "b".to_string()],
+            ..Default::default()
+        };
+        let tree = ExportGroup {
+            name: "Root".to_string(),
+            groups: vec![ExportGroup { name: "Work".to_string(), entries: vec![entry], ..Default::default() }],
+            ..Default::default()
+        };
+
+        let (read, errors) = ExportGroup::from_csv(&render_csv(&tree)).unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+        let back = &read.groups[0].entries[0];
+        assert_eq!(read.groups[0].name, "Work");
+        assert_eq!(back.title, "Mail, \"work\"");
+        assert_eq!(back.password, "p,a\"ss");
+        assert_eq!(back.notes, "two\nlines");
+        assert_eq!(back.otp.as_deref(), Some("otpauth://totp/x?secret=ABC"));
+        assert_eq!(back.tags, ["a", "b"]);
+    }
+}
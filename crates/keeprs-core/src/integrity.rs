@@ -0,0 +1,45 @@
+//! Structured results of the database integrity check.
+
+use std::fmt;
+
+/// A problem found by [`KeepassDatabase::check_integrity`](crate::KeepassDatabase::check_integrity).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityIssue {
+    /// Several nodes (entries or groups) share one UUID.
+    DuplicateUuid { uuid: String, count: usize },
+    /// A group appears inside its own subtree.
+    GroupIsOwnAncestor { uuid: String, name: String },
+    /// An attachment reference that isn't a valid index.
+    InvalidAttachmentRef { entry_uuid: String, field: String, reference: String },
+    /// An attachment reference pointing past the header attachments.
+    MissingAttachment { entry_uuid: String, field: String, index: usize },
+    /// A header attachment no entry refers to.
+    UnusedAttachment { index: usize },
+    /// The recycle bin UUID in the metadata doesn't match any group.
+    MissingRecycleBin { uuid: String },
+}
+
+impl fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityIssue::DuplicateUuid { uuid, count } => {
+                write!(f, "UUID {} is used by {} nodes", uuid, count)
+            }
+            IntegrityIssue::GroupIsOwnAncestor { uuid, name } => {
+                write!(f, "Group '{}' ({}) is its own ancestor", name, uuid)
+            }
+            IntegrityIssue::InvalidAttachmentRef { entry_uuid, field, reference } => {
+                write!(f, "Entry {}: attachment '{}' has an invalid reference '{}'", entry_uuid, field, reference)
+            }
+            IntegrityIssue::MissingAttachment { entry_uuid, field, index } => {
+                write!(f, "Entry {}: attachment '{}' refers to missing binary #{}", entry_uuid, field, index)
+            }
+            IntegrityIssue::UnusedAttachment { index } => {
+                write!(f, "Binary #{} is not used by any entry", index)
+            }
+            IntegrityIssue::MissingRecycleBin { uuid } => {
+                write!(f, "Recycle bin group {} does not exist", uuid)
+            }
+        }
+    }
+}
@@ -3,8 +3,19 @@
 //! This crate provides shared types and database operations used by both
 //! the GUI and TUI frontends.
 
+pub mod audit;
 pub mod database;
+pub mod export;
+pub mod integrity;
 pub mod models;
+pub mod password_generator;
+pub mod references;
+pub mod totp;
 
-pub use database::KeepassDatabase;
-pub use models::{Attachment, Entry, Group, NavigationPath, NavigationStep};
+pub use audit::{audit, audit_entry, find_duplicates, AuditIssue};
+pub use database::{KdfSettings, KeepassDatabase, DEFAULT_MAX_ATTACHMENT_BYTES};
+pub use export::{create_export_file, entry_to_json, ExportFormat, ImportReport};
+pub use integrity::IntegrityIssue;
+pub use models::{is_additional_url_field, normalize_url, title_from_url, Attachment, Entry, FieldMask, Group, NavigationPath, NavigationStep, SearchMatch, UnredactedEntry, DELETED_AT_FIELD, FAVORITE_FIELD, ORIGINAL_PARENT_FIELD, PASSWORD_POLICY_FIELD};
+pub use password_generator::{PasswordGenerator, PasswordPolicy};
+pub use totp::{Totp, TotpAlgorithm};
@@ -1,29 +1,304 @@
 //! Shared data types for the application.
 
-use std::collections::HashMap;
+use crate::password_generator::PasswordPolicy;
+use chrono::NaiveDateTime;
+use std::collections::{HashMap, HashSet};
+use zeroize::Zeroize;
 
 /// Represents a group (folder) in the database tree.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Group {
     pub uuid: String,
     pub name: String,
     pub children: Vec<Group>,
     pub entries: Vec<Entry>,
     pub is_recycle_bin: bool,
+    /// When the group was last moved (for recycle bin items, the deletion time).
+    pub location_changed: Option<NaiveDateTime>,
+    /// Default password policy (text form) for entries in this group and its
+    /// subgroups, see [`PASSWORD_POLICY_FIELD`].
+    pub password_policy: Option<String>,
+}
+
+impl Group {
+    /// Compare the user-meaningful content of two groups, recursively.
+    ///
+    /// Timestamps are ignored; nested groups and entries must match by UUID and content.
+    pub fn content_eq(&self, other: &Group) -> bool {
+        self.name == other.name
+            && self.is_recycle_bin == other.is_recycle_bin
+            && self.password_policy == other.password_policy
+            && self.entries.len() == other.entries.len()
+            && self.children.len() == other.children.len()
+            && self.entries.iter().zip(&other.entries).all(|(a, b)| a.uuid == b.uuid && a.content_eq(b))
+            && self.children.iter().zip(&other.children).all(|(a, b)| a.uuid == b.uuid && a.content_eq(b))
+    }
+
+    /// Whether this group has no entries, directly or in any child group.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.children.iter().all(Group::is_empty)
+    }
+
+    /// All tags used by entries in this group and its children, sorted and deduplicated.
+    ///
+    /// Entries in the recycle bin are ignored.
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut tags = std::collections::BTreeSet::new();
+        self.collect_tags(&mut tags);
+        tags.into_iter().collect()
+    }
+
+    fn collect_tags(&self, tags: &mut std::collections::BTreeSet<String>) {
+        if self.is_recycle_bin {
+            return;
+        }
+        for entry in &self.entries {
+            tags.extend(entry.tags.iter().cloned());
+        }
+        for child in &self.children {
+            child.collect_tags(tags);
+        }
+    }
+
+    /// Collect all entries (recursively) carrying the given tags.
+    ///
+    /// With `match_all` an entry needs every tag, otherwise any one of them is enough.
+    /// Entries in the recycle bin are ignored.
+    pub fn entries_with_tags(&self, tags: &[String], match_all: bool) -> Vec<Entry> {
+        let mut result = Vec::new();
+        self.collect_entries_where(
+            &|entry| {
+                if match_all {
+                    tags.iter().all(|t| entry.has_tag(t))
+                } else {
+                    tags.iter().any(|t| entry.has_tag(t))
+                }
+            },
+            &mut result,
+        );
+        result
+    }
+
+    /// Collect all entries (recursively) marked as favorite.
+    ///
+    /// Entries in the recycle bin are ignored.
+    pub fn favorite_entries(&self) -> Vec<Entry> {
+        let mut result = Vec::new();
+        self.collect_entries_where(&Entry::is_favorite, &mut result);
+        result
+    }
+
+    /// Every entry in the tree as one flat list, sorted by title.
+    ///
+    /// Entries in the recycle bin are ignored.
+    pub fn all_entries(&self) -> Vec<Entry> {
+        let mut result = Vec::new();
+        self.collect_entries_where(&|_| true, &mut result);
+        result.sort_by_cached_key(|e| e.title.to_lowercase());
+        result
+    }
+
+    /// Entries (recursively) matching `query` in the given fields, see [`Entry::matches`].
+    ///
+    /// A blank query finds nothing. Entries in the recycle bin are ignored.
+    pub fn search(&self, query: &str, fields: FieldMask) -> Vec<SearchMatch> {
+        let mut result = Vec::new();
+        if !query.trim().is_empty() {
+            self.collect_matches(&self.name, query, fields, &mut result);
+        }
+        result
+    }
+
+    /// Map each entry UUID to the path of the group holding it (e.g. `Work/Email`).
+    ///
+    /// Paths are relative to this group; its own entries map to its name.
+    pub fn entry_group_paths(&self) -> HashMap<String, String> {
+        let mut paths = HashMap::new();
+        for entry in &self.entries {
+            paths.insert(entry.uuid.clone(), self.name.clone());
+        }
+        for child in &self.children {
+            child.collect_entry_group_paths(&child.name, &mut paths);
+        }
+        paths
+    }
+
+    /// Path of the group with the given UUID (e.g. `Work/Email`), relative to this group.
+    pub fn group_path(&self, uuid: &str) -> Option<String> {
+        if self.uuid == uuid {
+            return Some(self.name.clone());
+        }
+        self.children.iter().find_map(|child| child.find_group_path(uuid, &child.name))
+    }
+
+    /// Find a group by a path as returned by [`Self::group_path`] (e.g. `Work/Email`).
+    ///
+    /// Like `group_path`, this group's own name stands for itself; an empty path does too.
+    pub fn find_group_by_path(&self, path: &str) -> Option<&Group> {
+        if path == self.name {
+            return Some(self);
+        }
+        path.split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(self, |group, name| group.children.iter().find(|child| child.name == name))
+    }
+
+    /// Groups from this one down to the group with the given UUID (both included).
+    pub fn group_ancestry(&self, uuid: &str) -> Option<Vec<&Group>> {
+        if self.uuid == uuid {
+            return Some(vec![self]);
+        }
+        self.children.iter().find_map(|child| {
+            child.group_ancestry(uuid).map(|mut ancestry| {
+                ancestry.insert(0, self);
+                ancestry
+            })
+        })
+    }
+
+    /// Groups from this one down to the group holding the entry with the given UUID.
+    pub fn entry_ancestry(&self, uuid: &str) -> Option<Vec<&Group>> {
+        if self.entries.iter().any(|e| e.uuid == uuid) {
+            return Some(vec![self]);
+        }
+        self.children.iter().find_map(|child| {
+            child.entry_ancestry(uuid).map(|mut ancestry| {
+                ancestry.insert(0, self);
+                ancestry
+            })
+        })
+    }
+
+    /// Policy for generating passwords of the entry with the given UUID when
+    /// it has none of its own: that of the nearest group (from the entry's
+    /// parent upwards) that sets one.
+    pub fn inherited_password_policy(&self, entry_uuid: &str) -> Option<&str> {
+        Self::nearest_password_policy(self.entry_ancestry(entry_uuid)?)
+    }
+
+    /// Policy in effect for new entries in the group with the given UUID (its
+    /// own or the nearest ancestor's).
+    pub fn effective_password_policy(&self, group_uuid: &str) -> Option<&str> {
+        Self::nearest_password_policy(self.group_ancestry(group_uuid)?)
+    }
+
+    fn nearest_password_policy(ancestry: Vec<&Group>) -> Option<&str> {
+        ancestry.into_iter().rev().find_map(|g| g.password_policy.as_deref())
+    }
+
+    fn find_group_path(&self, uuid: &str, path: &str) -> Option<String> {
+        if self.uuid == uuid {
+            return Some(path.to_string());
+        }
+        self.children
+            .iter()
+            .find_map(|child| child.find_group_path(uuid, &format!("{}/{}", path, child.name)))
+    }
+
+    fn collect_entry_group_paths(&self, path: &str, paths: &mut HashMap<String, String>) {
+        for entry in &self.entries {
+            paths.insert(entry.uuid.clone(), path.to_string());
+        }
+        for child in &self.children {
+            child.collect_entry_group_paths(&format!("{}/{}", path, child.name), paths);
+        }
+    }
+
+    fn collect_matches(&self, path: &str, query: &str, fields: FieldMask, result: &mut Vec<SearchMatch>) {
+        if self.is_recycle_bin {
+            return;
+        }
+        result.extend(self.entries.iter().filter(|e| e.matches(query, fields)).map(|entry| SearchMatch {
+            entry: entry.clone(),
+            group_uuid: self.uuid.clone(),
+            group_path: path.to_string(),
+        }));
+        for child in &self.children {
+            child.collect_matches(&format!("{}/{}", path, child.name), query, fields, result);
+        }
+    }
+
+    fn collect_entries_where(&self, keep: &dyn Fn(&Entry) -> bool, result: &mut Vec<Entry>) {
+        if self.is_recycle_bin {
+            return;
+        }
+        result.extend(self.entries.iter().filter(|e| keep(e)).cloned());
+        for child in &self.children {
+            child.collect_entries_where(keep, result);
+        }
+    }
+}
+
+/// An entry found by [`Group::search`], with the group it is in.
+#[derive(Debug, Clone)]
+pub struct SearchMatch {
+    pub entry: Entry,
+    /// UUID of the group holding the entry.
+    pub group_uuid: String,
+    /// Path of that group from the searched group (e.g. `Root/Work/Email`).
+    pub group_path: String,
 }
 
 /// Represents a password entry.
-#[derive(Debug, Clone, Default)]
+///
+/// `Debug` redacts the password, the OTP secret and custom field values so
+/// entries can't leak secrets through logging; use [`Entry::debug_unredacted`]
+/// when the real values are genuinely needed. The same values are zeroized
+/// when the entry is dropped.
+#[derive(Clone, Default)]
 pub struct Entry {
     pub uuid: String,
     pub title: String,
     pub username: String,
     pub password: String,
     pub url: String,
+    /// KeePass `OverrideURL`: how to launch the entry instead of opening `url`,
+    /// see [`Entry::launch_url`].
+    pub override_url: Option<String>,
     pub notes: String,
     pub custom_fields: HashMap<String, String>,
+    /// Custom fields KeePass keeps in memory-protected form; shown masked.
+    pub protected_fields: HashSet<String>,
     pub otp: Option<String>,
     pub attachments: Vec<Attachment>,
+    pub tags: Vec<String>,
+    /// Text color as `#rrggbb`.
+    pub foreground_color: Option<String>,
+    /// Highlight color as `#rrggbb`.
+    pub background_color: Option<String>,
+    /// When the entry was last moved (for recycle bin items, the deletion time).
+    pub location_changed: Option<NaiveDateTime>,
+    /// When the entry was last modified.
+    pub last_modified: Option<NaiveDateTime>,
+    /// When the entry expires (UTC), if it is set to expire.
+    pub expiry_time: Option<NaiveDateTime>,
+    /// Number of previous versions kept (see [`crate::KeepassDatabase::entry_history`]).
+    pub history_len: usize,
+}
+
+impl Drop for Entry {
+    fn drop(&mut self) {
+        self.password.zeroize();
+        self.otp.zeroize();
+        for value in self.custom_fields.values_mut() {
+            value.zeroize();
+        }
+    }
+}
+
+impl std::fmt::Debug for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_debug(f, true)
+    }
+}
+
+/// `Debug` view of an entry including its secrets, see [`Entry::debug_unredacted`].
+pub struct UnredactedEntry<'a>(&'a Entry);
+
+impl std::fmt::Debug for UnredactedEntry<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt_debug(f, false)
+    }
 }
 
 /// Represents a binary attachment.
@@ -32,6 +307,23 @@ pub struct Attachment {
     pub filename: String,
     pub _mime_type: Option<String>,
     pub data: Vec<u8>,
+    /// Binary reference that didn't resolve to a header attachment.
+    ///
+    /// Such attachments are placeholders with empty `data`; the reference itself
+    /// stays untouched in the database so saving doesn't break the link.
+    pub unresolved_ref: Option<String>,
+}
+
+impl Attachment {
+    /// Compare name and contents, ignoring the guessed MIME type.
+    pub fn content_eq(&self, other: &Attachment) -> bool {
+        self.filename == other.filename && self.data == other.data && self.unresolved_ref == other.unresolved_ref
+    }
+
+    /// Whether this is a placeholder for an unresolved binary reference.
+    pub fn is_missing(&self) -> bool {
+        self.unresolved_ref.is_some()
+    }
 }
 
 impl Entry {
@@ -39,19 +331,368 @@ impl Entry {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// `Debug` formatting that includes the password, OTP secret and custom field values.
+    ///
+    /// Never log this outside of local debugging.
+    pub fn debug_unredacted(&self) -> UnredactedEntry<'_> {
+        UnredactedEntry(self)
+    }
+
+    /// Compare the user-meaningful content of two entries.
+    ///
+    /// The UUID and timestamps are ignored, so an edit that changed nothing
+    /// compares equal to the stored entry.
+    pub fn content_eq(&self, other: &Entry) -> bool {
+        self.title == other.title
+            && self.username == other.username
+            && self.password == other.password
+            && self.url == other.url
+            && self.override_url == other.override_url
+            && self.notes == other.notes
+            && self.custom_fields == other.custom_fields
+            && self.otp == other.otp
+            && self.tags == other.tags
+            && self.foreground_color == other.foreground_color
+            && self.background_color == other.background_color
+            && self.attachments.len() == other.attachments.len()
+            && self.attachments.iter().zip(&other.attachments).all(|(a, b)| a.content_eq(b))
+    }
+
+    fn fmt_debug(&self, f: &mut std::fmt::Formatter<'_>, redact: bool) -> std::fmt::Result {
+        const REDACTED: &str = "***";
+        let secret = |value: &str| if redact && !value.is_empty() { REDACTED.to_string() } else { value.to_string() };
+
+        let custom_fields: std::collections::BTreeMap<&str, String> = self
+            .custom_fields
+            .iter()
+            .map(|(k, v)| (k.as_str(), secret(v)))
+            .collect();
+        let attachments: Vec<&str> = self.attachments.iter().map(|a| a.filename.as_str()).collect();
+
+        f.debug_struct("Entry")
+            .field("uuid", &self.uuid)
+            .field("title", &self.title)
+            .field("username", &self.username)
+            .field("password", &secret(&self.password))
+            .field("url", &self.url)
+            .field("override_url", &self.override_url)
+            .field("notes", &self.notes)
+            .field("custom_fields", &custom_fields)
+            .field("otp", &self.otp.as_deref().map(secret))
+            .field("attachments", &attachments)
+            .field("tags", &self.tags)
+            .field("foreground_color", &self.foreground_color)
+            .field("background_color", &self.background_color)
+            .field("location_changed", &self.location_changed)
+            .field("last_modified", &self.last_modified)
+            .field("expiry_time", &self.expiry_time)
+            .finish()
+    }
+
+    /// The URL the "open" action should launch.
+    ///
+    /// A non-empty [`override_url`](Self::override_url) wins, with `{USERNAME}`,
+    /// `{URL}` and `{PASSWORD}` (any case) replaced by the entry's values;
+    /// otherwise the normalized `url`. `None` if the entry has neither.
+    pub fn launch_url(&self) -> Option<String> {
+        if let Some(template) = self.override_url.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+            return Some(expand_placeholders(
+                template,
+                &[("{USERNAME}", &self.username), ("{URL}", &self.url), ("{PASSWORD}", &self.password)],
+            ));
+        }
+        let url = normalize_url(&self.url);
+        (!url.is_empty()).then_some(url)
+    }
+
+    /// Additional URLs stored in custom fields (`KP2A_URL`, `KP2A_URL_1`, ...).
+    ///
+    /// Returns `(field name, url)` pairs in field order, skipping empty values.
+    pub fn additional_urls(&self) -> Vec<(String, String)> {
+        let mut urls: Vec<(String, String)> = self
+            .custom_fields
+            .iter()
+            .filter(|(key, value)| is_additional_url_field(key) && !value.trim().is_empty())
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+
+        // Sort KP2A_URL, KP2A_URL_1, KP2A_URL_2, ..., KP2A_URL_10 numerically
+        urls.sort_by_key(|(key, _)| {
+            let index = key
+                .strip_prefix(ADDITIONAL_URL_PREFIX)
+                .and_then(|rest| rest.trim_start_matches('_').parse::<u32>().ok())
+                .unwrap_or(0);
+            (index, key.clone())
+        });
+        urls
+    }
+
+    /// Custom fields to list with the entry's details, sorted by name.
+    ///
+    /// Fields keeprs manages, additional URLs and TOTP settings are left out
+    /// since they are shown (or used) elsewhere.
+    pub fn display_custom_fields(&self) -> Vec<(&str, &str)> {
+        let mut fields: Vec<(&str, &str)> = self
+            .custom_fields
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .filter(|(key, _)| !is_internal_field(key) && !is_additional_url_field(key))
+            .collect();
+        fields.sort_unstable();
+        fields
+    }
+
+    /// Whole days since the entry was last moved, e.g. how long it has been in the recycle bin.
+    pub fn days_since_location_change(&self) -> Option<i64> {
+        self.location_changed
+            .map(|t| (chrono::Utc::now().naive_utc() - t).num_days().max(0))
+    }
+
+    /// Whether the entry's expiry time has passed.
+    pub fn is_expired(&self) -> bool {
+        self.expires_within(0)
+    }
+
+    /// Whether the entry has expired or will within the next `days` days.
+    pub fn expires_within(&self, days: i64) -> bool {
+        self.expiry_time
+            .is_some_and(|t| t <= chrono::Utc::now().naive_utc() + chrono::Duration::days(days))
+    }
+
+    /// Whether the entry carries the given tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    /// Whether the entry is starred (see [`FAVORITE_FIELD`]).
+    pub fn is_favorite(&self) -> bool {
+        self.custom_fields.get(FAVORITE_FIELD).is_some_and(|v| v == "true")
+    }
+
+    /// Star or unstar the entry.
+    pub fn set_favorite(&mut self, favorite: bool) {
+        if favorite {
+            self.custom_fields.insert(FAVORITE_FIELD.to_string(), "true".to_string());
+        } else {
+            self.custom_fields.remove(FAVORITE_FIELD);
+        }
+    }
+
+    /// UUID of the group a recycled entry was deleted from (see [`ORIGINAL_PARENT_FIELD`]).
+    pub fn original_parent_uuid(&self) -> Option<&str> {
+        self.custom_fields.get(ORIGINAL_PARENT_FIELD).map(String::as_str)
+    }
+
+    /// The password policy stored on the entry (see [`PASSWORD_POLICY_FIELD`]), if any.
+    pub fn password_policy(&self) -> anyhow::Result<Option<PasswordPolicy>> {
+        match self.custom_fields.get(PASSWORD_POLICY_FIELD) {
+            Some(value) if !value.trim().is_empty() => value.parse().map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Whether the entry matches a filter query in the fields selected by `fields`.
+    ///
+    /// Matching is case-insensitive; every whitespace-separated term of the
+    /// query must occur in at least one selected field. An empty query matches
+    /// everything.
+    pub fn matches(&self, query: &str, fields: FieldMask) -> bool {
+        let haystacks: Vec<String> = [
+            (fields.title, self.title.as_str()),
+            (fields.username, self.username.as_str()),
+            (fields.url, self.url.as_str()),
+            (fields.notes, self.notes.as_str()),
+        ]
+        .into_iter()
+        .filter(|(selected, _)| *selected)
+        .map(|(_, value)| value.to_lowercase())
+        .chain(fields.tags.then(|| self.tags.iter().map(|t| t.to_lowercase())).into_iter().flatten())
+        .collect();
+
+        query
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .all(|term| haystacks.iter().any(|h| h.contains(&term)))
+    }
+}
+
+/// Which entry fields [`Entry::matches`] looks at.
+///
+/// Secrets (password, OTP, custom field values) are never searched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldMask {
+    pub title: bool,
+    pub username: bool,
+    pub url: bool,
+    pub notes: bool,
+    pub tags: bool,
+}
+
+impl FieldMask {
+    /// Every searchable field.
+    pub const ALL: Self = Self { title: true, username: true, url: true, notes: true, tags: true };
+
+    /// Only the title.
+    pub const TITLE: Self = Self { title: true, username: false, url: false, notes: false, tags: false };
+}
+
+impl Default for FieldMask {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// Custom field marking an entry as favorite (value `true`).
+pub const FAVORITE_FIELD: &str = "_keeprs_favorite";
+
+/// Custom field recording the UUID of the group a recycled entry came from.
+pub const ORIGINAL_PARENT_FIELD: &str = "keeprs_original_parent_uuid";
+
+/// Custom field (custom data item for groups) recording when keeprs moved an
+/// item to the recycle bin, as `%Y-%m-%dT%H:%M:%S` in UTC.
+pub const DELETED_AT_FIELD: &str = "_keeprs_deleted_at";
+
+/// Custom field (custom data item for groups) holding a [`PasswordPolicy`] in
+/// its text form.
+pub const PASSWORD_POLICY_FIELD: &str = "_keeprs_password_policy";
+
+/// Whether a custom field holds keeprs bookkeeping or TOTP settings rather than user data.
+fn is_internal_field(key: &str) -> bool {
+    matches!(key, FAVORITE_FIELD | ORIGINAL_PARENT_FIELD | DELETED_AT_FIELD | PASSWORD_POLICY_FIELD | "otp")
+        || key.starts_with("TimeOtp-")
+        || key.starts_with("HmacOtp-")
+}
+
+/// Custom field prefix KeePass2Android/KeePassXC use for additional URLs.
+const ADDITIONAL_URL_PREFIX: &str = "KP2A_URL";
+
+/// Whether a custom field holds an additional URL (`KP2A_URL` or `KP2A_URL_<n>`).
+pub fn is_additional_url_field(key: &str) -> bool {
+    match key.strip_prefix(ADDITIONAL_URL_PREFIX) {
+        Some("") => true,
+        Some(rest) => rest.strip_prefix('_').is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())),
+        None => false,
+    }
+}
+
+/// Normalize a URL for storage.
+///
+/// Trims whitespace and assumes `https://` when no scheme is given, so that
+/// `example.com` becomes `https://example.com`. Empty values and KeePass
+/// placeholders (`{...}`) are left untouched.
+pub fn normalize_url(url: &str) -> String {
+    let url = url.trim();
+    if url.is_empty() || url.starts_with('{') || has_url_scheme(url) {
+        return url.to_string();
+    }
+    format!("https://{}", url)
+}
+
+/// Replace `{NAME}` placeholders (matched case-insensitively) in one pass,
+/// so placeholders inside substituted values are left alone.
+fn expand_placeholders(template: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let placeholder = values
+            .iter()
+            .find(|(name, _)| rest.get(..name.len()).is_some_and(|s| s.eq_ignore_ascii_case(name)));
+        match placeholder {
+            Some((name, value)) => {
+                out.push_str(value);
+                rest = &rest[name.len()..];
+            }
+            None => {
+                out.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Whether the URL starts with a scheme (`https://`, `ftp://`, `mailto:`...).
+///
+/// `host:port` is not treated as a scheme.
+fn has_url_scheme(url: &str) -> bool {
+    let Some((scheme, rest)) = url.split_once(':') else {
+        return false;
+    };
+    let valid_scheme = scheme.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+
+    valid_scheme
+        && (rest.starts_with("//")
+            || matches!(scheme.to_ascii_lowercase().as_str(), "mailto" | "tel" | "sms" | "data" | "urn"))
+}
+
+/// Brands whose usual spelling isn't plain title case.
+const KNOWN_TITLES: &[(&str, &str)] = &[
+    ("duckduckgo", "DuckDuckGo"),
+    ("github", "GitHub"),
+    ("gitlab", "GitLab"),
+    ("linkedin", "LinkedIn"),
+    ("paypal", "PayPal"),
+    ("youtube", "YouTube"),
+];
+
+/// Suggest an entry title from a URL's domain, e.g. `https://github.com/login` → `GitHub`.
+///
+/// Returns `None` for URLs without a usable host name (placeholders, IP addresses...).
+pub fn title_from_url(url: &str) -> Option<String> {
+    let url = normalize_url(url);
+    let (_, rest) = url.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?.to_ascii_lowercase();
+    if host.is_empty() || host.parse::<std::net::Ipv4Addr>().is_ok() {
+        return None;
+    }
+
+    let labels: Vec<&str> = host.trim_end_matches('.').split('.').filter(|l| !l.is_empty()).collect();
+    // Drop the TLD, and a short second level like the `co` in `example.co.uk`
+    let name = match labels.as_slice() {
+        [] => return None,
+        [single] => *single,
+        [.., name, sld, tld] if tld.len() == 2 && matches!(*sld, "co" | "com" | "org" | "net" | "ac" | "gov" | "edu") => name,
+        [.., name, _tld] => name,
+    };
+    if name.is_empty() || name == "www" {
+        return None;
+    }
+
+    if let Some((_, title)) = KNOWN_TITLES.iter().find(|(domain, _)| *domain == name) {
+        return Some(title.to_string());
+    }
+    let title = name
+        .split(['-', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!title.is_empty()).then_some(title)
 }
 
 /// Represents a step in the navigation path (for Miller columns).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum NavigationStep {
     /// A group/folder was selected.
-    Group { _uuid: String, name: String },
+    Group { uuid: String, name: String },
     /// An entry was selected.
-    Entry { _uuid: String, title: String },
+    Entry { uuid: String, title: String },
 }
 
 /// Navigation path tracking the current drill-down state.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct NavigationPath {
     /// The steps in the navigation (each click adds a step).
     pub steps: Vec<NavigationStep>,
@@ -63,14 +704,36 @@ impl NavigationPath {
         Self { steps: Vec::new() }
     }
 
+    /// Path from `root` down to the group with the given UUID.
+    pub fn to_group(root: &Group, uuid: &str) -> Option<Self> {
+        let mut path = Self::new();
+        for group in root.group_ancestry(uuid)? {
+            path.push_group(group.uuid.clone(), group.name.clone());
+        }
+        Some(path)
+    }
+
+    /// Path from `root` through the entry's groups down to the entry itself.
+    pub fn to_entry(root: &Group, uuid: &str) -> Option<Self> {
+        let ancestry = root.entry_ancestry(uuid)?;
+        let entry = ancestry.last()?.entries.iter().find(|e| e.uuid == uuid)?;
+
+        let mut path = Self::new();
+        for group in &ancestry {
+            path.push_group(group.uuid.clone(), group.name.clone());
+        }
+        path.push_entry(entry.uuid.clone(), entry.title.clone());
+        Some(path)
+    }
+
     /// Add a group selection to the path.
     pub fn push_group(&mut self, uuid: String, name: String) {
-        self.steps.push(NavigationStep::Group { _uuid: uuid, name });
+        self.steps.push(NavigationStep::Group { uuid, name });
     }
 
     /// Add an entry selection to the path.
     pub fn push_entry(&mut self, uuid: String, title: String) {
-        self.steps.push(NavigationStep::Entry { _uuid: uuid, title });
+        self.steps.push(NavigationStep::Entry { uuid, title });
     }
 
     /// Truncate the path to the given depth (0 = clear all).
@@ -83,3 +746,67 @@ impl NavigationPath {
         self.steps.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Entry implements Drop, which rules out struct update syntax
+    #[allow(clippy::field_reassign_with_default)]
+    fn entry() -> Entry {
+        let mut entry = Entry::default();
+        entry.title = "Mail".to_string();
+        entry.username = "alice".to_string();
+        entry.password = "hunter2".to_string();
+        entry.url = "https://example.org".to_string();
+        entry.notes = "Recovery codes".to_string();
+        entry.tags = vec!["Work".to_string()];
+        entry.otp = Some("otpauth://totp/secretissuer".to_string());
+        entry.custom_fields.insert("PIN".to_string(), "secretpin".to_string());
+        entry
+    }
+
+    const NONE: FieldMask = FieldMask { title: false, username: false, url: false, notes: false, tags: false };
+
+    #[test]
+    fn each_mask_bit_selects_its_field() {
+        let entry = entry();
+        let cases = [
+            ("mail", FieldMask { title: true, ..NONE }),
+            ("alice", FieldMask { username: true, ..NONE }),
+            ("example.org", FieldMask { url: true, ..NONE }),
+            ("recovery", FieldMask { notes: true, ..NONE }),
+            ("work", FieldMask { tags: true, ..NONE }),
+        ];
+        for (query, mask) in cases {
+            assert!(entry.matches(query, mask), "{query:?} should match with {mask:?}");
+            assert!(!entry.matches(query, NONE), "{query:?} should not match with no fields");
+            // Every other single field leaves the query unmatched
+            for (_, other) in cases.iter().filter(|(_, other)| *other != mask) {
+                assert!(!entry.matches(query, *other), "{query:?} should not match with {other:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn secrets_are_never_matched() {
+        let entry = entry();
+        for query in ["hunter2", "secretissuer", "secretpin", "PIN"] {
+            assert!(!entry.matches(query, FieldMask::ALL), "{query:?} should not match");
+        }
+    }
+
+    #[test]
+    fn every_term_must_match_some_selected_field() {
+        let entry = entry();
+        assert!(entry.matches("MAIL Alice", FieldMask::ALL));
+        assert!(!entry.matches("mail bob", FieldMask::ALL));
+        assert!(!entry.matches("mail alice", FieldMask::TITLE));
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert!(entry().matches("", NONE));
+        assert!(entry().matches("   ", FieldMask::TITLE));
+    }
+}
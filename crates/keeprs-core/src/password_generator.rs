@@ -0,0 +1,180 @@
+//! Random password generation.
+//!
+//! A [`PasswordPolicy`] describes what a site accepts and round-trips through a
+//! short text form, e.g. `length=20 lower upper digits symbols=!@#$%`, so it can
+//! be stored on an entry and edited by hand.
+
+use anyhow::{Context, Result};
+use rand::seq::{IndexedRandom, SliceRandom};
+use std::fmt;
+use std::str::FromStr;
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+/// Symbols used when a policy doesn't list its own.
+pub const DEFAULT_SYMBOLS: &str = "!@#$%^&*()-_=+[]{};:,.?/";
+/// Characters that are easy to mix up when read or typed.
+const AMBIGUOUS: &str = "0OoIl1|`'\"";
+
+/// Rules a generated password has to follow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordPolicy {
+    /// Number of characters.
+    pub length: usize,
+    /// Include `a-z`.
+    pub lowercase: bool,
+    /// Include `A-Z`.
+    pub uppercase: bool,
+    /// Include `0-9`.
+    pub digits: bool,
+    /// Allowed symbols; empty for none.
+    pub symbols: String,
+    /// Leave out look-alike characters such as `0`/`O` and `1`/`l`/`I`.
+    pub avoid_ambiguous: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            length: 20,
+            lowercase: true,
+            uppercase: true,
+            digits: true,
+            symbols: DEFAULT_SYMBOLS.to_string(),
+            avoid_ambiguous: false,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// The character sets this policy draws from, skipping disabled ones.
+    fn char_sets(&self) -> Vec<Vec<char>> {
+        let mut sets = Vec::new();
+        if self.lowercase {
+            sets.push(LOWERCASE.chars().collect());
+        }
+        if self.uppercase {
+            sets.push(UPPERCASE.chars().collect());
+        }
+        if self.digits {
+            sets.push(DIGITS.chars().collect());
+        }
+        if !self.symbols.is_empty() {
+            let mut symbols: Vec<char> = self.symbols.chars().collect();
+            symbols.sort_unstable();
+            symbols.dedup();
+            sets.push(symbols);
+        }
+        if self.avoid_ambiguous {
+            for set in &mut sets {
+                set.retain(|c| !AMBIGUOUS.contains(*c));
+            }
+            sets.retain(|set| !set.is_empty());
+        }
+        sets
+    }
+}
+
+impl fmt::Display for PasswordPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "length={}", self.length)?;
+        if self.lowercase {
+            f.write_str(" lower")?;
+        }
+        if self.uppercase {
+            f.write_str(" upper")?;
+        }
+        if self.digits {
+            f.write_str(" digits")?;
+        }
+        if !self.symbols.is_empty() {
+            write!(f, " symbols={}", self.symbols)?;
+        }
+        if self.avoid_ambiguous {
+            f.write_str(" unambiguous")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PasswordPolicy {
+    type Err = anyhow::Error;
+
+    /// Parse the text form. Character sets not mentioned are disabled.
+    fn from_str(s: &str) -> Result<Self> {
+        let mut policy = PasswordPolicy {
+            length: PasswordPolicy::default().length,
+            lowercase: false,
+            uppercase: false,
+            digits: false,
+            symbols: String::new(),
+            avoid_ambiguous: false,
+        };
+
+        for token in s.split_whitespace() {
+            match token.split_once('=') {
+                Some(("length", value)) => {
+                    policy.length = value
+                        .parse()
+                        .with_context(|| format!("Invalid password length '{}'", value))?;
+                }
+                Some(("symbols", value)) => policy.symbols = value.to_string(),
+                None if token == "lower" => policy.lowercase = true,
+                None if token == "upper" => policy.uppercase = true,
+                None if token == "digits" => policy.digits = true,
+                None if token == "unambiguous" => policy.avoid_ambiguous = true,
+                _ => anyhow::bail!("Unknown password policy option '{}'", token),
+            }
+        }
+
+        Ok(policy)
+    }
+}
+
+/// Generates passwords following a [`PasswordPolicy`].
+#[derive(Debug, Clone, Default)]
+pub struct PasswordGenerator {
+    policy: PasswordPolicy,
+}
+
+impl PasswordGenerator {
+    /// Create a generator for the given policy.
+    pub fn new(policy: PasswordPolicy) -> Self {
+        Self { policy }
+    }
+
+    /// The policy passwords are generated with.
+    pub fn policy(&self) -> &PasswordPolicy {
+        &self.policy
+    }
+
+    /// Generate a password containing at least one character of every enabled set.
+    pub fn generate(&self) -> Result<String> {
+        let sets = self.policy.char_sets();
+        if sets.is_empty() {
+            anyhow::bail!("Password policy allows no characters");
+        }
+        if self.policy.length < sets.len() {
+            anyhow::bail!(
+                "Password length {} is too short for {} character sets",
+                self.policy.length,
+                sets.len()
+            );
+        }
+
+        let mut rng = rand::rng();
+        let all: Vec<char> = sets.iter().flatten().copied().collect();
+
+        let mut chars: Vec<char> = sets
+            .iter()
+            .filter_map(|set| set.choose(&mut rng).copied())
+            .collect();
+        while chars.len() < self.policy.length {
+            chars.extend(all.choose(&mut rng));
+        }
+        chars.shuffle(&mut rng);
+
+        Ok(chars.into_iter().collect())
+    }
+}
@@ -0,0 +1,135 @@
+//! Resolution of KeePass field references and placeholders.
+//!
+//! Supported forms are `{USERNAME}`, `{PASSWORD}` and `{URL}` for the entry's
+//! own fields, and `{REF:<wanted>@<search>:<text>}` for another entry's
+//! field. Field codes are `T` (title), `U` (username), `P` (password), `A`
+//! (URL), `N` (notes) and, as the search field only, `I` (UUID). Anything
+//! else is left as written.
+
+use crate::models::{Entry, Group};
+use zeroize::Zeroize;
+
+/// How many references may chain before resolution stops, which also ends
+/// reference cycles.
+const MAX_DEPTH: usize = 10;
+
+impl Group {
+    /// Expand placeholders and references in `raw`, a field value of the entry
+    /// with the given UUID.
+    ///
+    /// Returns `raw` unchanged if the entry isn't in this tree.
+    pub fn resolve_field(&self, entry_uuid: &str, raw: &str) -> String {
+        if !raw.contains('{') {
+            return raw.to_string();
+        }
+        match self.find_entry(entry_uuid) {
+            Some(entry) => self.resolve(entry, raw, MAX_DEPTH),
+            None => raw.to_string(),
+        }
+    }
+
+    /// Copy of `entry` with its username, password and URL resolved, for
+    /// display and copying. Edits must keep using the raw entry.
+    pub fn resolved_entry(&self, entry: &Entry) -> Entry {
+        let mut resolved = entry.clone();
+        resolved.username = self.resolve(entry, &entry.username, MAX_DEPTH);
+        resolved.url = self.resolve(entry, &entry.url, MAX_DEPTH);
+        // Scrub the copied password before replacing it
+        resolved.password.zeroize();
+        resolved.password = self.resolve(entry, &entry.password, MAX_DEPTH);
+        resolved
+    }
+
+    fn resolve(&self, entry: &Entry, raw: &str, depth: usize) -> String {
+        if depth == 0 || !raw.contains('{') {
+            return raw.to_string();
+        }
+
+        let mut out = String::with_capacity(raw.len());
+        let mut rest = raw;
+        while let Some(start) = rest.find('{') {
+            out.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let Some(end) = rest.find('}') else {
+                break;
+            };
+            match self.resolve_token(entry, &rest[1..end]) {
+                Some((target, value)) => {
+                    out.push_str(&self.resolve(target, value, depth - 1));
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    out.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// The entry a placeholder or reference points at and its raw value.
+    fn resolve_token<'a>(&'a self, entry: &'a Entry, token: &str) -> Option<(&'a Entry, &'a str)> {
+        let upper = token.to_ascii_uppercase();
+        match upper.as_str() {
+            "USERNAME" => Some((entry, &entry.username)),
+            "PASSWORD" => Some((entry, &entry.password)),
+            "URL" => Some((entry, &entry.url)),
+            _ => {
+                upper.strip_prefix("REF:")?;
+                self.resolve_reference(&token[4..])
+            }
+        }
+    }
+
+    /// Parse `<wanted>@<search>:<text>` and look up the referenced entry.
+    fn resolve_reference(&self, reference: &str) -> Option<(&Entry, &str)> {
+        let (codes, text) = reference.split_once(':')?;
+        let (wanted, search) = codes.split_once('@')?;
+        let wanted = single_char(wanted)?;
+        let search = single_char(search)?;
+
+        let target = if search == 'I' {
+            let uuid = normalize_uuid(text);
+            self.find_entry_where(&|e| normalize_uuid(&e.uuid) == uuid)
+        } else {
+            let text = text.to_lowercase();
+            self.find_entry_where(&|e| field(e, search).is_some_and(|value| value.to_lowercase().contains(&text)))
+        }?;
+        Some((target, field(target, wanted)?))
+    }
+
+    fn find_entry(&self, uuid: &str) -> Option<&Entry> {
+        self.find_entry_where(&|e| e.uuid == uuid)
+    }
+
+    fn find_entry_where(&self, keep: &dyn Fn(&Entry) -> bool) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|e| keep(e))
+            .or_else(|| self.children.iter().find_map(|child| child.find_entry_where(keep)))
+    }
+}
+
+/// The entry field a reference code stands for (`I` is search-only).
+fn field(entry: &Entry, code: char) -> Option<&str> {
+    match code {
+        'T' => Some(&entry.title),
+        'U' => Some(&entry.username),
+        'P' => Some(&entry.password),
+        'A' => Some(&entry.url),
+        'N' => Some(&entry.notes),
+        _ => None,
+    }
+}
+
+fn single_char(code: &str) -> Option<char> {
+    let mut chars = code.chars();
+    let c = chars.next()?.to_ascii_uppercase();
+    chars.next().is_none().then_some(c)
+}
+
+/// KeePass writes UUIDs in references as 32 hex digits without dashes.
+fn normalize_uuid(uuid: &str) -> String {
+    uuid.chars().filter(|c| *c != '-').collect::<String>().to_lowercase()
+}
@@ -1,19 +1,45 @@
 //! Main application component.
 
-use crate::components::entry_browser::{EntryBrowser, EntryBrowserInput, EntryBrowserOutput};
+use crate::clipboard::ClipboardGuard;
+use crate::components::common;
+use crate::components::entry_browser::{EntryBrowser, EntryBrowserInit, EntryBrowserInput, EntryBrowserOutput};
 use crate::components::entry_edit::{EntryEdit, EntryEditInput, EntryEditOutput};
+use crate::components::entry_compare::{EntryCompare, EntryCompareInput, EntryCompareOutput};
+use crate::components::entry_history::{EntryHistory, EntryHistoryInput, EntryHistoryOutput};
 use crate::components::group_edit::{GroupEdit, GroupEditInput, GroupEditOutput};
-use crate::components::info_bar::{format_save_time, InfoBar, InfoBarInput};
+use crate::components::group_picker::{GroupFilter, GroupPicker, GroupPickerInput, GroupPickerOutput};
+use crate::components::info_bar::{format_save_time, InfoBar, InfoBarInit, InfoBarInput};
 use crate::components::search_palette::{SearchPalette, SearchPaletteInput, SearchPaletteOutput};
 use crate::components::sidebar::{Sidebar, SidebarInit, SidebarInput, SidebarOutput};
 use crate::components::unlock::{UnlockDialog, UnlockInput, UnlockOutput};
+use crate::components::import_preview::{ImportPreviewDialog, ImportPreviewInput, ImportPreviewOutput};
 use crate::components::password_confirmation::{PasswordConfirmation, PasswordConfirmationInput, PasswordConfirmationOutput};
-use crate::config::Config;
-use keeprs_core::{Entry, Group, KeepassDatabase};
+use crate::components::new_password::{NewPasswordDialog, NewPasswordInput, NewPasswordOutput};
+use crate::components::toast::{NotificationKind, Toast, ToastInput};
+use crate::components::unsaved_changes::{UnsavedChangesDialog, UnsavedChangesInput, UnsavedChangesOutput};
+use crate::config::{Config, FocusLossAction};
+use keeprs_core::{create_export_file, find_duplicates, Entry, ExportFormat, Group, KeepassDatabase};
 
+use anyhow::Context;
 use gtk4::prelude::*;
 use relm4::prelude::*;
+use std::cell::Cell;
+use std::io::Write;
+use std::rc::Rc;
 use std::sync::{Arc, RwLock};
+use zeroize::{Zeroize, Zeroizing};
+
+/// `action_id` of the password confirmation guarding the CSV export.
+const EXPORT_CSV_ACTION: &str = "export_csv";
+
+/// `action_id` of the password confirmation guarding the JSON export.
+const EXPORT_JSON_ACTION: &str = "export_json";
+
+/// `action_id` of the password confirmation guarding the export of selected entries.
+const EXPORT_ENTRIES_ACTION: &str = "export_entries";
+
+/// Passwords zxcvbn scores below this ("Strong") are listed by the password audit.
+const WEAK_PASSWORD_MIN_SCORE: u8 = 3;
 
 #[cfg(debug_assertions)]
 use serde::{Deserialize, Serialize};
@@ -42,6 +68,54 @@ impl DevState {
     }
 }
 
+/// Entry browser views that are not backed by a single group.
+#[derive(Debug, Clone)]
+enum VirtualView {
+    /// Entries carrying the selected tags.
+    Tags { tags: Vec<String>, match_all: bool },
+    /// Starred entries from the whole tree.
+    Favorites,
+    /// Every entry as one flat list.
+    AllEntries,
+}
+
+impl VirtualView {
+    /// Build a transient group holding the entries for this view.
+    fn build(&self, root: &Group) -> Group {
+        match self {
+            VirtualView::Tags { tags, match_all } => {
+                let separator = if *match_all { " + " } else { " | " };
+                Group {
+                    uuid: "virtual-tags".to_string(),
+                    name: format!("Tags: {}", tags.join(separator)),
+                    entries: root.entries_with_tags(tags, *match_all),
+                    ..Group::default()
+                }
+            }
+            VirtualView::Favorites => Group {
+                uuid: "virtual-favorites".to_string(),
+                name: "Favorites".to_string(),
+                entries: root.favorite_entries(),
+                ..Group::default()
+            },
+            VirtualView::AllEntries => Group {
+                uuid: "virtual-all".to_string(),
+                name: "All Entries".to_string(),
+                entries: root.all_entries(),
+                ..Group::default()
+            },
+        }
+    }
+}
+
+/// What the unsaved changes prompt is asking about.
+#[derive(Debug)]
+enum PendingAction {
+    Quit,
+    Open(std::path::PathBuf),
+    Lock,
+}
+
 /// Application state.
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppState {
@@ -54,8 +128,17 @@ pub enum AppState {
 /// Main app messages.
 #[derive(Debug)]
 pub enum AppInput {
-    /// Password submitted from unlock dialog.
-    PasswordSubmitted(String),
+    /// Credentials submitted from unlock dialog (the password may be empty with a key file).
+    PasswordSubmitted {
+        password: Zeroizing<String>,
+        key_file: Option<std::path::PathBuf>,
+    },
+    /// Create a new database at the configured path with this password.
+    CreateDatabase(Zeroizing<String>),
+    /// Ask for another database file to open.
+    ChooseDatabase,
+    /// Another database file was picked on the unlock screen.
+    DatabaseChosen(std::path::PathBuf),
     /// Unlock failed with error.
     // UnlockFailed(String), // Unused
     /// Database unlocked successfully.
@@ -64,6 +147,8 @@ pub enum AppInput {
     GroupSelected(String),
     /// Entry selected in sidebar.
     SidebarEntrySelected(String),
+    /// Group clicked in the entry browser breadcrumb.
+    BreadcrumbGroupSelected(String),
     /// Group selected from search with full data.
     SearchGroupSelected { uuid: String, name: String, group: Group },
     /// Entry selected from search.
@@ -75,33 +160,190 @@ pub enum AppInput {
     DeleteGroup(String),
     EmptyRecycleBin(String),
     AddEntry,
+    /// Copy an entry into its group.
+    DuplicateEntry(String),
     AddGroup,
+    /// Edit the name and password policy of a group.
+    EditGroup(String),
     /// Save attachment.
     SaveAttachment { filename: String, data: Vec<u8> },
     /// Open attachment.
     OpenAttachment { filename: String, data: Vec<u8> },
+    /// Pick a file to attach to the entry with the given UUID.
+    AddAttachment(String),
+    /// File picked to attach to an entry.
+    AttachmentChosen { uuid: String, filename: String, data: Vec<u8> },
+    /// Ask for the master password before exporting the given entries.
+    ExportEntries(Vec<String>),
+    /// The master password was entered for exporting the selected entries; choose the file.
+    ExportEntriesConfirmed(Zeroizing<String>),
+    /// Ask for a target group and move the given entries there.
+    MoveEntries(Vec<String>),
+    /// Move an entry to position `new_index` among its group's entries.
+    ReorderEntry { group_uuid: String, entry_uuid: String, new_index: usize },
+    /// New entry from the edit dialog, to be added to the given group.
+    EntryCreated { entry: Entry, group_uuid: String },
+    /// Ask for the group a new entry goes to.
+    ChooseNewEntryGroup,
+    /// Target group chosen in the group picker.
+    GroupPicked(String),
+    /// Show the previous versions of an entry.
+    ShowEntryHistory(String),
+    /// Restore a previous version of an entry.
+    RestoreEntryVersion { uuid: String, index: usize },
     /// Request to permanently delete an entry (shows confirmation).
     VerifyPermanentDeleteEntry(String),
     /// Request to permanently delete a group (shows confirmation).
     VerifyPermanentDeleteGroup(String),
     /// Confirmed permanent deletion
-    PermanentDeleteConfirmed { password: String, action_id: String },
+    PermanentDeleteConfirmed { password: Zeroizing<String>, action_id: String },
     /// Restore entry
     RestoreEntry(String),
+    /// Move a group (with its contents) out of the recycle bin.
+    RestoreGroup(String),
+    /// Restore every entry in the recycle bin.
+    RestoreAllEntries,
     /// Entry saved from edit dialog.
     EntrySaved(Entry),
     /// Group saved from edit dialog.
     GroupSaved(Group),
+    /// Reload the group tree from the database into every view, keeping the selection.
+    RefreshTree,
     /// Request to save the database.
     SaveDatabase,
+    /// Save after a change, unless saving is manual (`auto_save = false`).
+    AutoSave,
     /// Save operation finished.
     SaveFinished(Result<(), String>),
     /// Toggle search palette visibility.
     ToggleSearch,
+    /// Tag filter changed in the sidebar.
+    TagsSelected { tags: Vec<String>, match_all: bool },
+    /// Favorites shortcut selected in the sidebar.
+    ShowFavorites,
+    /// All Entries shortcut selected in the sidebar.
+    ShowAllEntries,
+    /// Go up one level in the entry browser.
+    NavigateBack,
+    /// Go back down to where `NavigateBack` came from.
+    NavigateForward,
+    /// Run the integrity check and show its report.
+    CheckIntegrity,
+    /// Look for duplicate entries and compare them pair by pair.
+    FindDuplicates,
+    /// List groups of entries sharing the same password.
+    FindReusedPasswords,
+    /// List entries with weak or old passwords.
+    PasswordAudit,
+    /// Show the next pending duplicate pair, skipping pairs already resolved.
+    CompareNextDuplicate,
+    /// One entry of a duplicate pair should go to the recycle bin.
+    DuplicateDiscarded(String),
+    /// The duplicate review was stopped.
+    DuplicatesCancelled,
+    /// Show a file in the file manager.
+    Reveal(std::path::PathBuf),
+    /// Show the database file in the file manager.
+    RevealDatabase,
+    /// Show the last saved attachment in the file manager.
+    RevealSavedAttachment,
+    /// An attachment was written to the given file.
+    AttachmentSaved(std::path::PathBuf),
+    /// Ask where to write a re-keyed copy of the database.
+    SaveCopy,
+    /// The file for the copy was chosen; ask for its password.
+    SaveCopyPathChosen(std::path::PathBuf),
+    /// The password for the copy was chosen; write it.
+    SaveCopyPasswordChosen(Zeroizing<String>),
+    /// Ask for the master password before exporting all entries as CSV.
+    ExportCsv,
+    /// The master password was entered for the CSV export; choose the file.
+    ExportCsvConfirmed(Zeroizing<String>),
+    /// Ask for the master password before exporting the whole group tree as JSON.
+    ExportJson,
+    /// The master password was entered for the JSON export; choose the file.
+    ExportJsonConfirmed(Zeroizing<String>),
+    /// Ask for a new master password.
+    ChangeMasterPassword,
+    /// The new master password was chosen; re-key and save the database.
+    MasterPasswordChosen(Zeroizing<String>),
+    /// The new password dialog returned a password.
+    NewPasswordChosen(Zeroizing<String>),
+    /// The new password dialog was cancelled.
+    NewPasswordCancelled,
+    /// Ask for a JSON export or CSV file to import.
+    ImportEntries,
+    /// An export file was read; preview what importing it would do.
+    PreviewImport { source: String, contents: String, format: ExportFormat },
+    /// The previewed import was confirmed.
+    ImportConfirmed,
+    /// The previewed import was cancelled.
+    ImportCancelled,
+    /// Show a transient notification to the user.
+    Notify(String, NotificationKind),
+    /// The main window is about to close.
+    CloseRequested,
+    /// Save pending changes, then quit (or open the pending database).
+    SaveAndContinue,
+    /// Drop pending changes, then quit (or open the pending database).
+    DiscardAndContinue,
+    /// The unsaved changes prompt was cancelled.
+    CancelPendingAction,
+    /// Quit without further checks.
+    Quit,
+    /// A database file was handed to the application (command line, file manager,
+    /// or a second invocation).
+    OpenFileRequested(std::path::PathBuf),
+    /// Lock the current database (if any) and open another one.
+    SwitchDatabase(std::path::PathBuf),
+    /// Lock the database and return to the unlock screen.
+    Lock,
+    /// No window of the application has focus anymore.
+    FocusLost,
+    /// Periodic check whether the auto-lock time has passed.
+    CheckIdle,
     /// No operation.
     NoOp,
 }
 
+impl AppInput {
+    /// Whether handling this input changes the open database (refused when read-only).
+    fn modifies_database(&self) -> bool {
+        matches!(
+            self,
+            AppInput::EditEntry(_)
+                | AppInput::DeleteEntry(_)
+                | AppInput::DeleteGroup(_)
+                | AppInput::EmptyRecycleBin(_)
+                | AppInput::AddEntry
+                | AppInput::DuplicateEntry(_)
+                | AppInput::AddGroup
+                | AppInput::EditGroup(_)
+                | AppInput::AddAttachment(_)
+                | AppInput::AttachmentChosen { .. }
+                | AppInput::MoveEntries(_)
+                | AppInput::ReorderEntry { .. }
+                | AppInput::EntryCreated { .. }
+                | AppInput::RestoreEntryVersion { .. }
+                | AppInput::VerifyPermanentDeleteEntry(_)
+                | AppInput::VerifyPermanentDeleteGroup(_)
+                | AppInput::PermanentDeleteConfirmed { .. }
+                | AppInput::RestoreEntry(_)
+                | AppInput::RestoreGroup(_)
+                | AppInput::RestoreAllEntries
+                | AppInput::EntrySaved(_)
+                | AppInput::GroupSaved(_)
+                | AppInput::SaveDatabase
+                | AppInput::DuplicateDiscarded(_)
+                | AppInput::ChangeMasterPassword
+                | AppInput::MasterPasswordChosen(_)
+                | AppInput::ImportEntries
+                | AppInput::PreviewImport { .. }
+                | AppInput::ImportConfirmed
+        )
+    }
+}
+
 /// Main application model.
 pub struct App {
     state: AppState,
@@ -115,6 +357,32 @@ pub struct App {
     last_save_time: String,
     current_group_uuid: Option<String>,
     root_group: Option<Group>,
+    /// Virtual view currently shown in the entry browser, if any.
+    virtual_view: Option<VirtualView>,
+    /// Set once the user agreed to close; checked by the close-request handler.
+    allow_close: Rc<Cell<bool>>,
+    /// Last keyboard or mouse input, or app message, for the auto-lock.
+    last_activity: Rc<Cell<std::time::Instant>>,
+    /// Carry out `pending_action` once the running save succeeds.
+    continue_after_save: bool,
+    /// A save was requested while another one ran; start it once that finishes.
+    save_again: bool,
+    /// Database to open once the unsaved changes prompt is answered.
+    pending_action: Option<PendingAction>,
+    /// Entries waiting for the group picker to choose their new group.
+    pending_move: Vec<String>,
+    /// Entries waiting for the password confirmation to be exported.
+    pending_export: Vec<String>,
+    /// The group picker is choosing the group of a new entry.
+    picking_new_entry_group: bool,
+    /// File the re-keyed copy goes to, while its password is asked for.
+    pending_copy_path: Option<std::path::PathBuf>,
+    /// Export being previewed, its format, and the group it goes into (`None` for the root).
+    pending_import: Option<(String, ExportFormat, Option<String>)>,
+    /// Duplicate pairs still to be compared, next one last.
+    pending_duplicates: Vec<(Entry, Entry)>,
+    /// Where the last attachment was saved this session.
+    last_saved_attachment: Option<std::path::PathBuf>,
 
     // Child components
     unlock: Controller<UnlockDialog>,
@@ -125,6 +393,13 @@ pub struct App {
     group_edit: Controller<GroupEdit>,
     info_bar: Controller<InfoBar>,
     password_confirmation: Controller<PasswordConfirmation>,
+    new_password: Controller<NewPasswordDialog>,
+    import_preview: Controller<ImportPreviewDialog>,
+    group_picker: Controller<GroupPicker>,
+    entry_history: Controller<EntryHistory>,
+    entry_compare: Controller<EntryCompare>,
+    toast: Controller<Toast>,
+    unsaved_changes_dialog: Controller<UnsavedChangesDialog>,
 }
 
 #[relm4::component(pub)]
@@ -187,6 +462,7 @@ impl Component for App {
                         },
     
                         add_overlay = model.search_palette.widget(),
+                        add_overlay = model.toast.widget(),
                     },
 
                     // Bottom info bar - use the InfoBar component
@@ -205,9 +481,13 @@ impl Component for App {
     ) -> ComponentParts<Self> {
         // Initialize child components
         let unlock = UnlockDialog::builder()
-            .launch(())
+            .launch(config.database_path.clone())
             .forward(sender.input_sender(), |output| match output {
-                UnlockOutput::Unlocked(password) => AppInput::PasswordSubmitted(password),
+                UnlockOutput::Unlocked { password, key_file } => {
+                    AppInput::PasswordSubmitted { password, key_file }
+                }
+                UnlockOutput::Create(password) => AppInput::CreateDatabase(password),
+                UnlockOutput::ChooseDatabase => AppInput::ChooseDatabase,
             });
 
         let search_palette = SearchPalette::builder()
@@ -230,6 +510,8 @@ impl Component for App {
                 initial_width: config.sidebar_initial_width,
                 min_width: config.sidebar_min_width,
                 hidden_groups: config.hidden_groups.clone(),
+                hide_empty_groups: config.hide_empty_groups,
+                read_only: config.read_only,
             })
             .forward(sender.input_sender(), |output| match output {
                 SidebarOutput::GroupSelected(uuid) => AppInput::GroupSelected(uuid),
@@ -237,32 +519,72 @@ impl Component for App {
                     AppInput::SidebarEntrySelected(uuid)
                 }
                 SidebarOutput::RequestAddGroup => AppInput::AddGroup,
+                SidebarOutput::RequestEditGroup(uuid) => AppInput::EditGroup(uuid),
 
                 SidebarOutput::RequestDeleteGroup(uuid) => AppInput::DeleteGroup(uuid),
                 SidebarOutput::RequestDeleteEntry(uuid) => AppInput::DeleteEntry(uuid),
                 SidebarOutput::RequestEmptyRecycleBin(uuid) => AppInput::EmptyRecycleBin(uuid),
                 SidebarOutput::RequestPermanentDeleteGroup(uuid) => AppInput::VerifyPermanentDeleteGroup(uuid),
                 SidebarOutput::RequestPermanentDeleteEntry(uuid) => AppInput::VerifyPermanentDeleteEntry(uuid),
+                SidebarOutput::RequestRestoreGroup(uuid) => AppInput::RestoreGroup(uuid),
+                SidebarOutput::RequestRestoreEntry(uuid) => AppInput::RestoreEntry(uuid),
+                SidebarOutput::RequestDuplicateEntry(uuid) => AppInput::DuplicateEntry(uuid),
+                SidebarOutput::TagsSelected { tags, match_all } => AppInput::TagsSelected { tags, match_all },
+                SidebarOutput::FavoritesSelected => AppInput::ShowFavorites,
+                SidebarOutput::AllEntriesSelected => AppInput::ShowAllEntries,
+                SidebarOutput::RequestCheckIntegrity => AppInput::CheckIntegrity,
+                SidebarOutput::RequestFindDuplicates => AppInput::FindDuplicates,
+                SidebarOutput::RequestFindReusedPasswords => AppInput::FindReusedPasswords,
+                SidebarOutput::RequestPasswordAudit => AppInput::PasswordAudit,
+                SidebarOutput::RequestSaveCopy => AppInput::SaveCopy,
+                SidebarOutput::RequestExportCsv => AppInput::ExportCsv,
+                SidebarOutput::RequestExportJson => AppInput::ExportJson,
+                SidebarOutput::RequestChangeMasterPassword => AppInput::ChangeMasterPassword,
+                SidebarOutput::RequestImport => AppInput::ImportEntries,
+                SidebarOutput::RequestRevealDatabase => AppInput::RevealDatabase,
+                SidebarOutput::RequestRevealAttachment => AppInput::RevealSavedAttachment,
             });
 
         let entry_browser = EntryBrowser::builder()
-            .launch((config.show_entropy_bar, config.show_totp_visible))
+            .launch(EntryBrowserInit {
+                show_entropy_bar: config.show_entropy_bar,
+                show_totp_visible: config.show_totp_visible,
+                datetime_format: config.datetime_format.clone(),
+                render_notes_markdown: config.render_notes_markdown,
+                density: config.entry_list_density,
+                column_min_width: config.column_min_width,
+                read_only: config.read_only,
+            })
             .forward(sender.input_sender(), |output| match output {
                 EntryBrowserOutput::EntryEdited(entry) => AppInput::EntrySaved(entry),
                 EntryBrowserOutput::DeleteEntry(uuid) => AppInput::DeleteEntry(uuid),
 
                 EntryBrowserOutput::AddEntry => AppInput::AddEntry,
+                EntryBrowserOutput::DuplicateEntry(uuid) => AppInput::DuplicateEntry(uuid),
                 EntryBrowserOutput::SaveAttachment { filename, data } => AppInput::SaveAttachment { filename, data },
                 EntryBrowserOutput::OpenAttachment { filename, data } => AppInput::OpenAttachment { filename, data },
+                EntryBrowserOutput::AddAttachment(uuid) => AppInput::AddAttachment(uuid),
                 EntryBrowserOutput::RequestPermanentDeleteEntry(uuid) => AppInput::VerifyPermanentDeleteEntry(uuid),
                 EntryBrowserOutput::RestoreEntry(uuid) => AppInput::RestoreEntry(uuid),
+                EntryBrowserOutput::Notify(message, kind) => AppInput::Notify(message, kind),
+                EntryBrowserOutput::ExportEntries(uuids) => AppInput::ExportEntries(uuids),
+                EntryBrowserOutput::MoveEntries(uuids) => AppInput::MoveEntries(uuids),
+                EntryBrowserOutput::ReorderEntry { group_uuid, entry_uuid, new_index } => {
+                    AppInput::ReorderEntry { group_uuid, entry_uuid, new_index }
+                }
+                EntryBrowserOutput::ShowHistory(uuid) => AppInput::ShowEntryHistory(uuid),
+                EntryBrowserOutput::RestoreAllEntries => AppInput::RestoreAllEntries,
+                EntryBrowserOutput::EmptyRecycleBin(uuid) => AppInput::EmptyRecycleBin(uuid),
+                EntryBrowserOutput::GroupSelected(uuid) => AppInput::BreadcrumbGroupSelected(uuid),
             });
 
         let entry_edit = EntryEdit::builder()
-            .launch(())
+            .launch(config.suggest_title_from_url)
             .forward(sender.input_sender(), |output| match output {
                 EntryEditOutput::Saved(entry) => AppInput::EntrySaved(entry),
-                EntryEditOutput::Cancelled => AppInput::SaveDatabase, // No-op trigger
+                EntryEditOutput::Created { entry, group_uuid } => AppInput::EntryCreated { entry, group_uuid },
+                EntryEditOutput::ChooseGroup => AppInput::ChooseNewEntryGroup,
+                EntryEditOutput::Cancelled => AppInput::AutoSave, // No-op trigger
             });
 
         let group_edit = GroupEdit::builder()
@@ -273,18 +595,78 @@ impl Component for App {
             });
 
         let info_bar = InfoBar::builder()
-            .launch(())
+            .launch(InfoBarInit {
+                manual_save: !config.auto_save,
+                save_shortcut: config.keybindings.save_database.clone(),
+                read_only: config.read_only,
+            })
             .detach();
 
         let password_confirmation = PasswordConfirmation::builder()
             .launch(())
             .forward(sender.input_sender(), |output| match output {
+                PasswordConfirmationOutput::Confirmed { password, action_id } if action_id == EXPORT_CSV_ACTION => {
+                    AppInput::ExportCsvConfirmed(password)
+                }
+                PasswordConfirmationOutput::Confirmed { password, action_id } if action_id == EXPORT_JSON_ACTION => {
+                    AppInput::ExportJsonConfirmed(password)
+                }
+                PasswordConfirmationOutput::Confirmed { password, action_id } if action_id == EXPORT_ENTRIES_ACTION => {
+                    AppInput::ExportEntriesConfirmed(password)
+                }
                 PasswordConfirmationOutput::Confirmed { password, action_id } => {
                      AppInput::PermanentDeleteConfirmed { password, action_id }
                 }
                 PasswordConfirmationOutput::Cancelled => AppInput::NoOp,
             });
 
+        let new_password = NewPasswordDialog::builder()
+            .launch(())
+            .forward(sender.input_sender(), |output| match output {
+                NewPasswordOutput::Chosen(password) => AppInput::NewPasswordChosen(password),
+                NewPasswordOutput::Cancelled => AppInput::NewPasswordCancelled,
+            });
+
+        let import_preview = ImportPreviewDialog::builder()
+            .launch(())
+            .forward(sender.input_sender(), |output| match output {
+                ImportPreviewOutput::Confirmed => AppInput::ImportConfirmed,
+                ImportPreviewOutput::Cancelled => AppInput::ImportCancelled,
+            });
+
+        let group_picker = GroupPicker::builder()
+            .launch(())
+            .forward(sender.input_sender(), |output| match output {
+                GroupPickerOutput::Picked(uuid) => AppInput::GroupPicked(uuid),
+                GroupPickerOutput::Cancelled => AppInput::NoOp,
+            });
+
+        let entry_history = EntryHistory::builder()
+            .launch(config.datetime_format.clone())
+            .forward(sender.input_sender(), |output| match output {
+                EntryHistoryOutput::Restore { uuid, index } => AppInput::RestoreEntryVersion { uuid, index },
+            });
+
+        let entry_compare = EntryCompare::builder()
+            .launch(config.datetime_format.clone())
+            .forward(sender.input_sender(), |output| match output {
+                EntryCompareOutput::Discard(uuid) => AppInput::DuplicateDiscarded(uuid),
+                EntryCompareOutput::KeepBoth => AppInput::CompareNextDuplicate,
+                EntryCompareOutput::Cancelled => AppInput::DuplicatesCancelled,
+            });
+
+        let toast = Toast::builder()
+            .launch(())
+            .detach();
+
+        let unsaved_changes_dialog = UnsavedChangesDialog::builder()
+            .launch(())
+            .forward(sender.input_sender(), |output| match output {
+                UnsavedChangesOutput::Save => AppInput::SaveAndContinue,
+                UnsavedChangesOutput::Discard => AppInput::DiscardAndContinue,
+                UnsavedChangesOutput::Cancelled => AppInput::CancelPendingAction,
+            });
+
         let mut model = App {
             state: AppState::Locked,
             config,
@@ -297,6 +679,19 @@ impl Component for App {
             last_save_time: String::new(),
             current_group_uuid: None,
             root_group: None,
+            virtual_view: None,
+            allow_close: Rc::new(Cell::new(false)),
+            last_activity: Rc::new(Cell::new(std::time::Instant::now())),
+            continue_after_save: false,
+            save_again: false,
+            pending_action: None,
+            pending_move: Vec::new(),
+            picking_new_entry_group: false,
+            pending_copy_path: None,
+            pending_import: None,
+            pending_export: Vec::new(),
+            pending_duplicates: Vec::new(),
+            last_saved_attachment: None,
             unlock,
             search_palette,
             sidebar,
@@ -306,6 +701,13 @@ impl Component for App {
 
             info_bar,
             password_confirmation,
+            new_password,
+            import_preview,
+            group_picker,
+            entry_history,
+            entry_compare,
+            toast,
+            unsaved_changes_dialog,
         };
         
         // Auto-unlock in dev mode
@@ -314,9 +716,10 @@ impl Component for App {
             // Load .env.dev if it exists
             let _ = dotenvy::from_filename(".env.dev");
             
-            if let Ok(password) = std::env::var("DB_PASSWORD") {
+            if let Ok(password) = std::env::var("DB_PASSWORD").map(Zeroizing::new) {
                 tracing::info!("Found DB_PASSWORD in env, attempting auto-unlock");
-                match KeepassDatabase::unlock(&model.config.database_path, &password) {
+                let key_file = std::env::var_os("DB_KEYFILE").map(std::path::PathBuf::from);
+                match KeepassDatabase::unlock_with_keyfile(&model.config.database_path, Some(&password), key_file.as_deref()) {
                     Ok(db) => {
                          let root = db.root_group();
                          model.root_group = Some(root.clone());
@@ -329,7 +732,7 @@ impl Component for App {
                             .map(|s| s.to_string());
                             
                          model.db_size = std::fs::metadata(&model.config.database_path)
-                            .map(|m| format_size(m.len()))
+                            .map(|m| common::format_size(m.len()))
                             .unwrap_or_else(|_| "Unknown".to_string());
                          
                          // We need to send these signals *after* widgets are created, 
@@ -411,7 +814,11 @@ impl Component for App {
         
         let sender_clone = sender.clone();
         let save_binding = model.config.keybindings.save_database.clone();
+        let lock_binding = model.config.keybindings.lock_database.clone();
         let search_binding = model.config.keybindings.toggle_search.clone();
+        let back_binding = model.config.keybindings.navigate_back.clone();
+        let forward_binding = model.config.keybindings.navigate_forward.clone();
+        let window = widgets._main_window.clone();
         
         key_controller.connect_key_pressed(move |_, key, _keycode, state| {
             // Check for Save Database shortcut
@@ -419,16 +826,101 @@ impl Component for App {
                 sender_clone.input(AppInput::SaveDatabase);
                 return gtk4::glib::Propagation::Stop;
             }
+            if crate::config::Keybindings::matches(&lock_binding, key, state) {
+                sender_clone.input(AppInput::Lock);
+                return gtk4::glib::Propagation::Stop;
+            }
             // Check for Toggle Search shortcut
             if crate::config::Keybindings::matches(&search_binding, key, state) {
                 sender_clone.input(AppInput::ToggleSearch);
                 return gtk4::glib::Propagation::Stop;
             }
+            // Breadcrumb navigation; a bare Backspace only when it wouldn't edit text
+            let typing = gtk4::prelude::RootExt::focus(&window)
+                .is_some_and(|w| w.is::<gtk4::Text>() || w.is::<gtk4::TextView>());
+            let plain_backspace = key == gtk4::gdk::Key::BackSpace
+                && !state.intersects(gtk4::gdk::ModifierType::CONTROL_MASK | gtk4::gdk::ModifierType::ALT_MASK)
+                && !typing;
+            if plain_backspace || crate::config::Keybindings::matches(&back_binding, key, state) {
+                sender_clone.input(AppInput::NavigateBack);
+                return gtk4::glib::Propagation::Stop;
+            }
+            if crate::config::Keybindings::matches(&forward_binding, key, state) {
+                sender_clone.input(AppInput::NavigateForward);
+                return gtk4::glib::Propagation::Stop;
+            }
             gtk4::glib::Propagation::Proceed
         });
         
         widgets._main_window.add_controller(key_controller);
 
+        // Intercept window close so unsaved changes are not silently lost
+        let allow_close = model.allow_close.clone();
+        let sender_clone = sender.clone();
+        widgets._main_window.connect_close_request(move |_| {
+            if allow_close.get() {
+                gtk4::glib::Propagation::Proceed
+            } else {
+                sender_clone.input(AppInput::CloseRequested);
+                gtk4::glib::Propagation::Stop
+            }
+        });
+
+        // Files forwarded by a second invocation (or the file manager) once we're running
+        let sender_clone = sender.clone();
+        relm4::main_application().connect_open(move |app, files, _hint| {
+            match files.first().and_then(|file| file.path()) {
+                Some(path) => sender_clone.input(AppInput::OpenFileRequested(path)),
+                None => tracing::warn!("Ignoring request to open a non-local file"),
+            }
+            app.activate();
+        });
+
+        // Lock or mask when the application loses focus
+        if model.config.lock_on_focus_loss {
+            let sender_clone = sender.clone();
+            widgets._main_window.connect_is_active_notify(move |window| {
+                if window.is_active() {
+                    return;
+                }
+                // Focus may just be moving to one of our dialogs; check again once it settled
+                let sender_clone = sender_clone.clone();
+                gtk4::glib::timeout_add_local_once(std::time::Duration::from_millis(200), move || {
+                    let any_active = gtk4::Window::list_toplevels()
+                        .iter()
+                        .filter_map(|w| w.downcast_ref::<gtk4::Window>())
+                        .any(|w| w.is_active());
+                    if !any_active {
+                        sender_clone.input(AppInput::FocusLost);
+                    }
+                });
+            });
+        }
+
+        // Auto-lock after a period without input
+        if model.config.auto_lock_minutes > 0 {
+            let activity_controller = gtk4::EventControllerLegacy::new();
+            activity_controller.set_propagation_phase(gtk4::PropagationPhase::Capture);
+            let last_activity = model.last_activity.clone();
+            activity_controller.connect_event(move |_, event| {
+                use gtk4::gdk::EventType;
+                if matches!(
+                    event.event_type(),
+                    EventType::KeyPress | EventType::ButtonPress | EventType::MotionNotify | EventType::Scroll | EventType::TouchBegin
+                ) {
+                    last_activity.set(std::time::Instant::now());
+                }
+                gtk4::glib::Propagation::Proceed
+            });
+            widgets._main_window.add_controller(activity_controller);
+
+            let sender_clone = sender.clone();
+            gtk4::glib::timeout_add_seconds_local(15, move || {
+                sender_clone.input(AppInput::CheckIdle);
+                gtk4::glib::ControlFlow::Continue
+            });
+        }
+
         // Set initial sidebar width from config
         widgets._main_paned.set_position(model.config.sidebar_initial_width);
         tracing::info!("Set main_paned position to: {}", model.config.sidebar_initial_width);
@@ -445,6 +937,12 @@ impl Component for App {
 
         model.group_edit.widget().set_transient_for(Some(&widgets._main_window));
         model.password_confirmation.widget().set_transient_for(Some(&widgets._main_window));
+        model.new_password.widget().set_transient_for(Some(&widgets._main_window));
+        model.import_preview.widget().set_transient_for(Some(&widgets._main_window));
+        model.group_picker.widget().set_transient_for(Some(&widgets._main_window));
+        model.entry_history.widget().set_transient_for(Some(&widgets._main_window));
+        model.entry_compare.widget().set_transient_for(Some(&widgets._main_window));
+        model.unsaved_changes_dialog.widget().set_transient_for(Some(&widgets._main_window));
 
         ComponentParts { model, widgets }
     }
@@ -456,62 +954,79 @@ impl Component for App {
         sender: ComponentSender<Self>,
         _root: &Self::Root,
     ) {
+        if !matches!(message, AppInput::CheckIdle) {
+            self.last_activity.set(std::time::Instant::now());
+        }
+        if self.config.read_only && message.modifies_database() {
+            tracing::debug!("Refusing a change to the read-only database");
+            sender.input(AppInput::Notify("The database is open read-only".to_string(), NotificationKind::Info));
+            return;
+        }
         match message {
-            AppInput::PasswordSubmitted(password) => {
-                // Attempt to unlock database
-                match KeepassDatabase::unlock(&self.config.database_path, &password) {
+            AppInput::PasswordSubmitted { password, key_file } => {
+                // An empty password next to a key file means the database has no password
+                let password = (!password.is_empty() || key_file.is_none()).then_some(password.as_str());
+                match KeepassDatabase::unlock_with_keyfile(&self.config.database_path, password, key_file.as_deref()) {
+                    Ok(db) => self.open_database(db, widgets, &sender),
+                    Err(e) => {
+                        self.unlock.emit(UnlockInput::ShowError(format!("Failed to unlock: {:#}", e)));
+                    }
+                }
+            }
+            AppInput::CreateDatabase(password) => {
+                match KeepassDatabase::create(&self.config.database_path, &password) {
                     Ok(db) => {
-                        let root = db.root_group();
-                        self.root_group = Some(root.clone());
-                        self.database = Some(Arc::new(RwLock::new(db)));
-                        self.state = AppState::Unlocked;
-                        self.entry_count = count_entries(&root);
-                        self.db_filename = std::path::Path::new(&self.config.database_path)
-                            .file_name()
-                            .and_then(|n| n.to_str())
-                            .map(|s| s.to_string());
-                            
-                        self.db_size = std::fs::metadata(&self.config.database_path)
-                            .map(|m| format_size(m.len()))
-                            .unwrap_or_else(|_| "Unknown".to_string());
-                        
-                        // Sync initial state to info bar
-                        self.info_bar.emit(InfoBarInput::SetFilename(self.db_filename.clone()));
-                        self.info_bar.emit(InfoBarInput::SetFullPath(self.config.database_path.display().to_string()));
-                        self.info_bar.emit(InfoBarInput::SetEntryCount(self.entry_count));
-                        self.info_bar.emit(InfoBarInput::SetDbSize(self.db_size.clone()));
-                            
-                        // Populate sidebar and search
-                        self.sidebar.emit(SidebarInput::SetRootGroup(root.clone()));
-                        self.search_palette.emit(SearchPaletteInput::SetRootGroup(root.clone()));
-
-                        // Set root group in entry browser
-                        self.entry_browser.emit(EntryBrowserInput::SetRootGroup(root.clone()));
-
-                        // Switch to main view
-                        widgets._main_stack.set_visible_child_name("main");
+                        tracing::info!("Created database at {}", self.config.database_path.display());
+                        self.open_database(db, widgets, &sender);
+                        sender.input(AppInput::Notify("Database created".to_string(), NotificationKind::Success));
                     }
                     Err(e) => {
-                        self.unlock.emit(UnlockInput::ShowError(format!("Failed to unlock: {:#}", e)));
+                        self.unlock.emit(UnlockInput::ShowError(format!("Failed to create database: {:#}", e)));
                     }
                 }
             }
+            AppInput::ChooseDatabase => {
+                let file_chooser = gtk4::FileChooserNative::new(
+                    Some("Open Database"),
+                    Some(&widgets._main_window),
+                    gtk4::FileChooserAction::Open,
+                    Some("Open"),
+                    Some("Cancel"),
+                );
+                let filter = gtk4::FileFilter::new();
+                filter.set_name(Some("KeePass Databases"));
+                filter.add_pattern("*.kdbx");
+                file_chooser.add_filter(&filter);
+
+                let sender_clone = sender.clone();
+                file_chooser.connect_response(move |dialog, response| {
+                    if response == gtk4::ResponseType::Accept {
+                        if let Some(path) = dialog.file().and_then(|f| f.path()) {
+                            sender_clone.input(AppInput::DatabaseChosen(path));
+                        }
+                    }
+                    dialog.destroy();
+                });
+
+                file_chooser.show();
+            }
+            AppInput::DatabaseChosen(path) => {
+                tracing::info!("Database path changed to {}", path.display());
+                self.config.database_path = path.clone();
+                self.unlock.emit(UnlockInput::SetDatabasePath(path, false));
+            }
 
             AppInput::ToggleSearch => {
                 self.search_palette.emit(SearchPaletteInput::Toggle);
             }
             AppInput::GroupSelected(uuid) => {
                 self.current_group_uuid = Some(uuid.clone());
+                self.clear_virtual_view();
 
                 // Find the group and show its entries in entry browser
                 if let Some(ref root) = self.root_group {
                     if let Some(group) = find_group_by_uuid(root, &uuid) {
-                        // Check if in recycle bin
-                        let in_trash = group.is_recycle_bin || if let Some(ref db) = self.database {
-                             if let Ok(db) = db.read() {
-                                 db.is_inside_recycle_bin(&group.uuid)
-                             } else { false }
-                        } else { false };
+                        let in_trash = self.is_in_trash(group);
 
                         self.entry_browser.emit(EntryBrowserInput::SelectGroup {
                             uuid: uuid.clone(),
@@ -531,8 +1046,13 @@ impl Component for App {
                     }
                 }
             }
+            AppInput::BreadcrumbGroupSelected(uuid) => {
+                self.sidebar.emit(SidebarInput::UpdateSelection(uuid.clone()));
+                sender.input(AppInput::GroupSelected(uuid));
+            }
             AppInput::SearchGroupSelected { uuid, name, group } => {
                 self.current_group_uuid = Some(uuid.clone());
+                self.clear_virtual_view();
                 // Highlight in sidebar
                 self.sidebar.emit(SidebarInput::UpdateSelection(uuid.clone()));
                 
@@ -541,18 +1061,14 @@ impl Component for App {
             AppInput::SearchEntrySelected { entry, group_uuid } => {
                 // Select the group first, then the entry
                 self.current_group_uuid = Some(group_uuid.clone());
+                self.clear_virtual_view();
                 
                 // Highlight in sidebar
                 self.sidebar.emit(SidebarInput::UpdateSelection(group_uuid.clone()));
                 
                 if let Some(ref root) = self.root_group {
                     if let Some(group) = find_group_by_uuid(root, &group_uuid) {
-                        // Check if in recycle bin
-                        let in_trash = group.is_recycle_bin || if let Some(ref db) = self.database {
-                             if let Ok(db) = db.read() {
-                                 db.is_inside_recycle_bin(&group.uuid)
-                             } else { false }
-                        } else { false };
+                        let in_trash = self.is_in_trash(group);
 
                         self.entry_browser.emit(EntryBrowserInput::SelectGroup {
                             uuid: group_uuid.clone(),
@@ -577,6 +1093,38 @@ impl Component for App {
                     }
                 }
             }
+            AppInput::RefreshTree => {
+                let Some(root) = self.database.as_ref().and_then(|db| db.read().ok()).map(|db| db.root_group()) else {
+                    return;
+                };
+                self.entry_count = count_entries(&root);
+                self.info_bar.emit(InfoBarInput::SetEntryCount(self.entry_count));
+                self.sidebar.emit(SidebarInput::SetRootGroup(root.clone()));
+                self.search_palette.emit(SearchPaletteInput::SetRootGroup(root.clone()));
+                self.entry_browser.emit(EntryBrowserInput::SetRootGroup(root.clone()));
+
+                if self.virtual_view.is_some() {
+                    self.root_group = Some(root);
+                    self.show_virtual_view();
+                    return;
+                }
+                match self.current_group_uuid.as_ref().and_then(|uuid| find_group_by_uuid(&root, uuid)) {
+                    Some(group) => {
+                        self.entry_browser.emit(EntryBrowserInput::RefreshGroup {
+                            uuid: group.uuid.clone(),
+                            name: group.name.clone(),
+                            group: group.clone(),
+                        });
+                        self.entry_browser.emit(EntryBrowserInput::SetTrashMode(self.is_in_trash(group)));
+                    }
+                    None => {
+                        // The listed group is gone: fall back to the root
+                        self.sidebar.emit(SidebarInput::UpdateSelection(root.uuid.clone()));
+                        sender.input(AppInput::GroupSelected(root.uuid.clone()));
+                    }
+                }
+                self.root_group = Some(root);
+            }
             AppInput::EditEntry(entry) => {
                 tracing::info!("Opening edit dialog for entry: {}", entry.title);
                 self.entry_edit.emit(EntryEditInput::Edit(entry));
@@ -587,23 +1135,12 @@ impl Component for App {
                     if let Ok(mut db) = db.write() {
                         if let Err(e) = db.delete_entry(&uuid) {
                              tracing::error!("Failed to delete entry: {}", e);
-                             // TODO: Show error dialog
+                             sender.input(AppInput::Notify(format!("Failed to delete entry: {}", e), NotificationKind::Error));
                         } else {
-                             // Refresh
-                             let root = db.root_group().clone();
-                             self.root_group = Some(root.clone());
                              self.unsaved_changes = true;
                              self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
-                             
-                             self.sidebar.emit(SidebarInput::SetRootGroup(root.clone()));
-                             self.search_palette.emit(SearchPaletteInput::SetRootGroup(root.clone()));
-                             
-                             // If the deleted entry was selected, deselect it?
-                             // Or just select parent group?
-                             if let Some(ref group_uuid) = self.current_group_uuid {
-                                 sender.input(AppInput::GroupSelected(group_uuid.clone()));
-                             }
-                             sender.input(AppInput::SaveDatabase);
+                             sender.input(AppInput::RefreshTree);
+                             sender.input(AppInput::AutoSave);
                         }
                     }
                 }
@@ -614,59 +1151,40 @@ impl Component for App {
                     if let Ok(mut db) = db.write() {
                         if let Err(e) = db.delete_group(&uuid) {
                              tracing::error!("Failed to delete group: {}", e);
-                             // TODO: Show error dialog
+                             sender.input(AppInput::Notify(format!("Failed to delete group: {}", e), NotificationKind::Error));
                         } else {
-                             // Refresh
-                             let root = db.root_group().clone();
-                             self.root_group = Some(root.clone());
+                             // The group now sits in the recycle bin; leave it for the root
+                             if self.current_group_uuid.as_ref() == Some(&uuid) {
+                                 self.current_group_uuid = None;
+                             }
                              self.unsaved_changes = true;
                              self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
-                             
-                             self.sidebar.emit(SidebarInput::SetRootGroup(root.clone()));
-                             self.search_palette.emit(SearchPaletteInput::SetRootGroup(root.clone()));
-
-                             // If the deleted group was selected, navigate to root or parent?
-                             // Since we don't track parent easily here without finding it first,
-                             // let's just go to root for now if the deleted group was the active one.
-                             let mut switching_away = false;
-                             if let Some(ref current) = self.current_group_uuid {
-                                 if current == &uuid {
-                                     switching_away = true;
-                                 }
-                             }
-                             
-                             if switching_away {
-                                 // Select root
-                                 self.current_group_uuid = Some(root.uuid.clone());
-                                 sender.input(AppInput::GroupSelected(root.uuid.clone()));
-                             } else {
-                                 // Re-select current to refresh view?
-                                 if let Some(ref current) = self.current_group_uuid {
-                                     sender.input(AppInput::GroupSelected(current.clone()));
-                                 }
-                             }
-                             sender.input(AppInput::SaveDatabase);
+                             sender.input(AppInput::RefreshTree);
+                             sender.input(AppInput::AutoSave);
                         }
                     }
                 }
             }
             AppInput::EmptyRecycleBin(_uuid) => {
-                 // Prompt for password
+                 // Always confirm, with the password if configured
                  self.password_confirmation.emit(PasswordConfirmationInput::Show {
                      message: "This will permanently delete all items in the Recycle Bin. This action cannot be undone.".to_string(),
                      action_id: "empty_recycle_bin".to_string(),
+                     require_password: self.config.confirm_permanent_delete_with_password,
                  });
             }
             AppInput::VerifyPermanentDeleteGroup(uuid) => {
                   self.password_confirmation.emit(PasswordConfirmationInput::Show {
                      message: "This will permanently delete this group and all its contents. This action cannot be undone.".to_string(),
                      action_id: format!("delete_group_perm:{}", uuid),
+                     require_password: self.config.confirm_permanent_delete_with_password,
                  });
             }
             AppInput::VerifyPermanentDeleteEntry(uuid) => {
                 self.password_confirmation.emit(PasswordConfirmationInput::Show {
                     message: "This will permanently delete this entry. This action cannot be undone.".to_string(),
                     action_id: format!("delete_entry_perm:{}", uuid),
+                    require_password: self.config.confirm_permanent_delete_with_password,
                 });
             }
             AppInput::RestoreEntry(uuid) => {
@@ -675,46 +1193,73 @@ impl Component for App {
                      if let Ok(mut db) = db.write() {
                          if let Err(e) = db.restore_entry(&uuid) {
                               tracing::error!("Failed to restore entry: {}", e);
-                              // TODO: Show error
+                              sender.input(AppInput::Notify(format!("Failed to restore entry: {}", e), NotificationKind::Error));
                          } else {
                               tracing::info!("Entry restored");
-                              // Refresh
-                              let root = db.root_group().clone();
-                              self.root_group = Some(root.clone());
+                              sender.input(AppInput::Notify("Entry restored".to_string(), NotificationKind::Success));
                               self.unsaved_changes = true;
                               self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
-                              
-                              self.sidebar.emit(SidebarInput::SetRootGroup(root.clone()));
-                              self.search_palette.emit(SearchPaletteInput::SetRootGroup(root.clone()));
-                              
-                              // Select the recycle bin again to refresh list
-                              if let Some(bin_uuid) = db.get_recycle_bin_uuid() {
-                                   if let Some(group) = find_group_by_uuid(&root, &bin_uuid) {
-                                       if let Some(current_uuid) = &self.current_group_uuid {
-                                            if let Some(current_group) = find_group_by_uuid(&root, current_uuid) {
-                                                self.entry_browser.emit(EntryBrowserInput::SelectGroup { 
-                                                    uuid: current_uuid.clone(),
-                                                    name: current_group.name.clone(),
-                                                    group: current_group.clone()
-                                                });
-                                                
-                                                let in_trash = db.is_inside_recycle_bin(current_uuid);
-                                                self.entry_browser.emit(EntryBrowserInput::SetTrashMode(in_trash));
-                                            }
-                                       }
-                                   }
-                              }
+                              sender.input(AppInput::RefreshTree);
+                              sender.input(AppInput::AutoSave);
                          }
                      }
                  }
             }
-            AppInput::PermanentDeleteConfirmed { password, action_id } => {
-                // Verify password matches DB
-                // We use KeepassDatabase::unlock to verify
-                let verified = match KeepassDatabase::unlock(&self.config.database_path, &password) {
-                     Ok(_) => true,
-                     Err(_) => false,
+            AppInput::RestoreGroup(uuid) => {
+                let Some(ref db) = self.database else {
+                    return;
+                };
+                let Ok(mut db) = db.write() else {
+                    return;
+                };
+
+                if let Err(e) = db.restore_group(&uuid) {
+                    tracing::error!("Failed to restore group: {:#}", e);
+                    sender.input(AppInput::Notify(format!("Failed to restore group: {:#}", e), NotificationKind::Error));
+                    return;
+                }
+                tracing::info!("Group {} restored", uuid);
+
+                self.unsaved_changes = true;
+                self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
+                sender.input(AppInput::RefreshTree);
+                sender.input(AppInput::Notify("Group restored".to_string(), NotificationKind::Success));
+                sender.input(AppInput::AutoSave);
+            }
+            AppInput::RestoreAllEntries => {
+                let Some(ref db) = self.database else {
+                    return;
                 };
+                let Ok(mut db) = db.write() else {
+                    return;
+                };
+
+                match db.restore_all_entries() {
+                    Ok(0) => {
+                        sender.input(AppInput::Notify("Nothing to restore".to_string(), NotificationKind::Info));
+                    }
+                    Ok(count) => {
+                        tracing::info!("Restored {} entries from the Recycle Bin", count);
+                        self.unsaved_changes = true;
+                        self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
+                        sender.input(AppInput::RefreshTree);
+                        sender.input(AppInput::Notify(format!("Restored {} entries", count), NotificationKind::Success));
+                        sender.input(AppInput::AutoSave);
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to restore entries: {:#}", e);
+                        sender.input(AppInput::Notify(format!("Failed to restore entries: {:#}", e), NotificationKind::Error));
+                    }
+                }
+            }
+            AppInput::PermanentDeleteConfirmed { password, action_id } => {
+                // Verify password against the open database's key (unless disabled in config)
+                let verified = !self.config.confirm_permanent_delete_with_password
+                    || self
+                        .database
+                        .as_ref()
+                        .and_then(|db| db.read().ok().map(|db| db.verify_password(&password)))
+                        .unwrap_or(false);
 
                 if !verified {
                     self.password_confirmation.emit(PasswordConfirmationInput::ShowError("Incorrect password".to_string()));
@@ -738,54 +1283,135 @@ impl Component for App {
 
                         if let Err(e) = res {
                             tracing::error!("Failed to execute permanent delete: {}", e);
-                            // TODO: Show global error
+                            sender.input(AppInput::Notify(format!("Failed to delete permanently: {}", e), NotificationKind::Error));
                         } else {
                              // Success - refresh
                              tracing::info!("Permanent delete successful");
-                             let root = db.root_group().clone();
-                             self.root_group = Some(root.clone());
-                             
+                             // A deleted current group sends the view back to the root
                              self.unsaved_changes = true;
                              self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
-
-                             self.sidebar.emit(SidebarInput::SetRootGroup(root.clone()));
-                             self.search_palette.emit(SearchPaletteInput::SetRootGroup(root.clone()));
-
-                             // Re-navigate if needed
-                             if action_id == "empty_recycle_bin" {
-                                 // Usually we stay on bin
-                             } else {
-                                 // If deleted current group/entry, move up
-                                 // For now simple refresh to root selection or same selection if valid
-                                 // But since we refreshed root, ID matching handles it?
-                                 // If current group was deleted, `GroupSelected` logic might fail to find it.
-                                 // App handles this gracefully by not updating or showing empty?
-                             }
-
-                             if let Some(ref group_uuid) = self.current_group_uuid {
-                                  // Re-emit group selected to refresh view, or root if not found
-                                  if find_group_by_uuid(&root, group_uuid).is_some() {
-                                      sender.input(AppInput::GroupSelected(group_uuid.clone()));
-                                  } else {
-                                      // Deleted current group -> go to root
-                                      sender.input(AppInput::GroupSelected(root.uuid.clone()));
-                                  }
-                             }
-                             
-                             sender.input(AppInput::SaveDatabase);
+                             sender.input(AppInput::RefreshTree);
+                             sender.input(AppInput::AutoSave);
                         }
                     }
                 }
             }
             AppInput::AddEntry => {
-                if self.current_group_uuid.is_some() {
-                    self.entry_edit.emit(EntryEditInput::AddNew);
+                let Some(ref root) = self.root_group else {
+                    return;
+                };
+                let group_uuid = self.new_entry_group_uuid(root);
+                let group_path = root.group_path(&group_uuid).unwrap_or_default();
+                self.entry_edit.emit(EntryEditInput::AddNew { group_uuid, group_path });
+            }
+            AppInput::ChooseNewEntryGroup => {
+                let Some(ref root) = self.root_group else {
+                    return;
+                };
+                self.picking_new_entry_group = true;
+                self.group_picker.emit(GroupPickerInput::Show {
+                    root: root.clone(),
+                    title: "Add Entry To".to_string(),
+                    filter: Some(GroupFilter::new(|group| !group.is_recycle_bin)),
+                });
+            }
+            AppInput::EntryCreated { entry, group_uuid } => {
+                let Some(ref db) = self.database else {
+                    return;
+                };
+                let Ok(mut db) = db.write() else {
+                    return;
+                };
+
+                let new_uuid = match db.add_entry(&group_uuid, &entry) {
+                    Ok(new_uuid) => new_uuid,
+                    Err(e) => {
+                        tracing::error!("Failed to add entry: {:#}", e);
+                        sender.input(AppInput::Notify(format!("Failed to add entry: {:#}", e), NotificationKind::Error));
+                        return;
+                    }
+                };
+                tracing::info!("Added new entry with UUID: {}", new_uuid);
+
+                self.unsaved_changes = true;
+                self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
+                sender.input(AppInput::RefreshTree);
+
+                // Show the new entry in its group, wherever the user was
+                if let Some(entry) = db.find_entry(&new_uuid) {
+                    sender.input(AppInput::SearchEntrySelected { entry, group_uuid });
                 }
+                sender.input(AppInput::AutoSave);
+            }
+            AppInput::DuplicateEntry(uuid) => {
+                let Some(ref db) = self.database else {
+                    return;
+                };
+                let Ok(mut db) = db.write() else {
+                    return;
+                };
+
+                let new_uuid = match db.duplicate_entry(&uuid) {
+                    Ok(new_uuid) => new_uuid,
+                    Err(e) => {
+                        tracing::error!("Failed to duplicate entry: {:#}", e);
+                        sender.input(AppInput::Notify(format!("Failed to duplicate entry: {:#}", e), NotificationKind::Error));
+                        return;
+                    }
+                };
+                tracing::info!("Duplicated entry {} as {}", uuid, new_uuid);
+
+                self.unsaved_changes = true;
+                self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
+                sender.input(AppInput::RefreshTree);
+                // Show the copy, ready to be edited
+                sender.input(AppInput::SidebarEntrySelected(new_uuid));
+                sender.input(AppInput::AutoSave);
             }
             AppInput::AddGroup => {
-                if self.current_group_uuid.is_some() {
-                    self.group_edit.emit(GroupEditInput::AddNew);
+                if let Some(ref group_uuid) = self.current_group_uuid {
+                    let inherited_policy = self
+                        .root_group
+                        .as_ref()
+                        .and_then(|root| root.effective_password_policy(group_uuid))
+                        .map(str::to_string);
+                    self.group_edit.emit(GroupEditInput::AddNew { inherited_policy });
+                }
+            }
+            AppInput::EditGroup(uuid) => {
+                let Some(ref root) = self.root_group else {
+                    return;
+                };
+                let Some(ancestry) = root.group_ancestry(&uuid) else {
+                    return;
+                };
+                let Some((group, ancestors)) = ancestry.split_last() else {
+                    return;
+                };
+                let inherited_policy = ancestors
+                    .iter()
+                    .rev()
+                    .find_map(|g| g.password_policy.clone());
+                self.group_edit.emit(GroupEditInput::Edit { group: (*group).clone(), inherited_policy });
+            }
+            AppInput::GroupSaved(group) if !group.uuid.is_empty() => {
+                let Some(ref db) = self.database else {
+                    return;
+                };
+                let Ok(mut db) = db.write() else {
+                    return;
+                };
+                if let Err(e) = db.update_group(&group) {
+                    tracing::error!("Failed to update group: {:#}", e);
+                    sender.input(AppInput::Notify(format!("Failed to update folder: {:#}", e), NotificationKind::Error));
+                    return;
                 }
+                tracing::info!("Updated group {}", group.uuid);
+
+                self.unsaved_changes = true;
+                self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
+                sender.input(AppInput::RefreshTree);
+                sender.input(AppInput::AutoSave);
             }
             AppInput::GroupSaved(group) => {
                  if let Some(ref db) = self.database {
@@ -794,27 +1420,17 @@ impl Component for App {
                              match db.add_group(group_uuid, &group) {
                                  Ok(new_uuid) => {
                                      tracing::info!("Added new group with UUID: {}", new_uuid);
-                                     
-                                     // Refresh
-                                     let root = db.root_group().clone();
-                                     self.root_group = Some(root.clone());
+
+                                     // Refresh, then open the new group
                                      self.unsaved_changes = true;
                                      self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
-                                     
-                                     self.sidebar.emit(SidebarInput::SetRootGroup(root.clone()));
-                                     self.search_palette.emit(SearchPaletteInput::SetRootGroup(root.clone()));
-                                     // Re-select current group (parent) or the new group?
-                                     // Usually we select the NEW group.
-                                     
-                                     // Let's select the new group
-                                     if let Some(_new_group) = find_group_by_uuid(&root, &new_uuid) {
-                                          sender.input(AppInput::GroupSelected(new_uuid));
-                                     }
-                                     
-                                     sender.input(AppInput::SaveDatabase);
+                                     sender.input(AppInput::RefreshTree);
+                                     sender.input(AppInput::GroupSelected(new_uuid));
+                                     sender.input(AppInput::AutoSave);
                                  }
                                  Err(e) => {
                                      tracing::error!("Failed to add group: {}", e);
+                                     sender.input(AppInput::Notify(format!("Failed to add group: {}", e), NotificationKind::Error));
                                  }
                              }
                         }
@@ -823,123 +1439,62 @@ impl Component for App {
             }
             AppInput::EntrySaved(entry) => {
                 tracing::info!("Entry saved: {}", entry.title);
-                
-                let mut refreshed_root = None;
 
-                if let Some(ref db) = self.database {
-                    // Lock for writing
-                    if let Ok(mut db) = db.write() {
-                        // Update entry in database
-                        if let Err(e) = db.update_entry(&entry) {
-                            // If update failed, maybe it's a new entry? 
-                            // Try adding it if we have a current group.
-                             if e.to_string().contains("not found") {
-                                 if let Some(ref group_uuid) = self.current_group_uuid {
-                                     tracing::info!("Entry not found, attempting to add new entry to group {}", group_uuid);
-                                     match db.add_entry(group_uuid, &entry) {
-                                         Ok(new_uuid) => {
-                                             tracing::info!("Added new entry with UUID: {}", new_uuid);
-                                             // Update the entry object with the real UUID from DB
-                                             // This is needed so selection works
-                                             // But `entry` is immutable here.
-                                             // We need to re-assign `entry` or create a new scope.
-                                             // Actually we can just shadow it.
-                                             // But we can't shadow `entry` easily inside match arm.
-                                             // We will use a new variable for the final entry to select.
-                                             // Note: `add_entry` generates a NEW UUID. `entry.uuid` is ignored.
-                                             // We must propagate this new UUID to the view.
-                                             let mut new_entry = entry.clone();
-                                             new_entry.uuid = new_uuid;
-                                             
-                                             // Now we need to use `new_entry` for the rest of the logic
-                                             // We can't easily change `entry` binding.
-                                             // We'll duplicate the post-save logic here or refactor.
-                                             
-                                             // Refactored logic:
-                                             let root = db.root_group().clone();
-                                             let _root_clone = root.clone();
-                                             self.root_group = Some(root.clone());
-                                             
-                                             self.unsaved_changes = true;
-                                             self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
-                                             
-                                             // Critical: Notify components of the new data tree
-                                             self.sidebar.emit(SidebarInput::SetRootGroup(root.clone()));
-                                             self.search_palette.emit(SearchPaletteInput::SetRootGroup(root.clone()));
-                                             
-                                             // Also update EntryBrowser root if needed, or just let GroupSelected/SearchEntrySelected handle it?
-                                             // SearchEntrySelected usually calculates path and selects.
-                                             
-                                             sender.input(AppInput::SearchEntrySelected { 
-                                                 entry: new_entry, 
-                                                 group_uuid: group_uuid.clone() 
-                                             });
-                                             sender.input(AppInput::SaveDatabase);
-                                             return;
-                                         }
-                                         Err(err) => {
-                                             tracing::error!("Failed to add entry: {}", err);
-                                         }
-                                     }
-                                 } else {
-                                     tracing::error!("Cannot add new entry: no group selected");
-                                 }
-                             } else {
-                                 tracing::error!("Failed to update entry: {}", e);
-                             }
-                            // TODO: Show error dialog
-                            return;
-                        }
+                let Some(ref db) = self.database else {
+                    return;
+                };
+                let Ok(mut db) = db.write() else {
+                    return;
+                };
 
-                        // DO NOT auto-save to disk
-                        // if let Err(e) = db.save() { ... }
-                        
-                        tracing::info!("Entry updated in memory");
+                // An edit that changed nothing shouldn't mark the database dirty
+                if db.find_entry(&entry.uuid).is_some_and(|stored| stored.content_eq(&entry)) {
+                    tracing::debug!("Entry unchanged, nothing to save");
+                    return;
+                }
 
-                        // Refresh root group to reflect changes
-                        refreshed_root = Some(db.root_group().clone());
+                if let Err(e) = db.update_entry(&entry) {
+                    if !e.to_string().contains("not found") {
+                        tracing::error!("Failed to update entry: {}", e);
+                        sender.input(AppInput::Notify(format!("Failed to update entry: {}", e), NotificationKind::Error));
+                        return;
                     }
-                }
 
-                if let Some(root) = refreshed_root {
-                    self.root_group = Some(root.clone());
-                    
-                    // Critical: Notify components of changes
-                    self.sidebar.emit(SidebarInput::SetRootGroup(root.clone()));
-                    self.search_palette.emit(SearchPaletteInput::SetRootGroup(root.clone()));
+                    // Not in the database yet: add it to the current group
+                    let Some(group_uuid) = self.current_group_uuid.clone() else {
+                        tracing::error!("Cannot add new entry: no group selected");
+                        sender.input(AppInput::Notify("Cannot add entry: no group selected".to_string(), NotificationKind::Error));
+                        return;
+                    };
+                    tracing::info!("Entry not found, attempting to add new entry to group {}", group_uuid);
+                    match db.add_entry(&group_uuid, &entry) {
+                        Ok(new_uuid) => {
+                            tracing::info!("Added new entry with UUID: {}", new_uuid);
+                            // `add_entry` assigns a new UUID, which the view needs to select it
+                            let mut new_entry = entry;
+                            new_entry.uuid = new_uuid;
+                            self.unsaved_changes = true;
+                            self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
+                            sender.input(AppInput::RefreshTree);
+                            sender.input(AppInput::SearchEntrySelected { entry: new_entry, group_uuid });
+                            sender.input(AppInput::AutoSave);
+                        }
+                        Err(err) => {
+                            tracing::error!("Failed to add entry: {}", err);
+                            sender.input(AppInput::Notify(format!("Failed to add entry: {}", err), NotificationKind::Error));
+                        }
+                    }
+                    return;
                 }
-                
-                // Mark as unsaved
+
+                // Not written to disk here; `SaveDatabase` does that
+                tracing::info!("Entry updated in memory");
+
+                // The refresh keeps the entry selected, now with its new data
                 self.unsaved_changes = true;
                 self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
-                
-                // Update count? If we added/removed (not yet supported via this message), we'd need to recount.
-                // For safety, let's recount.
-                if let Some(ref _root) = self.root_group {
-                     // Wait, self.root_group might be stale if we don't reload it from DB?
-                     // keeprs-core keeps them in sync? 
-                     // Usually we need to reload the group tree from the DB or update the in-memory tree.
-                     // Assuming `db.update_entry` updates the in-memory structure referenced by `self.root_group`?
-                     // Actually `db.root_group()` returns a clone in `init` and `PasswordSubmitted`.
-                     // The `self.root_group` is a detached clone. `db` has its own copy.
-                     // We need to update `self.root_group` to reflect changes if we want the UI tree to update.
-                     // But strictly for *counting*, we can ask the DB.
-                     
-                     // For now, let's just assume count didn't change on EDIT.
-                     // On ADD/DELETE we will handle it elsewhere or recount.
-                }
-
-                // Refresh the view and re-select the entry
-                // (Only for updates)
-                if let Some(ref group_uuid) = self.current_group_uuid {
-                    sender.input(AppInput::SearchEntrySelected { 
-                        entry: entry.clone(), 
-                        group_uuid: group_uuid.clone() 
-                    });
-                }
-                
-                // Auto-save
-                sender.input(AppInput::SaveDatabase);
+                sender.input(AppInput::RefreshTree);
+                sender.input(AppInput::AutoSave);
             }
             AppInput::SaveAttachment { filename, data } => {
                 let file_chooser = gtk4::FileChooserNative::new(
@@ -952,16 +1507,21 @@ impl Component for App {
                 
                 file_chooser.set_current_name(&filename);
                 
+                let sender_clone = sender.clone();
                 file_chooser.connect_response(move |dialog, response| {
                     if response == gtk4::ResponseType::Accept {
                         if let Some(file) = dialog.file() {
                              if let Some(path) = file.path() {
                                  let data = data.clone();
+                                 let sender_clone = sender_clone.clone();
                                  std::thread::spawn(move || {
                                      if let Err(e) = std::fs::write(&path, data) {
                                          tracing::error!("Failed to save attachment to {}: {}", path.display(), e);
+                                         sender_clone.input(AppInput::Notify(format!("Failed to save attachment: {}", e), NotificationKind::Error));
                                      } else {
                                          tracing::info!("Saved attachment to {}", path.display());
+                                         sender_clone.input(AppInput::AttachmentSaved(path));
+                                         sender_clone.input(AppInput::Notify("Attachment saved".to_string(), NotificationKind::Success));
                                      }
                                  });
                              }
@@ -972,27 +1532,298 @@ impl Component for App {
                 
                 file_chooser.show();
             }
-            AppInput::OpenAttachment { filename, data } => {
-                std::thread::spawn(move || {
-                    let temp_dir = std::env::temp_dir();
-                    let path = temp_dir.join(&filename);
-                    
+            AppInput::ExportEntries(uuids) => {
+                self.pending_export = uuids;
+                self.password_confirmation.emit(PasswordConfirmationInput::Show {
+                    message: "The export will contain the selected passwords in plain text. Enter the master password to export.".to_string(),
+                    action_id: EXPORT_ENTRIES_ACTION.to_string(),
+                    require_password: true,
+                });
+            }
+            AppInput::ExportEntriesConfirmed(password) => {
+                let Some(db) = self.database.clone() else {
+                    return;
+                };
+                let verified = db.read().ok().is_some_and(|db| db.verify_password(&password));
+                if !verified {
+                    self.password_confirmation.emit(PasswordConfirmationInput::ShowError("Incorrect password".to_string()));
+                    return;
+                }
+                self.password_confirmation.emit(PasswordConfirmationInput::Cancel); // Close dialog
+                let uuids = std::mem::take(&mut self.pending_export);
+
+                let file_chooser = gtk4::FileChooserNative::new(
+                    Some("Export Selected Entries"),
+                    Some(&widgets._main_window),
+                    gtk4::FileChooserAction::Save,
+                    Some("Export"),
+                    Some("Cancel"),
+                );
+                file_chooser.set_current_name("export.json");
+
+                let sender_clone = sender.clone();
+                file_chooser.connect_response(move |dialog, response| {
+                    if response == gtk4::ResponseType::Accept {
+                        if let Some(path) = dialog.file().and_then(|f| f.path()) {
+                            let format = ExportFormat::from_path(&path);
+                            let exported = match db.read() {
+                                Ok(db) => db.export_entries(&uuids, format),
+                                Err(_) => Err(anyhow::anyhow!("Database lock poisoned")),
+                            };
+
+                            match exported {
+                                Ok(contents) => {
+                                    let sender_clone = sender_clone.clone();
+                                    std::thread::spawn(move || {
+                                        let written = create_export_file(&path).and_then(|mut file| {
+                                            file.write_all(contents.as_bytes())
+                                                .with_context(|| format!("Failed to write {}", path.display()))
+                                        });
+                                        if let Err(e) = written {
+                                            tracing::error!("Failed to write export to {}: {:#}", path.display(), e);
+                                            sender_clone.input(AppInput::Notify(format!("Export failed: {:#}", e), NotificationKind::Error));
+                                        } else {
+                                            tracing::info!("Exported entries to {}", path.display());
+                                            sender_clone.input(AppInput::Notify("Entries exported".to_string(), NotificationKind::Success));
+                                        }
+                                    });
+                                }
+                                Err(e) => {
+                                    tracing::error!("Failed to export entries: {:#}", e);
+                                    sender_clone.input(AppInput::Notify(format!("Export failed: {:#}", e), NotificationKind::Error));
+                                }
+                            }
+                        }
+                    }
+                    dialog.destroy();
+                });
+
+                file_chooser.show();
+            }
+            AppInput::ReorderEntry { group_uuid, entry_uuid, new_index } => {
+                let Some(ref db) = self.database else {
+                    return;
+                };
+                let Ok(mut db) = db.write() else {
+                    return;
+                };
+
+                if let Err(e) = db.reorder_entry(&group_uuid, &entry_uuid, new_index) {
+                    tracing::error!("Failed to reorder entry: {:#}", e);
+                    sender.input(AppInput::Notify(format!("Failed to move entry: {:#}", e), NotificationKind::Error));
+                    return;
+                }
+
+                self.unsaved_changes = true;
+                self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
+                sender.input(AppInput::RefreshTree);
+                sender.input(AppInput::AutoSave);
+            }
+            AppInput::MoveEntries(uuids) => {
+                let Some(ref root) = self.root_group else {
+                    return;
+                };
+                let title = if uuids.len() == 1 {
+                    "Move Entry To".to_string()
+                } else {
+                    format!("Move {} Entries To", uuids.len())
+                };
+                self.pending_move = uuids;
+                self.picking_new_entry_group = false;
+                self.group_picker.emit(GroupPickerInput::Show {
+                    root: root.clone(),
+                    title,
+                    filter: None,
+                });
+            }
+            AppInput::GroupPicked(target_uuid) => {
+                if std::mem::take(&mut self.picking_new_entry_group) {
+                    let path = self
+                        .root_group
+                        .as_ref()
+                        .and_then(|root| root.group_path(&target_uuid))
+                        .unwrap_or_default();
+                    self.entry_edit.emit(EntryEditInput::SetGroup { uuid: target_uuid, path });
+                    return;
+                }
+                let uuids = std::mem::take(&mut self.pending_move);
+                let Some(ref db) = self.database else {
+                    return;
+                };
+                let Ok(mut db) = db.write() else {
+                    return;
+                };
+
+                // Keep going on failures so one bad entry doesn't block the rest
+                let mut failed = Vec::new();
+                for uuid in &uuids {
+                    if let Err(e) = db.move_entry(uuid, &target_uuid) {
+                        tracing::error!("Failed to move entry {}: {:#}", uuid, e);
+                        failed.push(uuid);
+                    }
+                }
+                let moved = uuids.len() - failed.len();
+
+                if moved > 0 {
+                    self.unsaved_changes = true;
+                    self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
+                    sender.input(AppInput::RefreshTree);
+                    sender.input(AppInput::AutoSave);
+                }
+
+                if failed.is_empty() {
+                    sender.input(AppInput::Notify(format!("Moved {} entries", moved), NotificationKind::Success));
+                } else {
+                    sender.input(AppInput::Notify(
+                        format!("Moved {} entries, {} could not be moved", moved, failed.len()),
+                        NotificationKind::Error,
+                    ));
+                }
+            }
+            AppInput::ShowEntryHistory(uuid) => {
+                let Some(ref db) = self.database else {
+                    return;
+                };
+                let Ok(db) = db.read() else {
+                    return;
+                };
+                let Some(current) = db.find_entry(&uuid) else {
+                    return;
+                };
+
+                match db.entry_history(&uuid) {
+                    Ok(versions) if versions.is_empty() => {
+                        sender.input(AppInput::Notify("No previous versions".to_string(), NotificationKind::Info));
+                    }
+                    Ok(versions) => {
+                        self.entry_history.emit(EntryHistoryInput::Show { current, versions });
+                    }
+                    Err(e) => {
+                        sender.input(AppInput::Notify(format!("Failed to read history: {:#}", e), NotificationKind::Error));
+                    }
+                }
+            }
+            AppInput::RestoreEntryVersion { uuid, index } => {
+                let Some(ref db) = self.database else {
+                    return;
+                };
+                let Ok(mut db) = db.write() else {
+                    return;
+                };
+
+                if let Err(e) = db.restore_entry_version(&uuid, index) {
+                    tracing::error!("Failed to restore entry version: {:#}", e);
+                    sender.input(AppInput::Notify(format!("Failed to restore version: {:#}", e), NotificationKind::Error));
+                    return;
+                }
+
+                self.unsaved_changes = true;
+                self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
+                sender.input(AppInput::RefreshTree);
+
+                sender.input(AppInput::Notify("Version restored".to_string(), NotificationKind::Success));
+                sender.input(AppInput::AutoSave);
+            }
+            AppInput::AddAttachment(uuid) => {
+                let file_chooser = gtk4::FileChooserNative::new(
+                    Some("Add Attachment"),
+                    Some(&widgets._main_window),
+                    gtk4::FileChooserAction::Open,
+                    Some("Attach"),
+                    Some("Cancel"),
+                );
+
+                let max_bytes = self.config.max_attachment_bytes;
+                let sender_clone = sender.clone();
+                file_chooser.connect_response(move |dialog, response| {
+                    if response == gtk4::ResponseType::Accept {
+                        if let Some(path) = dialog.file().and_then(|f| f.path()) {
+                            let filename = path
+                                .file_name()
+                                .map(|name| name.to_string_lossy().into_owned())
+                                .unwrap_or_default();
+                            // Check the size first so huge files are never read into memory
+                            match std::fs::metadata(&path).map(|m| m.len()) {
+                                Ok(size) if max_bytes > 0 && size > max_bytes => {
+                                    sender_clone.input(AppInput::Notify(
+                                        format!(
+                                            "'{}' is {}, larger than the {} attachment limit",
+                                            filename,
+                                            common::format_size(size),
+                                            common::format_size(max_bytes)
+                                        ),
+                                        NotificationKind::Error,
+                                    ));
+                                }
+                                Ok(_) => match std::fs::read(&path) {
+                                    Ok(data) => sender_clone.input(AppInput::AttachmentChosen {
+                                        uuid: uuid.clone(),
+                                        filename,
+                                        data,
+                                    }),
+                                    Err(e) => sender_clone.input(AppInput::Notify(
+                                        format!("Failed to read file: {}", e),
+                                        NotificationKind::Error,
+                                    )),
+                                },
+                                Err(e) => sender_clone.input(AppInput::Notify(
+                                    format!("Failed to read file: {}", e),
+                                    NotificationKind::Error,
+                                )),
+                            }
+                        }
+                    }
+                    dialog.destroy();
+                });
+
+                file_chooser.show();
+            }
+            AppInput::AttachmentChosen { uuid, filename, data } => {
+                let Some(ref db) = self.database else {
+                    return;
+                };
+                let Ok(mut db) = db.write() else {
+                    return;
+                };
+
+                if let Err(e) = db.add_attachment(&uuid, &filename, data) {
+                    tracing::error!("Failed to add attachment: {:#}", e);
+                    sender.input(AppInput::Notify(format!("Failed to add attachment: {:#}", e), NotificationKind::Error));
+                    return;
+                }
+
+                self.unsaved_changes = true;
+                self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
+                sender.input(AppInput::RefreshTree);
+
+                sender.input(AppInput::Notify(format!("Attached '{}'", filename), NotificationKind::Success));
+                sender.input(AppInput::AutoSave);
+            }
+            AppInput::OpenAttachment { filename, data } => {
+                let sender_clone = sender.clone();
+                std::thread::spawn(move || {
+                    let temp_dir = std::env::temp_dir();
+                    let path = temp_dir.join(&filename);
+                    
                     if let Err(e) = std::fs::write(&path, data) {
                         tracing::error!("Failed to write temp file {}: {}", path.display(), e);
+                        sender_clone.input(AppInput::Notify(format!("Failed to open attachment: {}", e), NotificationKind::Error));
                         return;
                     }
                     
                     tracing::info!("Opening attachment: {}", path.display());
                     
-                    // Use xdg-open on Linux
-                    if let Err(e) = std::process::Command::new("xdg-open")
-                        .arg(&path)
-                        .spawn() 
-                    {
+                    if let Err(e) = crate::desktop::open(&path) {
                         tracing::error!("Failed to open file {}: {}", path.display(), e);
+                        sender_clone.input(AppInput::Notify(format!("Failed to open attachment: {}", e), NotificationKind::Error));
                     }
                 });
             }
+            AppInput::AutoSave => {
+                if self.config.auto_save {
+                    sender.input(AppInput::SaveDatabase);
+                }
+            }
             AppInput::SaveDatabase => {
                 if let Some(ref db) = self.database {
                     // Skip save if nothing has changed
@@ -1002,12 +1833,15 @@ impl Component for App {
                     }
                     
                     if self.is_saving {
-                        tracing::debug!("[SPINNER] SaveDatabase called but already saving, returning");
+                        tracing::debug!("SaveDatabase called while saving, saving again afterwards");
+                        self.save_again = true;
                         return;
                     }
                     
-                    tracing::info!("[SPINNER] Setting is_saving = true");
                     self.is_saving = true;
+                    // Changes made while the save runs set this again, so they aren't
+                    // mistaken for saved when it finishes
+                    self.unsaved_changes = false;
                     self.info_bar.emit(InfoBarInput::SetSaving(true));
                     
                     // Clone Arc for thread (cheap)
@@ -1015,8 +1849,8 @@ impl Component for App {
                     let sender_clone = sender.clone();
                     
                     std::thread::spawn(move || {
+                        let _span = tracing::info_span!("save").entered();
                         let start = std::time::Instant::now();
-                        tracing::info!("[SPINNER] Background thread started, beginning save...");
                         
                         // Lock for reading in the thread
                         let res = if let Ok(db) = db_arc.read() {
@@ -1026,50 +1860,60 @@ impl Component for App {
                         };
                         
                         let save_duration = start.elapsed();
-                        tracing::info!("[SPINNER] Save operation took {:?}", save_duration);
                         
                         // Enforce at least 500ms delay for better UI UX (optional, but requested "freeze for a second or so" -> "Saving..." spinner)
                         // User complained about FREEZE, not speed. But if it's too fast, spinner flicks.
                         let min_display_time = std::time::Duration::from_millis(500);
                         if save_duration < min_display_time {
                             let remaining = min_display_time - save_duration;
-                            tracing::info!("[SPINNER] Sleeping for additional {:?} to ensure spinner is visible", remaining);
+                            tracing::debug!(delay = ?remaining, "Keeping the saving spinner visible");
                             std::thread::sleep(remaining);
                         }
                         
-                        let total_duration = start.elapsed();
-                        tracing::info!("[SPINNER] Total save+delay took {:?}, sending SaveFinished", total_duration);
+                        tracing::debug!(
+                            save = ?save_duration,
+                            total = ?start.elapsed(),
+                            ok = res.is_ok(),
+                            "Background save finished"
+                        );
                         
                         sender_clone.input(AppInput::SaveFinished(res));
                     });
-                    
-                    tracing::info!("[SPINNER] SaveDatabase handler returning, is_saving = {}", self.is_saving);
                 }
             }
             AppInput::SaveFinished(result) => {
-                tracing::info!("[SPINNER] SaveFinished received, setting is_saving = false");
                 self.is_saving = false;
                 self.info_bar.emit(InfoBarInput::SetSaving(false));
+                let save_again = std::mem::take(&mut self.save_again);
                 match result {
                     Ok(_) => {
-                        self.unsaved_changes = false;
-                        self.info_bar.emit(InfoBarInput::SetUnsavedChanges(false));
+                        self.info_bar.emit(InfoBarInput::SetUnsavedChanges(self.unsaved_changes));
                         
-                        // Use locale-aware time formatting with seconds (time only, no date)
-                        self.last_save_time = format_save_time();
+                        // Configured format, or locale-aware time with seconds (time only, no date)
+                        self.last_save_time = format_save_time(&self.config.datetime_format);
                         self.info_bar.emit(InfoBarInput::SetLastSaveTime(self.last_save_time.clone()));
                         
                         // Update size
                         self.db_size = std::fs::metadata(&self.config.database_path)
-                            .map(|m| format_size(m.len()))
+                            .map(|m| common::format_size(m.len()))
                             .unwrap_or_else(|_| "Unknown".to_string());
                         self.info_bar.emit(InfoBarInput::SetDbSize(self.db_size.clone()));
                             
                         tracing::info!("Database saved successfully");
+                        sender.input(AppInput::Notify("Saved".to_string(), NotificationKind::Success));
+
+                        if self.unsaved_changes && (save_again || self.continue_after_save) {
+                            // Changed while saving; write those changes too before moving on
+                            sender.input(AppInput::SaveDatabase);
+                        } else if std::mem::take(&mut self.continue_after_save) {
+                            self.continue_pending_action(&sender);
+                        }
                     }
                     Err(e) => {
                         tracing::error!("Failed to save database: {}", e);
-                        // TODO: Show error
+                        self.unsaved_changes = true;
+                        self.continue_after_save = false;
+                        sender.input(AppInput::Notify(format!("Save failed: {}", e), NotificationKind::Error));
                     }
                 }
             }
@@ -1084,6 +1928,662 @@ impl Component for App {
                     }
                 }
             }
+            AppInput::TagsSelected { tags, match_all } => {
+                if tags.is_empty() {
+                    self.clear_virtual_view();
+                    if let Some(ref group_uuid) = self.current_group_uuid {
+                        sender.input(AppInput::GroupSelected(group_uuid.clone()));
+                    }
+                } else {
+                    self.virtual_view = Some(VirtualView::Tags { tags, match_all });
+                    self.show_virtual_view();
+                }
+            }
+            AppInput::ShowFavorites => {
+                self.clear_virtual_view();
+                self.virtual_view = Some(VirtualView::Favorites);
+                self.show_virtual_view();
+            }
+            AppInput::ShowAllEntries => {
+                self.clear_virtual_view();
+                self.virtual_view = Some(VirtualView::AllEntries);
+                self.show_virtual_view();
+            }
+            AppInput::CheckIntegrity => {
+                let Some(issues) = self
+                    .database
+                    .as_ref()
+                    .and_then(|db| db.read().ok().map(|db| db.check_integrity()))
+                else {
+                    return;
+                };
+
+                if issues.is_empty() {
+                    sender.input(AppInput::Notify("No problems found".to_string(), NotificationKind::Success));
+                } else {
+                    let lines: Vec<String> = issues.iter().map(|issue| format!("• {}", issue)).collect();
+                    crate::components::common::show_report_dialog(
+                        widgets._main_window.upcast_ref(),
+                        "Database Check",
+                        &format!("Found {} problem(s) in the database:", issues.len()),
+                        &lines,
+                    );
+                }
+            }
+            AppInput::FindDuplicates => {
+                let Some(ref root) = self.root_group else {
+                    return;
+                };
+                let mut pairs = find_duplicates(root);
+                if pairs.is_empty() {
+                    sender.input(AppInput::Notify("No duplicate entries found".to_string(), NotificationKind::Success));
+                    return;
+                }
+                pairs.reverse();
+                self.pending_duplicates = pairs;
+                sender.input(AppInput::CompareNextDuplicate);
+            }
+            AppInput::FindReusedPasswords => {
+                let Some(reused) = self
+                    .database
+                    .as_ref()
+                    .and_then(|db| db.read().ok().map(|db| db.reused_passwords()))
+                else {
+                    return;
+                };
+                let Some(ref root) = self.root_group else {
+                    return;
+                };
+
+                if reused.is_empty() {
+                    sender.input(AppInput::Notify("No reused passwords found".to_string(), NotificationKind::Success));
+                    return;
+                }
+
+                let sections: Vec<(String, Vec<(String, String)>)> = reused
+                    .iter()
+                    .map(|(_, uuids)| (format!("{} accounts share a password", uuids.len()), entry_rows(root, uuids)))
+                    .collect();
+                let input_sender = sender.input_sender().clone();
+                crate::components::common::show_entry_list_dialog(
+                    widgets._main_window.upcast_ref(),
+                    "Reused Passwords",
+                    &format!("{} password(s) are used by more than one entry. Select an entry to open it.", sections.len()),
+                    &sections,
+                    move |uuid| input_sender.emit(AppInput::SidebarEntrySelected(uuid)),
+                );
+            }
+            AppInput::PasswordAudit => {
+                let max_age_days = self.config.password_max_age_days;
+                let Some((weak, stale)) = self.database.as_ref().and_then(|db| {
+                    db.read().ok().map(|db| {
+                        let stale = if max_age_days > 0 { db.stale_entries(max_age_days) } else { Vec::new() };
+                        (db.weak_entries(WEAK_PASSWORD_MIN_SCORE), stale)
+                    })
+                }) else {
+                    return;
+                };
+                let Some(ref root) = self.root_group else {
+                    return;
+                };
+
+                if weak.is_empty() && stale.is_empty() {
+                    sender.input(AppInput::Notify("No weak or old passwords found".to_string(), NotificationKind::Success));
+                    return;
+                }
+
+                let mut sections = Vec::new();
+                if !weak.is_empty() {
+                    sections.push((format!("Weak passwords ({})", weak.len()), entry_rows(root, &weak)));
+                }
+                if !stale.is_empty() {
+                    sections.push((format!("Not changed in {} days ({})", max_age_days, stale.len()), entry_rows(root, &stale)));
+                }
+                let input_sender = sender.input_sender().clone();
+                crate::components::common::show_entry_list_dialog(
+                    widgets._main_window.upcast_ref(),
+                    "Password Audit",
+                    "These entries should get a new password. Select an entry to open it.",
+                    &sections,
+                    move |uuid| input_sender.emit(AppInput::SidebarEntrySelected(uuid)),
+                );
+            }
+            AppInput::CompareNextDuplicate => {
+                let mut pending = std::mem::take(&mut self.pending_duplicates);
+                let Some(ref root) = self.root_group else {
+                    return;
+                };
+                // Earlier choices may have binned one side of a pair; compare the stored entries as they are now
+                let current = |entry: &Entry| {
+                    find_entry_and_group(root, &entry.uuid)
+                        .filter(|(group, _)| !self.is_in_trash(group))
+                        .map(|(group, entry)| (entry.clone(), root.group_path(&group.uuid).unwrap_or_default()))
+                };
+                let next = std::iter::from_fn(|| pending.pop()).find_map(|(left, right)| Some((current(&left)?, current(&right)?)));
+                match next {
+                    Some(((left, left_path), (right, right_path))) => {
+                        self.entry_compare.emit(EntryCompareInput::Show { left, left_path, right, right_path });
+                    }
+                    None => {
+                        sender.input(AppInput::Notify("Finished reviewing duplicates".to_string(), NotificationKind::Info));
+                    }
+                }
+                self.pending_duplicates = pending;
+            }
+            AppInput::DuplicateDiscarded(uuid) => {
+                let Some(ref db) = self.database else {
+                    return;
+                };
+                let Ok(mut db) = db.write() else {
+                    return;
+                };
+
+                if let Err(e) = db.delete_entry(&uuid) {
+                    tracing::error!("Failed to delete duplicate entry: {:#}", e);
+                    sender.input(AppInput::Notify(format!("Failed to delete entry: {:#}", e), NotificationKind::Error));
+                } else {
+                    self.unsaved_changes = true;
+                    self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
+                    sender.input(AppInput::RefreshTree);
+                    sender.input(AppInput::AutoSave);
+                }
+                sender.input(AppInput::CompareNextDuplicate);
+            }
+            AppInput::DuplicatesCancelled => {
+                self.pending_duplicates.clear();
+            }
+            AppInput::NavigateBack => {
+                if self.state == AppState::Unlocked {
+                    self.entry_browser.emit(EntryBrowserInput::NavigateBack);
+                }
+            }
+            AppInput::NavigateForward => {
+                if self.state == AppState::Unlocked {
+                    self.entry_browser.emit(EntryBrowserInput::NavigateForward);
+                }
+            }
+            AppInput::Reveal(path) => {
+                let sender_clone = sender.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = crate::desktop::reveal(&path) {
+                        tracing::error!("Failed to show {} in the file manager: {}", path.display(), e);
+                        sender_clone.input(AppInput::Notify(format!("Failed to open the file manager: {}", e), NotificationKind::Error));
+                    }
+                });
+            }
+            AppInput::RevealDatabase => {
+                sender.input(AppInput::Reveal(self.config.database_path.clone()));
+            }
+            AppInput::RevealSavedAttachment => match self.last_saved_attachment.clone() {
+                Some(path) => sender.input(AppInput::Reveal(path)),
+                None => sender.input(AppInput::Notify("No attachment saved yet".to_string(), NotificationKind::Info)),
+            },
+            AppInput::AttachmentSaved(path) => {
+                self.last_saved_attachment = Some(path);
+            }
+            AppInput::ExportCsv => {
+                self.password_confirmation.emit(PasswordConfirmationInput::Show {
+                    message: "The CSV file will contain all passwords in plain text. Enter the master password to export.".to_string(),
+                    action_id: EXPORT_CSV_ACTION.to_string(),
+                    require_password: true,
+                });
+            }
+            AppInput::ExportCsvConfirmed(password) => {
+                let Some(db) = self.database.clone() else {
+                    return;
+                };
+                let verified = db.read().ok().is_some_and(|db| db.verify_password(&password));
+                if !verified {
+                    self.password_confirmation.emit(PasswordConfirmationInput::ShowError("Incorrect password".to_string()));
+                    return;
+                }
+                self.password_confirmation.emit(PasswordConfirmationInput::Cancel); // Close dialog
+
+                let file_chooser = gtk4::FileChooserNative::new(
+                    Some("Export as CSV"),
+                    Some(&widgets._main_window),
+                    gtk4::FileChooserAction::Save,
+                    Some("Export"),
+                    Some("Cancel"),
+                );
+                let stem = self
+                    .config
+                    .database_path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "database".to_string());
+                file_chooser.set_current_name(&format!("{}.csv", stem));
+
+                let sender_clone = sender.clone();
+                file_chooser.connect_response(move |dialog, response| {
+                    if response == gtk4::ResponseType::Accept {
+                        if let Some(path) = dialog.file().and_then(|f| f.path()) {
+                            let exported = create_export_file(&path)
+                                .and_then(|file| match db.read() {
+                                    Ok(db) => db.export_csv(std::io::BufWriter::new(file)),
+                                    Err(_) => Err(anyhow::anyhow!("Database lock poisoned")),
+                                });
+                            match exported {
+                                Ok(()) => {
+                                    tracing::info!("Exported all entries to {}", path.display());
+                                    sender_clone.input(AppInput::Notify(
+                                        format!("Entries exported to {}", path.display()),
+                                        NotificationKind::Success,
+                                    ));
+                                }
+                                Err(e) => {
+                                    tracing::error!("Failed to export entries: {:#}", e);
+                                    sender_clone.input(AppInput::Notify(format!("Export failed: {:#}", e), NotificationKind::Error));
+                                }
+                            }
+                        }
+                    }
+                    dialog.destroy();
+                });
+
+                file_chooser.show();
+            }
+            AppInput::ExportJson => {
+                self.password_confirmation.emit(PasswordConfirmationInput::Show {
+                    message: "The JSON file will contain all passwords in plain text. Enter the master password to export.".to_string(),
+                    action_id: EXPORT_JSON_ACTION.to_string(),
+                    require_password: true,
+                });
+            }
+            AppInput::ExportJsonConfirmed(password) => {
+                let Some(db) = self.database.clone() else {
+                    return;
+                };
+                let verified = db.read().ok().is_some_and(|db| db.verify_password(&password));
+                if !verified {
+                    self.password_confirmation.emit(PasswordConfirmationInput::ShowError("Incorrect password".to_string()));
+                    return;
+                }
+                self.password_confirmation.emit(PasswordConfirmationInput::Cancel); // Close dialog
+
+                let file_chooser = gtk4::FileChooserNative::new(
+                    Some("Export as JSON"),
+                    Some(&widgets._main_window),
+                    gtk4::FileChooserAction::Save,
+                    Some("Export"),
+                    Some("Cancel"),
+                );
+                let stem = self
+                    .config
+                    .database_path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "database".to_string());
+                file_chooser.set_current_name(&format!("{}.json", stem));
+                // Attachment data can make the export large; leave it out unless asked for
+                file_chooser.add_choice("attachments", "Include attachment data", &[], &[]);
+                file_chooser.set_choice("attachments", "false");
+
+                let sender_clone = sender.clone();
+                file_chooser.connect_response(move |dialog, response| {
+                    if response == gtk4::ResponseType::Accept {
+                        if let Some(path) = dialog.file().and_then(|f| f.path()) {
+                            let include_attachment_data = dialog.choice("attachments").as_deref() == Some("true");
+                            let exported = create_export_file(&path)
+                                .and_then(|file| match db.read() {
+                                    Ok(db) => db.export_json(std::io::BufWriter::new(file), include_attachment_data),
+                                    Err(_) => Err(anyhow::anyhow!("Database lock poisoned")),
+                                });
+                            match exported {
+                                Ok(()) => {
+                                    tracing::info!("Exported the database as JSON to {}", path.display());
+                                    sender_clone.input(AppInput::Notify(
+                                        format!("Entries exported to {}", path.display()),
+                                        NotificationKind::Success,
+                                    ));
+                                }
+                                Err(e) => {
+                                    tracing::error!("Failed to export entries: {:#}", e);
+                                    sender_clone.input(AppInput::Notify(format!("Export failed: {:#}", e), NotificationKind::Error));
+                                }
+                            }
+                        }
+                    }
+                    dialog.destroy();
+                });
+
+                file_chooser.show();
+            }
+            AppInput::SaveCopy => {
+                if self.database.is_none() {
+                    return;
+                }
+
+                let file_chooser = gtk4::FileChooserNative::new(
+                    Some("Save a Copy"),
+                    Some(&widgets._main_window),
+                    gtk4::FileChooserAction::Save,
+                    Some("Save"),
+                    Some("Cancel"),
+                );
+                let stem = self
+                    .config
+                    .database_path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "database".to_string());
+                file_chooser.set_current_name(&format!("{}-copy.kdbx", stem));
+
+                let sender_clone = sender.clone();
+                file_chooser.connect_response(move |dialog, response| {
+                    if response == gtk4::ResponseType::Accept {
+                        if let Some(path) = dialog.file().and_then(|f| f.path()) {
+                            sender_clone.input(AppInput::SaveCopyPathChosen(path));
+                        }
+                    }
+                    dialog.destroy();
+                });
+
+                file_chooser.show();
+            }
+            AppInput::SaveCopyPathChosen(path) => {
+                if path == self.config.database_path {
+                    sender.input(AppInput::Notify(
+                        "Choose a different file than the open database".to_string(),
+                        NotificationKind::Error,
+                    ));
+                    return;
+                }
+                let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                self.pending_copy_path = Some(path);
+                self.new_password.emit(NewPasswordInput::Show {
+                    title: "Save a Copy".to_string(),
+                    message: format!("Choose the password for {}. The open database keeps its own.", name),
+                });
+            }
+            AppInput::SaveCopyPasswordChosen(password) => {
+                let (Some(db), Some(path)) = (self.database.clone(), self.pending_copy_path.take()) else {
+                    return;
+                };
+
+                // Key derivation takes a while by design; keep it off the UI thread
+                let sender_clone = sender.clone();
+                std::thread::spawn(move || {
+                    let saved = match db.read() {
+                        Ok(db) => db.save_as(&path, &password, db.kdf_settings().unwrap_or_default()),
+                        Err(_) => Err(anyhow::anyhow!("Database lock poisoned")),
+                    };
+                    match saved {
+                        Ok(()) => {
+                            tracing::info!("Saved a copy to {}", path.display());
+                            sender_clone.input(AppInput::Notify(
+                                format!("Copy saved to {}", path.display()),
+                                NotificationKind::Success,
+                            ));
+                        }
+                        Err(e) => {
+                            tracing::error!("Failed to save a copy to {}: {:#}", path.display(), e);
+                            sender_clone.input(AppInput::Notify(format!("Saving the copy failed: {:#}", e), NotificationKind::Error));
+                        }
+                    }
+                });
+            }
+            AppInput::ChangeMasterPassword => {
+                if self.database.is_none() {
+                    return;
+                }
+                self.pending_copy_path = None;
+                self.new_password.emit(NewPasswordInput::Show {
+                    title: "Change Master Password".to_string(),
+                    message: "Choose the new master password. The database is saved with it right away.".to_string(),
+                });
+            }
+            AppInput::MasterPasswordChosen(password) => {
+                let Some(db) = self.database.clone() else {
+                    return;
+                };
+                let changed = match db.write() {
+                    Ok(mut db) => db.change_master_password(&password),
+                    Err(_) => Err(anyhow::anyhow!("Database lock poisoned")),
+                };
+                match changed {
+                    Ok(()) => {
+                        tracing::info!("Master password changed");
+                        self.unsaved_changes = true;
+                        self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
+                        sender.input(AppInput::SaveDatabase);
+                        sender.input(AppInput::Notify("Master password changed".to_string(), NotificationKind::Success));
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to change the master password: {:#}", e);
+                        sender.input(AppInput::Notify(
+                            format!("Changing the master password failed: {:#}", e),
+                            NotificationKind::Error,
+                        ));
+                    }
+                }
+            }
+            AppInput::NewPasswordChosen(password) => {
+                // The dialog serves both "Save a Copy" and "Change Master Password"
+                if self.pending_copy_path.is_some() {
+                    sender.input(AppInput::SaveCopyPasswordChosen(password));
+                } else {
+                    sender.input(AppInput::MasterPasswordChosen(password));
+                }
+            }
+            AppInput::NewPasswordCancelled => {
+                self.pending_copy_path = None;
+            }
+            AppInput::ImportEntries => {
+                if self.database.is_none() {
+                    return;
+                }
+
+                let file_chooser = gtk4::FileChooserNative::new(
+                    Some("Import Entries"),
+                    Some(&widgets._main_window),
+                    gtk4::FileChooserAction::Open,
+                    Some("Open"),
+                    Some("Cancel"),
+                );
+                let filter = gtk4::FileFilter::new();
+                filter.set_name(Some("JSON exports and CSV files"));
+                filter.add_pattern("*.json");
+                filter.add_pattern("*.csv");
+                file_chooser.add_filter(&filter);
+
+                let sender_clone = sender.clone();
+                file_chooser.connect_response(move |dialog, response| {
+                    if response == gtk4::ResponseType::Accept {
+                        if let Some(path) = dialog.file().and_then(|f| f.path()) {
+                            let source = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                            let format = ExportFormat::from_path(&path);
+                            match std::fs::read_to_string(&path) {
+                                Ok(contents) => sender_clone.input(AppInput::PreviewImport { source, contents, format }),
+                                Err(e) => {
+                                    tracing::error!("Failed to read {}: {}", path.display(), e);
+                                    sender_clone.input(AppInput::Notify(format!("Import failed: {}", e), NotificationKind::Error));
+                                }
+                            }
+                        }
+                    }
+                    dialog.destroy();
+                });
+
+                file_chooser.show();
+            }
+            AppInput::PreviewImport { source, contents, format } => {
+                let Some(ref db) = self.database else {
+                    return;
+                };
+                // Import into the open folder; virtual views have no folder of their own
+                let target = if self.virtual_view.is_some() { None } else { self.current_group_uuid.clone() };
+                let target_name = target
+                    .as_ref()
+                    .and_then(|uuid| self.root_group.as_ref().and_then(|root| find_group_by_uuid(root, uuid)))
+                    .or(self.root_group.as_ref())
+                    .map(|g| format!("'{}'", g.name))
+                    .unwrap_or_else(|| "the database".to_string());
+
+                let preview = match db.read() {
+                    Ok(db) => match format {
+                        ExportFormat::Json => db.preview_import_json(&contents, target.as_deref(), false),
+                        ExportFormat::Csv => {
+                            let parent = target.clone().unwrap_or_else(|| db.root_group().uuid);
+                            db.preview_import_csv(&contents, &parent)
+                        }
+                    },
+                    Err(_) => Err(anyhow::anyhow!("Database lock poisoned")),
+                };
+                match preview {
+                    Ok(report) => {
+                        self.pending_import = Some((contents, format, target));
+                        self.import_preview.emit(ImportPreviewInput::Show { source, target: target_name, report });
+                    }
+                    Err(e) => {
+                        tracing::error!("Import preview failed: {:#}", e);
+                        sender.input(AppInput::Notify(format!("Import failed: {:#}", e), NotificationKind::Error));
+                    }
+                }
+            }
+            AppInput::ImportConfirmed => {
+                let (Some(db), Some((contents, format, target))) = (self.database.clone(), self.pending_import.take()) else {
+                    return;
+                };
+                let Ok(mut db) = db.write() else {
+                    return;
+                };
+
+                let imported = match format {
+                    ExportFormat::Json => db.import_json(&contents, target.as_deref(), false).map(|report| report.entries_imported),
+                    ExportFormat::Csv => {
+                        let parent = target.unwrap_or_else(|| db.root_group().uuid);
+                        db.import_csv(contents.as_bytes(), &parent)
+                    }
+                };
+                match imported {
+                    Ok(count) => {
+                        self.unsaved_changes = true;
+                        self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
+                        sender.input(AppInput::RefreshTree);
+                        sender.input(AppInput::AutoSave);
+                        sender.input(AppInput::Notify(
+                            format!("Imported {} entries", count),
+                            NotificationKind::Success,
+                        ));
+                    }
+                    Err(e) => {
+                        tracing::error!("Import failed: {:#}", e);
+                        sender.input(AppInput::Notify(format!("Import failed: {:#}", e), NotificationKind::Error));
+                    }
+                }
+            }
+            AppInput::ImportCancelled => {
+                self.pending_import = None;
+            }
+            AppInput::Notify(message, kind) => {
+                self.toast.emit(ToastInput::Show { message, kind });
+            }
+            AppInput::CloseRequested => {
+                if self.unsaved_changes || self.is_saving {
+                    self.pending_action = Some(PendingAction::Quit);
+                    self.unsaved_changes_dialog.emit(UnsavedChangesInput::Show(
+                        "The database has changes that have not been saved. Save them before closing?".to_string(),
+                    ));
+                } else {
+                    sender.input(AppInput::Quit);
+                }
+            }
+            AppInput::SaveAndContinue => {
+                self.continue_after_save = true;
+                // If a save is already running, SaveFinished will quit for us
+                if !self.is_saving {
+                    sender.input(AppInput::SaveDatabase);
+                }
+            }
+            AppInput::DiscardAndContinue => {
+                self.continue_pending_action(&sender);
+            }
+            AppInput::CancelPendingAction => {
+                self.pending_action = None;
+            }
+            AppInput::Quit => {
+                self.allow_close.set(true);
+                widgets._main_window.close();
+            }
+            AppInput::OpenFileRequested(path) => {
+                widgets._main_window.present();
+                if self.state != AppState::Unlocked {
+                    sender.input(AppInput::DatabaseChosen(path));
+                    return;
+                }
+                if path == self.config.database_path {
+                    return;
+                }
+
+                tracing::info!("Asked to open {} while another database is unlocked", path.display());
+                if self.unsaved_changes || self.is_saving {
+                    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                    self.pending_action = Some(PendingAction::Open(path));
+                    self.unsaved_changes_dialog.emit(UnsavedChangesInput::Show(format!(
+                        "The database has changes that have not been saved. Save them before opening {}?",
+                        name
+                    )));
+                } else {
+                    self.pending_action = Some(PendingAction::Open(path));
+                    self.continue_pending_action(&sender);
+                }
+            }
+            AppInput::FocusLost => {
+                if self.state != AppState::Unlocked {
+                    return;
+                }
+                match self.config.focus_loss_action {
+                    FocusLossAction::Lock if !self.unsaved_changes && !self.is_saving => {
+                        sender.input(AppInput::Lock);
+                    }
+                    _ => {
+                        self.entry_browser.emit(EntryBrowserInput::MaskSecrets);
+                    }
+                }
+            }
+            AppInput::CheckIdle => {
+                let idle_limit = std::time::Duration::from_secs(u64::from(self.config.auto_lock_minutes) * 60);
+                // Saves finish first; an unsaved changes prompt already shown is left to the user
+                if self.state != AppState::Unlocked
+                    || self.is_saving
+                    || self.pending_action.is_some()
+                    || self.last_activity.get().elapsed() < idle_limit
+                {
+                    return;
+                }
+                tracing::info!("No activity for {} minutes, locking", self.config.auto_lock_minutes);
+                sender.input(AppInput::Lock);
+            }
+            AppInput::Lock => {
+                if self.state != AppState::Unlocked {
+                    return;
+                }
+                if self.is_saving {
+                    sender.input(AppInput::Notify(
+                        "Wait for the save to finish before locking".to_string(),
+                        NotificationKind::Error,
+                    ));
+                    return;
+                }
+                if self.unsaved_changes {
+                    self.pending_action = Some(PendingAction::Lock);
+                    self.unsaved_changes_dialog.emit(UnsavedChangesInput::Show(
+                        "The database has changes that have not been saved. Save them before locking?".to_string(),
+                    ));
+                    return;
+                }
+                self.lock(widgets);
+            }
+            AppInput::SwitchDatabase(path) => {
+                // Any unsaved changes were saved or discarded by now
+                self.unsaved_changes = false;
+                self.info_bar.emit(InfoBarInput::SetUnsavedChanges(false));
+                if self.state == AppState::Unlocked {
+                    self.lock(widgets);
+                }
+                sender.input(AppInput::DatabaseChosen(path));
+            }
             AppInput::NoOp => {}
         }
         
@@ -1092,6 +2592,171 @@ impl Component for App {
     }
 }
 
+impl App {
+    /// Drop the unlocked database and return to the unlock screen.
+    fn lock(&mut self, widgets: &AppWidgets) {
+        self.database = None;
+        self.root_group = None;
+        self.current_group_uuid = None;
+        self.virtual_view = None;
+        self.pending_duplicates.clear();
+        self.pending_export.clear();
+        // The previewed export holds passwords in plain text
+        if let Some((mut contents, _, _)) = self.pending_import.take() {
+            contents.zeroize();
+        }
+        self.state = AppState::Locked;
+
+        self.sidebar.emit(SidebarInput::SetRootGroup(Group::default()));
+        self.search_palette.emit(SearchPaletteInput::Hide);
+        self.search_palette.emit(SearchPaletteInput::SetRootGroup(Group::default()));
+        // Also drops the cached password strengths
+        self.entry_browser.emit(EntryBrowserInput::Clear);
+        self.unlock.emit(UnlockInput::Reset);
+        ClipboardGuard::clear_now();
+
+        widgets._main_stack.set_visible_child_name("unlock");
+        tracing::info!("Database locked");
+    }
+
+    /// Carry out what the unsaved changes prompt was shown for: open the
+    /// pending database, lock, or quit.
+    fn continue_pending_action(&mut self, sender: &ComponentSender<Self>) {
+        match self.pending_action.take() {
+            Some(PendingAction::Open(path)) => sender.input(AppInput::SwitchDatabase(path)),
+            Some(PendingAction::Lock) => {
+                // Saved by now, or the user chose to drop the changes
+                self.unsaved_changes = false;
+                self.info_bar.emit(InfoBarInput::SetUnsavedChanges(false));
+                sender.input(AppInput::Lock);
+            }
+            Some(PendingAction::Quit) | None => sender.input(AppInput::Quit),
+        }
+    }
+
+    /// Switch to the main view for a freshly unlocked (or created) database.
+    fn open_database(&mut self, mut db: KeepassDatabase, widgets: &AppWidgets, sender: &ComponentSender<Self>) {
+        let max_attachment_bytes = self.config.max_attachment_bytes;
+        db.set_max_attachment_bytes((max_attachment_bytes > 0).then_some(max_attachment_bytes));
+        db.set_read_only(self.config.read_only);
+        db.set_backup_count(self.config.backup_count);
+
+        let purged = match self.config.recycle_bin_auto_purge_days {
+            _ if self.config.read_only => 0,
+            0 => 0,
+            days => db.purge_recycle_bin_older_than(days).unwrap_or_else(|e| {
+                tracing::error!("Failed to purge recycle bin: {:#}", e);
+                0
+            }),
+        };
+
+        let root = db.root_group();
+        self.root_group = Some(root.clone());
+        self.database = Some(Arc::new(RwLock::new(db)));
+        self.state = AppState::Unlocked;
+        self.entry_count = count_entries(&root);
+        self.db_filename = std::path::Path::new(&self.config.database_path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|s| s.to_string());
+            
+        self.db_size = std::fs::metadata(&self.config.database_path)
+            .map(|m| common::format_size(m.len()))
+            .unwrap_or_else(|_| "Unknown".to_string());
+        
+        // Sync initial state to info bar
+        self.info_bar.emit(InfoBarInput::SetFilename(self.db_filename.clone()));
+        self.info_bar.emit(InfoBarInput::SetFullPath(self.config.database_path.display().to_string()));
+        self.info_bar.emit(InfoBarInput::SetEntryCount(self.entry_count));
+        self.info_bar.emit(InfoBarInput::SetDbSize(self.db_size.clone()));
+            
+        // Populate sidebar and search
+        self.sidebar.emit(SidebarInput::SetRootGroup(root.clone()));
+        self.search_palette.emit(SearchPaletteInput::SetRootGroup(root.clone()));
+
+        // Set root group in entry browser
+        self.entry_browser.emit(EntryBrowserInput::SetRootGroup(root.clone()));
+
+        // Switch to main view and drop the typed password from the unlock form
+        widgets._main_stack.set_visible_child_name("main");
+        self.unlock.emit(UnlockInput::Reset);
+
+        if purged > 0 {
+            self.unsaved_changes = true;
+            self.info_bar.emit(InfoBarInput::SetUnsavedChanges(true));
+            sender.input(AppInput::Notify(
+                format!("Purged {} old item(s) from the Recycle Bin", purged),
+                NotificationKind::Info,
+            ));
+            sender.input(AppInput::AutoSave);
+        }
+    }
+
+    /// Show the active virtual view (rebuilt from the current tree) in the entry browser.
+    fn show_virtual_view(&self) {
+        if let (Some(view), Some(root)) = (&self.virtual_view, &self.root_group) {
+            let group = view.build(root);
+            self.entry_browser.emit(EntryBrowserInput::SetShowGroupPaths(true));
+            self.entry_browser.emit(EntryBrowserInput::RefreshGroup {
+                uuid: group.uuid.clone(),
+                name: group.name.clone(),
+                group,
+            });
+            self.entry_browser.emit(EntryBrowserInput::SetTrashMode(false));
+        }
+    }
+
+    /// Whether a group is the recycle bin or inside it.
+    fn is_in_trash(&self, group: &Group) -> bool {
+        group.is_recycle_bin
+            || self
+                .database
+                .as_ref()
+                .and_then(|db| db.read().ok())
+                .is_some_and(|db| db.is_inside_recycle_bin(&group.uuid))
+    }
+
+    /// Group a new entry goes to: the selected group, else the configured
+    /// default, else the root group.
+    fn new_entry_group_uuid(&self, root: &Group) -> String {
+        let usable = |uuid: &str| {
+            find_group_by_uuid(root, uuid).is_some_and(|group| !group.is_recycle_bin)
+                && !self
+                    .database
+                    .as_ref()
+                    .and_then(|db| db.read().ok())
+                    .is_some_and(|db| db.is_inside_recycle_bin(uuid))
+        };
+
+        if self.virtual_view.is_none() {
+            if let Some(uuid) = self.current_group_uuid.as_deref().filter(|uuid| usable(uuid)) {
+                return uuid.to_string();
+            }
+        }
+
+        let default = self.config.default_new_entry_group.trim();
+        if !default.is_empty() {
+            let configured = find_group_by_uuid(root, default)
+                .or_else(|| root.find_group_by_path(default))
+                .map(|group| group.uuid.clone());
+            match configured {
+                Some(uuid) if usable(&uuid) => return uuid,
+                _ => tracing::warn!("Default group for new entries '{}' not found, using the root group", default),
+            }
+        }
+
+        root.uuid.clone()
+    }
+
+    /// Leave the virtual view (if any) and reset the sidebar filter that produced it.
+    fn clear_virtual_view(&mut self) {
+        if self.virtual_view.take().is_some() {
+            self.sidebar.emit(SidebarInput::ClearTagSelection);
+            self.entry_browser.emit(EntryBrowserInput::SetShowGroupPaths(false));
+        }
+    }
+}
+
 /// Find a group by UUID recursively.
 fn find_group_by_uuid<'a>(group: &'a Group, uuid: &str) -> Option<&'a Group> {
     if group.uuid == uuid {
@@ -1120,20 +2785,16 @@ fn find_entry_and_group<'a>(group: &'a Group, entry_uuid: &str) -> Option<(&'a G
     None
 }
 
-fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} B", bytes)
-    }
+/// `(uuid, "Title — Group/Path")` rows for the entries with the given UUIDs.
+fn entry_rows(root: &Group, uuids: &[String]) -> Vec<(String, String)> {
+    uuids
+        .iter()
+        .filter_map(|uuid| {
+            let (group, entry) = find_entry_and_group(root, uuid)?;
+            let path = root.group_path(&group.uuid).unwrap_or_default();
+            Some((uuid.clone(), format!("{} — {}", entry.title, path)))
+        })
+        .collect()
 }
 
 /// Recursively count entries in a group.
@@ -0,0 +1,103 @@
+//! Clipboard access with automatic clearing.
+//!
+//! Every copy goes through [`ClipboardGuard`], which remembers what was copied
+//! last and owns the single timer that clears it again. A new copy cancels the
+//! pending clear instead of stacking another timer on top of it.
+
+use gtk4::gdk;
+use gtk4::glib;
+use gtk4::prelude::*;
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
+
+thread_local! {
+    static GUARD: RefCell<ClipboardGuard> = RefCell::new(ClipboardGuard::default());
+}
+
+/// Owner of "what did we last copy and when".
+#[derive(Default)]
+pub struct ClipboardGuard {
+    /// How long copied values stay on the clipboard (`None` = forever).
+    clear_after: Option<Duration>,
+    /// Last value put on the clipboard by keeprs.
+    last_copied: Option<Zeroizing<String>>,
+    /// When `last_copied` was copied.
+    copied_at: Option<Instant>,
+    /// Pending clear, if any.
+    clear_timer: Option<glib::SourceId>,
+}
+
+impl ClipboardGuard {
+    /// Set how long copied values stay on the clipboard (`None` to never clear them).
+    pub fn set_clear_after(clear_after: Option<Duration>) {
+        GUARD.with(|guard| guard.borrow_mut().clear_after = clear_after);
+    }
+
+    /// Copy `text` to the clipboard and (re)start the clear timer.
+    pub fn copy(text: &str) {
+        let Some(display) = gdk::Display::default() else {
+            tracing::warn!("No display available, can't copy to clipboard");
+            return;
+        };
+        display.clipboard().set_text(text);
+
+        GUARD.with(|guard| {
+            let mut guard = guard.borrow_mut();
+            guard.cancel_timer();
+            guard.last_copied = Some(Zeroizing::new(text.to_string()));
+            guard.copied_at = Some(Instant::now());
+            if let Some(clear_after) = guard.clear_after {
+                guard.clear_timer = Some(glib::timeout_add_local_once(clear_after, || {
+                    GUARD.with(|guard| {
+                        let mut guard = guard.borrow_mut();
+                        // The timer has fired, so its source must not be removed again
+                        guard.clear_timer = None;
+                        guard.clear();
+                    });
+                }));
+            }
+        });
+    }
+
+    /// Clear the clipboard now if it still holds what keeprs copied.
+    pub fn clear_now() {
+        GUARD.with(|guard| {
+            let mut guard = guard.borrow_mut();
+            guard.cancel_timer();
+            guard.clear();
+        });
+    }
+
+    fn cancel_timer(&mut self) {
+        if let Some(timer) = self.clear_timer.take() {
+            timer.remove();
+        }
+    }
+
+    fn clear(&mut self) {
+        let Some(copied) = self.last_copied.take() else {
+            return;
+        };
+        let copied_for = self.copied_at.take().map(|at| at.elapsed());
+
+        let Some(display) = gdk::Display::default() else {
+            return;
+        };
+        let clipboard = display.clipboard();
+        // Something copied elsewhere since is none of our business
+        if !clipboard.is_local() {
+            return;
+        }
+        // Text copied inside keeprs (e.g. from an entry field) is local too, so compare contents
+        clipboard.read_text_async(None::<&gtk4::gio::Cancellable>, move |result| {
+            let current = result.ok().flatten().map(|text| Zeroizing::new(text.to_string()));
+            if current.as_deref().is_some_and(|text| text.as_str() == copied.as_str()) {
+                if let Some(display) = gdk::Display::default() {
+                    display.clipboard().set_text("");
+                }
+                tracing::debug!(?copied_for, "Clipboard cleared");
+            }
+        });
+    }
+}
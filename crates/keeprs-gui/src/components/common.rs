@@ -1,4 +1,7 @@
 use gtk4::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use zxcvbn::{zxcvbn, Score};
 
 
 /// Create a standardized "Primary" action button (e.g. Add).
@@ -126,3 +129,497 @@ pub fn create_text_area_row(label: &str, value: &str) -> (gtk4::Box, gtk4::TextV
     
     (row, text_view)
 }
+
+/// Create a standardized "Label + Color picker" row with a button to clear the color.
+///
+/// `on_change` receives the picked color as `#rrggbb`, or `None` when cleared.
+pub fn create_color_picker_row<F>(label: &str, value: Option<&str>, on_change: F) -> gtk4::Box
+where
+    F: Fn(Option<String>) + 'static,
+{
+    let row = gtk4::Box::new(gtk4::Orientation::Vertical, 4);
+
+    let label_widget = gtk4::Label::new(Some(label));
+    label_widget.add_css_class("dim-label");
+    label_widget.set_halign(gtk4::Align::Start);
+    row.append(&label_widget);
+
+    let controls = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+
+    let color_btn = gtk4::ColorButton::new();
+    color_btn.set_use_alpha(false);
+    if let Some(rgba) = value.and_then(|v| gtk4::gdk::RGBA::parse(v).ok()) {
+        color_btn.set_rgba(&rgba);
+    }
+    controls.append(&color_btn);
+
+    let clear_btn = gtk4::Button::from_icon_name("edit-clear-symbolic");
+    clear_btn.add_css_class("flat");
+    clear_btn.set_tooltip_text(Some("No color"));
+    clear_btn.set_sensitive(value.is_some());
+    controls.append(&clear_btn);
+
+    let on_change = std::rc::Rc::new(on_change);
+
+    let on_change_clone = on_change.clone();
+    let clear_clone = clear_btn.clone();
+    color_btn.connect_color_set(move |btn| {
+        on_change_clone(Some(rgba_to_hex(&btn.rgba())));
+        clear_clone.set_sensitive(true);
+    });
+
+    clear_btn.connect_clicked(move |btn| {
+        on_change(None);
+        btn.set_sensitive(false);
+    });
+
+    row.append(&controls);
+    row
+}
+
+/// Create a standardized "Label + Calendar" row with a button to clear the date.
+///
+/// `value` and the value passed to `on_change` are UTC; a picked day means
+/// local midnight at its start. `None` means no date.
+pub fn create_date_picker_row<F>(label: &str, value: Option<chrono::NaiveDateTime>, on_change: F) -> gtk4::Box
+where
+    F: Fn(Option<chrono::NaiveDateTime>) + 'static,
+{
+    use chrono::{Datelike, TimeZone};
+
+    let row = gtk4::Box::new(gtk4::Orientation::Vertical, 4);
+
+    let label_widget = gtk4::Label::new(Some(label));
+    label_widget.add_css_class("dim-label");
+    label_widget.set_halign(gtk4::Align::Start);
+    row.append(&label_widget);
+
+    let controls = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+
+    let local_date = |time: chrono::NaiveDateTime| time.and_utc().with_timezone(&chrono::Local).date_naive();
+    let date_label = |date: Option<chrono::NaiveDate>| match date {
+        Some(date) => date.format("%Y-%m-%d").to_string(),
+        None => "Never".to_string(),
+    };
+
+    let calendar = gtk4::Calendar::new();
+    if let Some(date) = value.map(local_date) {
+        if let Ok(selected) = gtk4::glib::DateTime::from_local(date.year(), date.month() as i32, date.day() as i32, 0, 0, 0.0) {
+            calendar.select_day(&selected);
+        }
+    }
+
+    let popover = gtk4::Popover::new();
+    popover.set_child(Some(&calendar));
+
+    let date_btn = gtk4::MenuButton::new();
+    date_btn.set_label(&date_label(value.map(local_date)));
+    date_btn.set_popover(Some(&popover));
+    controls.append(&date_btn);
+
+    let clear_btn = gtk4::Button::from_icon_name("edit-clear-symbolic");
+    clear_btn.add_css_class("flat");
+    clear_btn.set_tooltip_text(Some("No date"));
+    clear_btn.set_sensitive(value.is_some());
+    controls.append(&clear_btn);
+
+    let on_change = std::rc::Rc::new(on_change);
+
+    let on_change_clone = on_change.clone();
+    let date_btn_clone = date_btn.clone();
+    let clear_clone = clear_btn.clone();
+    calendar.connect_day_selected(move |calendar| {
+        let picked = calendar.date();
+        let Some(date) = chrono::NaiveDate::from_ymd_opt(picked.year(), picked.month() as u32, picked.day_of_month() as u32) else {
+            return;
+        };
+        let Some(start) = chrono::Local.from_local_datetime(&date.and_time(chrono::NaiveTime::MIN)).earliest() else {
+            return;
+        };
+        on_change_clone(Some(start.naive_utc()));
+        date_btn_clone.set_label(&date_label(Some(date)));
+        date_btn_clone.popdown();
+        clear_clone.set_sensitive(true);
+    });
+
+    clear_btn.connect_clicked(move |btn| {
+        on_change(None);
+        date_btn.set_label(&date_label(None));
+        btn.set_sensitive(false);
+    });
+
+    row.append(&controls);
+    row
+}
+
+/// Create a thin vertical stripe filled with the given `#rrggbb` color.
+///
+/// Returns `None` if the color can't be parsed.
+pub fn create_color_stripe(color: &str) -> Option<gtk4::DrawingArea> {
+    let rgba = gtk4::gdk::RGBA::parse(color).ok()?;
+
+    let stripe = gtk4::DrawingArea::new();
+    stripe.set_content_width(4);
+    stripe.set_vexpand(true);
+    stripe.add_css_class("color-stripe");
+    stripe.set_draw_func(move |_, cr, width, height| {
+        cr.set_source_rgba(rgba.red() as f64, rgba.green() as f64, rgba.blue() as f64, 1.0);
+        cr.rectangle(0.0, 0.0, width as f64, height as f64);
+        let _ = cr.fill();
+    });
+    Some(stripe)
+}
+
+/// Show a modal window listing report lines (e.g. problems found by a check).
+pub fn show_report_dialog(parent: &gtk4::Window, title: &str, summary: &str, lines: &[String]) {
+    let dialog = gtk4::Window::new();
+    dialog.set_title(Some(title));
+    dialog.set_modal(true);
+    dialog.set_transient_for(Some(parent));
+    dialog.set_default_width(520);
+    dialog.set_default_height(360);
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 12);
+    vbox.set_margin_top(16);
+    vbox.set_margin_bottom(16);
+    vbox.set_margin_start(16);
+    vbox.set_margin_end(16);
+
+    let summary_label = gtk4::Label::new(Some(summary));
+    summary_label.set_halign(gtk4::Align::Start);
+    summary_label.set_wrap(true);
+    vbox.append(&summary_label);
+
+    if !lines.is_empty() {
+        let text = gtk4::Label::new(Some(&lines.join("\n")));
+        text.set_halign(gtk4::Align::Start);
+        text.set_valign(gtk4::Align::Start);
+        text.set_selectable(true);
+        text.set_wrap(true);
+
+        let scrolled = gtk4::ScrolledWindow::new();
+        scrolled.set_vexpand(true);
+        scrolled.set_child(Some(&text));
+        vbox.append(&scrolled);
+    }
+
+    let close_btn = gtk4::Button::with_label("Close");
+    close_btn.set_halign(gtk4::Align::End);
+    let dialog_clone = dialog.clone();
+    close_btn.connect_clicked(move |_| dialog_clone.close());
+    vbox.append(&close_btn);
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+/// Show lists of entries under headings, e.g. the results of a password audit.
+///
+/// Each section is a heading with `(uuid, label)` rows; activating a row calls
+/// `on_select` with its UUID. The window is not modal so the selected entry can
+/// be inspected while it stays open.
+pub fn show_entry_list_dialog<F>(
+    parent: &gtk4::Window,
+    title: &str,
+    summary: &str,
+    sections: &[(String, Vec<(String, String)>)],
+    on_select: F,
+) where
+    F: Fn(String) + 'static,
+{
+    let dialog = gtk4::Window::new();
+    dialog.set_title(Some(title));
+    dialog.set_transient_for(Some(parent));
+    dialog.set_default_width(520);
+    dialog.set_default_height(420);
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 12);
+    vbox.set_margin_top(16);
+    vbox.set_margin_bottom(16);
+    vbox.set_margin_start(16);
+    vbox.set_margin_end(16);
+
+    let summary_label = gtk4::Label::new(Some(summary));
+    summary_label.set_halign(gtk4::Align::Start);
+    summary_label.set_wrap(true);
+    vbox.append(&summary_label);
+
+    let content = gtk4::Box::new(gtk4::Orientation::Vertical, 12);
+    let on_select = std::rc::Rc::new(on_select);
+    for (heading, rows) in sections {
+        let heading = gtk4::Label::new(Some(heading));
+        heading.set_halign(gtk4::Align::Start);
+        heading.add_css_class("heading");
+        content.append(&heading);
+
+        let list = gtk4::ListBox::new();
+        list.add_css_class("boxed-list");
+        list.set_selection_mode(gtk4::SelectionMode::None);
+        for (_, label) in rows {
+            let row_label = gtk4::Label::new(Some(label));
+            row_label.set_halign(gtk4::Align::Start);
+            row_label.set_margin_top(6);
+            row_label.set_margin_bottom(6);
+            row_label.set_margin_start(8);
+            row_label.set_margin_end(8);
+            list.append(&row_label);
+        }
+        let uuids: Vec<String> = rows.iter().map(|(uuid, _)| uuid.clone()).collect();
+        let on_select = on_select.clone();
+        list.connect_row_activated(move |_, row| {
+            if let Some(uuid) = usize::try_from(row.index()).ok().and_then(|i| uuids.get(i)) {
+                on_select(uuid.clone());
+            }
+        });
+        content.append(&list);
+    }
+
+    let scrolled = gtk4::ScrolledWindow::new();
+    scrolled.set_vexpand(true);
+    scrolled.set_child(Some(&content));
+    vbox.append(&scrolled);
+
+    let close_btn = gtk4::Button::with_label("Close");
+    close_btn.set_halign(gtk4::Align::End);
+    let dialog_clone = dialog.clone();
+    close_btn.connect_clicked(move |_| dialog_clone.close());
+    vbox.append(&close_btn);
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+/// Human readable file size, e.g. `1.50 MB`.
+pub fn format_size(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    const GB: u64 = MB * 1024;
+
+    if bytes >= GB {
+        format!("{:.2} GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.2} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.2} KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// Create the connector lines drawn in front of a tree row.
+///
+/// `levels` holds, for each ancestor level, whether that ancestor was the last
+/// child of its parent (no vertical line is drawn below last children).
+pub fn create_tree_lines(levels: &[bool], is_last: bool) -> gtk4::DrawingArea {
+    let depth = levels.len();
+    let indent_width = (depth + 1) * 24;
+
+    let drawing_area = gtk4::DrawingArea::new();
+    drawing_area.set_content_width(indent_width as i32);
+    drawing_area.set_content_height(32);
+    drawing_area.set_vexpand(true);
+
+    let levels = levels.to_vec();
+
+    drawing_area.set_draw_func(move |_area, cr: &gtk4::cairo::Context, _width, height| {
+        cr.set_source_rgba(0.6, 0.6, 0.6, 0.5);
+        cr.set_line_width(1.0);
+        let indent = 24.0;
+        let half_indent = 12.0;
+
+        for (i, &parent_is_last) in levels.iter().enumerate() {
+            if !parent_is_last {
+                let x = i as f64 * indent + half_indent;
+                cr.move_to(x, -2.0);
+                cr.line_to(x, height as f64 + 2.0);
+                cr.stroke().expect("Invalid cairo");
+            }
+        }
+
+        let current_x = depth as f64 * indent + half_indent;
+        cr.move_to(current_x, -2.0);
+        if is_last {
+            cr.line_to(current_x, height as f64 / 2.0);
+        } else {
+            cr.line_to(current_x, height as f64 + 2.0);
+        }
+        cr.stroke().expect("Invalid cairo");
+
+        cr.move_to(current_x, height as f64 / 2.0);
+        cr.line_to(current_x + half_indent + 4.0, height as f64 / 2.0);
+        cr.stroke().expect("Invalid cairo");
+    });
+
+    drawing_area
+}
+
+/// Create a round avatar showing the first letter of a URL's domain.
+///
+/// The background color is derived from the domain, so each site keeps the
+/// same color across runs. Used when no favicon is available.
+pub fn create_letter_avatar(url: &str, size: i32) -> gtk4::DrawingArea {
+    let host = url::Url::parse(&keeprs_core::normalize_url(url))
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_lowercase()))
+        .unwrap_or_else(|| url.trim().to_lowercase());
+    let letter = host
+        .chars()
+        .find(|c| c.is_alphanumeric())
+        .map(|c| c.to_uppercase().to_string())
+        .unwrap_or_else(|| "?".to_string());
+
+    // FNV-1a: unlike DefaultHasher it is stable across Rust versions
+    let hash = host
+        .bytes()
+        .fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+    let (r, g, b) = hsl_to_rgb((hash % 360) as f64, 0.55, 0.45);
+
+    let avatar = gtk4::DrawingArea::new();
+    avatar.set_content_width(size);
+    avatar.set_content_height(size);
+    avatar.set_valign(gtk4::Align::Center);
+    avatar.set_tooltip_text(Some(&host));
+    avatar.set_draw_func(move |_, cr, width, height| {
+        let center_x = width as f64 / 2.0;
+        let center_y = height as f64 / 2.0;
+        let radius = f64::min(center_x, center_y);
+
+        cr.set_source_rgb(r, g, b);
+        cr.arc(center_x, center_y, radius, 0.0, 2.0 * std::f64::consts::PI);
+        let _ = cr.fill();
+
+        cr.set_source_rgb(1.0, 1.0, 1.0);
+        cr.select_font_face("Sans", gtk4::cairo::FontSlant::Normal, gtk4::cairo::FontWeight::Bold);
+        cr.set_font_size(radius * 1.2);
+        if let Ok(extents) = cr.text_extents(&letter) {
+            cr.move_to(
+                center_x - extents.width() / 2.0 - extents.x_bearing(),
+                center_y - extents.height() / 2.0 - extents.y_bearing(),
+            );
+            let _ = cr.show_text(&letter);
+        }
+    });
+    avatar
+}
+
+/// Convert a color from HSL (hue in degrees, saturation and lightness in 0..1) to RGB.
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (f64, f64, f64) {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    (r + m, g + m, b + m)
+}
+
+fn rgba_to_hex(rgba: &gtk4::gdk::RGBA) -> String {
+    let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", channel(rgba.red()), channel(rgba.green()), channel(rgba.blue()))
+}
+
+/// Flag a URL entry whose text doesn't parse as a URL (after normalization).
+///
+/// Meant to be connected to the entry's `changed` signal.
+pub fn validate_url_entry(entry: &gtk4::Entry) {
+    let text = entry.text();
+    let valid = text.trim().is_empty()
+        || text.trim().starts_with('{')
+        || url::Url::parse(&keeprs_core::normalize_url(&text)).is_ok();
+
+    if valid {
+        entry.remove_css_class("warning");
+        entry.set_tooltip_text(None);
+    } else {
+        entry.add_css_class("warning");
+        entry.set_tooltip_text(Some("This doesn't look like a valid URL"));
+    }
+}
+
+/// Flag a password policy entry whose text doesn't parse as a policy.
+///
+/// Meant to be connected to the entry's `changed` signal.
+pub fn validate_password_policy_entry(entry: &gtk4::Entry) {
+    let text = entry.text();
+    let error = if text.trim().is_empty() {
+        None
+    } else {
+        text.parse::<keeprs_core::PasswordPolicy>().err()
+    };
+
+    match error {
+        None => {
+            entry.remove_css_class("warning");
+            entry.set_tooltip_text(None);
+        }
+        Some(e) => {
+            entry.add_css_class("warning");
+            entry.set_tooltip_text(Some(&format!("{:#}", e)));
+        }
+    }
+}
+
+/// Rate a password with zxcvbn.
+///
+/// Returns (score 0-4, log10 of guesses, label, css class).
+pub fn get_password_strength(password: &str) -> (u8, f64, &'static str, &'static str) {
+    if password.is_empty() {
+        return (0, 0.0, "Empty", "error");
+    }
+    let entropy = zxcvbn(password, &[]);
+    let score = entropy.score();
+    let guesses_log10 = entropy.guesses_log10();
+
+    let (score_num, label, css_class) = match score {
+        Score::Zero => (0, "Very Weak", "error"),
+        Score::One => (1, "Weak", "error"),
+        Score::Two => (2, "Fair", "warning"),
+        Score::Three => (3, "Strong", "success"),
+        Score::Four => (4, "Very Strong", "success"),
+        _ => (2, "Unknown", "warning"),
+    };
+    (score_num, guesses_log10, label, css_class)
+}
+
+/// Result of [`get_password_strength`].
+pub type Strength = (u8, f64, &'static str, &'static str);
+
+/// Cache of zxcvbn results keyed by entry UUID.
+///
+/// Nothing derived from the password is kept; a result stays valid until the
+/// entry's modification time changes or it is invalidated after an edit.
+#[derive(Default)]
+pub struct StrengthCache {
+    scores: RefCell<HashMap<String, (Option<chrono::NaiveDateTime>, Strength)>>,
+}
+
+impl StrengthCache {
+    /// Get the strength for an entry's password, computing it only if not cached.
+    pub fn get(&self, entry: &keeprs_core::Entry) -> Strength {
+        if let Some((cached_modified, strength)) = self.scores.borrow().get(&entry.uuid) {
+            if *cached_modified == entry.last_modified {
+                return *strength;
+            }
+        }
+
+        let strength = get_password_strength(&entry.password);
+        self.scores.borrow_mut().insert(entry.uuid.clone(), (entry.last_modified, strength));
+        strength
+    }
+
+    /// Forget the cached result for an entry (e.g. after it was edited).
+    pub fn invalidate(&self, uuid: &str) {
+        self.scores.borrow_mut().remove(uuid);
+    }
+
+    /// Forget every cached result (e.g. when the database is locked).
+    pub fn clear(&self) {
+        self.scores.borrow_mut().clear();
+    }
+}
@@ -3,16 +3,18 @@
 //! Displays entry list and entry details with breadcrumb navigation.
 //! Uses a two-column layout: entry list on the left, details on the right.
 
-use keeprs_core::{Entry, Group, NavigationPath, NavigationStep};
+use keeprs_core::{Entry, FieldMask, Group, NavigationPath, NavigationStep};
 use gtk4::prelude::*;
 
 
 use relm4::prelude::*;
-use crate::components::entry_detail_view::{EntryDetailView, EntryDetailViewInput, EntryDetailViewOutput};
-use crate::components::common::create_composite_button;
-
-/// Minimum width for each column.
-const COLUMN_MIN_WIDTH: i32 = 250;
+use std::collections::HashMap;
+use crate::clipboard::ClipboardGuard;
+use crate::components::entry_detail_view::{EntryDetailView, EntryDetailViewInit, EntryDetailViewInput, EntryDetailViewOutput};
+use crate::components::common::{self, create_composite_button, StrengthCache};
+use crate::components::info_bar::format_timestamp;
+use crate::components::toast::NotificationKind;
+use crate::config::EntryListDensity;
 
 /// Messages for the entry browser.
 #[derive(Debug)]
@@ -21,14 +23,44 @@ pub enum EntryBrowserInput {
     SetRootGroup(Group),
     /// User selected a group in sidebar (start fresh navigation).
     SelectGroup { uuid: String, name: String, group: Group },
+    /// List a group again after the tree changed, keeping the selected entry if it's still there.
+    RefreshGroup { uuid: String, name: String, group: Group },
     /// User selected an entry.
     SelectEntry { uuid: String, entry: Entry },
     /// Navigate to a specific depth via breadcrumb.
     NavigateToDepth(usize),
+    /// Go up one level, remembering the step for `NavigateForward`.
+    NavigateBack,
+    /// Go back down the step last left with `NavigateBack`.
+    NavigateForward,
     /// Add new entry.
     AddEntry,
     /// Set whether we are in trash mode (enables permanent deletion).
     SetTrashMode(bool),
+    /// Show each entry's group path under its title (for views spanning several groups).
+    SetShowGroupPaths(bool),
+    /// Open the selected entry's URL and copy its password.
+    OpenAndFill,
+    /// Select every entry in the list.
+    SelectAll,
+    /// Only list entries matching this query (empty = all).
+    SetFilter(String),
+    /// Hide revealed secrets in the detail view.
+    MaskSecrets,
+    /// Drop all displayed data (on lock).
+    Clear,
+    /// Export the entries selected in the list.
+    ExportSelected,
+    /// Move the entries selected in the list to another group.
+    MoveSelected,
+    /// Move the selected entry one place up in its group.
+    MoveSelectedUp,
+    /// Move the selected entry one place down in its group.
+    MoveSelectedDown,
+    /// Restore every entry in the recycle bin.
+    RestoreAll,
+    /// Empty the recycle bin (after confirmation).
+    EmptyTrash,
     /// Internal: User clicked a row.
     EntryRowActivated(String),
     /// Message from the detail view sub-component.
@@ -40,6 +72,8 @@ pub enum EntryBrowserInput {
 pub enum EntryBrowserOutput {
     /// User wants to add an entry.
     AddEntry,
+    /// User wants a copy of an entry.
+    DuplicateEntry(String),
     /// User wants to delete an entry.
     DeleteEntry(String),
     /// User wants to permanently delete an entry.
@@ -50,8 +84,40 @@ pub enum EntryBrowserOutput {
     SaveAttachment { filename: String, data: Vec<u8> },
     /// User wants to open an attachment.
     OpenAttachment { filename: String, data: Vec<u8> },
+    /// User wants to attach a file to the entry with the given UUID.
+    AddAttachment(String),
     /// Entry was edited inline and saved.
     EntryEdited(Entry),
+    /// Show a notification to the user.
+    Notify(String, NotificationKind),
+    /// User wants to export the given entries.
+    ExportEntries(Vec<String>),
+    /// User wants to move the given entries to another group.
+    MoveEntries(Vec<String>),
+    /// User wants an entry at position `new_index` among its group's entries.
+    ReorderEntry { group_uuid: String, entry_uuid: String, new_index: usize },
+    /// User wants to see the previous versions of an entry.
+    ShowHistory(String),
+    /// User wants to restore every entry in the recycle bin.
+    RestoreAllEntries,
+    /// User wants to empty the recycle bin with the given UUID.
+    EmptyRecycleBin(String),
+    /// User clicked a group in the breadcrumb other than the one listed.
+    GroupSelected(String),
+}
+
+/// Settings for the entry browser.
+#[derive(Debug, Clone)]
+pub struct EntryBrowserInit {
+    pub show_entropy_bar: bool,
+    pub show_totp_visible: bool,
+    pub datetime_format: String,
+    pub render_notes_markdown: bool,
+    pub density: EntryListDensity,
+    /// Minimum width of the list and detail columns.
+    pub column_min_width: i32,
+    /// Hide adding, editing, moving and deleting entries.
+    pub read_only: bool,
 }
 
 /// Entry browser model.
@@ -60,20 +126,39 @@ pub struct EntryBrowser {
     root_group: Option<Group>,
     /// Current navigation path.
     nav_path: NavigationPath,
+    /// UUID of the group whose entries are listed.
+    list_group_uuid: Option<String>,
+    /// Steps left with `NavigateBack`, most recent last.
+    forward_steps: Vec<NavigationStep>,
     /// Currently selected group's entries.
     current_entries: Vec<Entry>,
+    /// Query the listed entries are filtered by (see [`Entry::matches`]).
+    filter_query: String,
     /// Currently selected entry details.
     selected_entry: Option<Entry>,
 
     /// Whether we are in trash mode (permanent deletion).
     trash_mode: bool,
+    read_only: bool,
+    /// Group path per entry UUID, when paths are shown in the list.
+    group_paths: Option<HashMap<String, String>>,
+    /// Whether to show password strength indicators.
+    show_entropy_bar: bool,
+    /// Configured format for timestamps (empty = locale default).
+    datetime_format: String,
+    /// Row spacing of the entry list.
+    density: EntryListDensity,
+    /// Cached password strength per entry.
+    strength_cache: StrengthCache,
     /// Controller for the entry detail view.
     detail_view: Controller<EntryDetailView>,
+    /// Right-click menu shared by the entry rows.
+    context_menu: gtk4::PopoverMenu,
 }
 
 #[relm4::component(pub)]
 impl Component for EntryBrowser {
-    type Init = (bool, bool); // (show_entropy_bar, show_totp_visible)
+    type Init = EntryBrowserInit;
     type Input = EntryBrowserInput;
     type Output = EntryBrowserOutput;
     type CommandOutput = ();
@@ -115,7 +200,7 @@ impl Component for EntryBrowser {
                      // Entry list column (always visible)
                     gtk4::Box {
                         set_orientation: gtk4::Orientation::Vertical,
-                        set_width_request: COLUMN_MIN_WIDTH,
+                        set_width_request: init.column_min_width,
                         set_vexpand: true,
 
                         // Toolbar
@@ -132,14 +217,124 @@ impl Component for EntryBrowser {
                                 3,
                                 3 
                             ) {
+                                set_visible: !model.read_only,
                                 connect_clicked => EntryBrowserInput::AddEntry,
-                            }
+                            },
+
+                            gtk4::Button {
+                                set_icon_name: "go-up-symbolic",
+                                add_css_class: "flat",
+                                set_tooltip_text: Some("Move Entry Up"),
+                                set_visible: !model.read_only,
+                                #[watch]
+                                set_sensitive: model.selected_position().is_some_and(|(_, index)| index > 0),
+                                connect_clicked => EntryBrowserInput::MoveSelectedUp,
+                            },
+
+                            gtk4::Button {
+                                set_icon_name: "go-down-symbolic",
+                                add_css_class: "flat",
+                                set_tooltip_text: Some("Move Entry Down"),
+                                set_visible: !model.read_only,
+                                #[watch]
+                                set_sensitive: model
+                                    .selected_position()
+                                    .is_some_and(|(_, index)| index + 1 < model.current_entries.len()),
+                                connect_clicked => EntryBrowserInput::MoveSelectedDown,
+                            },
+
+                            gtk4::Box {
+                                set_hexpand: true,
+                            },
+
+                            gtk4::MenuButton {
+                                set_icon_name: "view-more-symbolic",
+                                add_css_class: "flat",
+                                set_tooltip_text: Some("Entry actions"),
+
+                                #[wrap(Some)]
+                                set_popover: entry_menu = &gtk4::Popover {
+                                    gtk4::Box {
+                                        set_orientation: gtk4::Orientation::Vertical,
+                                        set_spacing: 2,
+
+                                        gtk4::Button {
+                                            set_label: "Select All",
+                                            add_css_class: "flat",
+                                            connect_clicked => EntryBrowserInput::SelectAll,
+                                        },
+
+                                        gtk4::Button {
+                                            set_label: "Export Selected…",
+                                            add_css_class: "flat",
+                                            connect_clicked => EntryBrowserInput::ExportSelected,
+                                        },
+
+                                        gtk4::Button {
+                                            set_label: "Move Selected To…",
+                                            add_css_class: "flat",
+                                            set_visible: !model.read_only,
+                                            connect_clicked => EntryBrowserInput::MoveSelected,
+                                        },
+                                    }
+                                },
+                            },
+                        },
+
+                        // Filter for the listed entries
+                        #[name = "filter_entry"]
+                        gtk4::SearchEntry {
+                            set_placeholder_text: Some("Filter entries"),
+                            set_margin_start: 8,
+                            set_margin_end: 8,
+                            set_margin_bottom: 8,
+                            connect_search_changed[sender] => move |entry| {
+                                sender.input(EntryBrowserInput::SetFilter(entry.text().to_string()));
+                            },
                         },
 
                         gtk4::Separator {
                             set_orientation: gtk4::Orientation::Horizontal,
                         },
 
+                        // Recycle bin actions
+                        gtk4::Box {
+                            set_orientation: gtk4::Orientation::Horizontal,
+                            set_spacing: 6,
+                            set_margin_all: 8,
+                            #[watch]
+                            set_visible: model.trash_mode,
+
+                            gtk4::Label {
+                                #[watch]
+                                set_text: &format!("{} deleted entries", model.current_entries.len()),
+                                set_hexpand: true,
+                                set_halign: gtk4::Align::Start,
+                                add_css_class: "dim-label",
+                            },
+
+                            gtk4::Button {
+                                set_label: "Restore All",
+                                set_visible: !model.read_only,
+                                #[watch]
+                                set_sensitive: !model.current_entries.is_empty(),
+                                connect_clicked => EntryBrowserInput::RestoreAll,
+                            },
+
+                            gtk4::Button {
+                                set_label: "Empty…",
+                                add_css_class: "destructive-action",
+                                set_visible: !model.read_only,
+                                connect_clicked => EntryBrowserInput::EmptyTrash,
+                            },
+                        },
+
+                        gtk4::Separator {
+                            set_orientation: gtk4::Orientation::Horizontal,
+                            #[watch]
+                            set_visible: model.trash_mode,
+                        },
+
                         // Entry list
                         gtk4::ScrolledWindow {
                             set_vexpand: true,
@@ -148,7 +343,8 @@ impl Component for EntryBrowser {
                             #[name = "_entry_list_box"]
                             gtk4::ListBox {
                                 add_css_class: "navigation-sidebar",
-                                set_selection_mode: gtk4::SelectionMode::Single,
+                                // Ctrl/Shift+click to select several entries (for export)
+                                set_selection_mode: gtk4::SelectionMode::Multiple,
                                 set_margin_all: 8,
                                 
                                 connect_row_activated[sender] => move |_, row| {
@@ -194,22 +390,43 @@ impl Component for EntryBrowser {
     }
 
     fn init(
-        (show_entropy_bar, show_totp_visible): Self::Init,
+        init: Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
         let detail_view = EntryDetailView::builder()
-            .launch((show_entropy_bar, show_totp_visible))
+            .launch(EntryDetailViewInit {
+                show_entropy_bar: init.show_entropy_bar,
+                show_totp_default: init.show_totp_visible,
+                render_notes_markdown: init.render_notes_markdown,
+                datetime_format: init.datetime_format.clone(),
+                column_min_width: init.column_min_width,
+                read_only: init.read_only,
+            })
             .forward(sender.input_sender(), EntryBrowserInput::DetailViewMessage);
 
+        let context_menu = gtk4::PopoverMenu::from_model(None::<&gtk4::gio::MenuModel>);
+        context_menu.set_has_arrow(true);
+        context_menu.set_position(gtk4::PositionType::Bottom);
+
         let model = EntryBrowser {
             root_group: None,
             nav_path: NavigationPath::new(),
+            list_group_uuid: None,
+            forward_steps: Vec::new(),
             current_entries: Vec::new(),
+            filter_query: String::new(),
             selected_entry: None,
 
             trash_mode: false,
+            read_only: init.read_only,
+            group_paths: None,
+            show_entropy_bar: init.show_entropy_bar,
+            datetime_format: init.datetime_format,
+            density: init.density,
+            strength_cache: StrengthCache::default(),
             detail_view,
+            context_menu,
         };
 
         let widgets = view_output!();
@@ -230,24 +447,57 @@ impl Component for EntryBrowser {
     ) {
         match message {
             EntryBrowserInput::SetRootGroup(group) => {
+                if self.group_paths.is_some() {
+                    self.group_paths = Some(group.entry_group_paths());
+                }
                 self.root_group = Some(group);
             }
+            EntryBrowserInput::SetShowGroupPaths(show) => {
+                if show != self.group_paths.is_some() {
+                    self.group_paths = if show {
+                        self.root_group.as_ref().map(Group::entry_group_paths)
+                    } else {
+                        None
+                    };
+                    self.refresh_list(widgets, &sender);
+                }
+            }
             EntryBrowserInput::SelectGroup { uuid, name, group } => {
-                // Start fresh navigation from this group
-                self.nav_path = NavigationPath::new();
-                self.nav_path.push_group(uuid, name);
-                self.current_entries = group.entries.clone();
-                self.selected_entry = None;
-                self.trash_mode = false;
-                
-                self.refresh_breadcrumbs(widgets, &sender);
-                self.refresh_list(widgets, &sender);
-                self.detail_view.emit(EntryDetailViewInput::UpdateEntry(None));
+                self.select_group(uuid, name, &group, widgets, &sender);
+            }
+            EntryBrowserInput::RefreshGroup { uuid, name, group } => {
+                // Pick up the selected entry's new data, or drop it if it's gone
+                let reselect = self
+                    .selected_entry
+                    .as_ref()
+                    .filter(|_| self.list_group_uuid.as_ref() == Some(&uuid))
+                    .and_then(|selected| group.entries.iter().find(|e| e.uuid == selected.uuid))
+                    .cloned();
+                self.select_group(uuid, name, &group, widgets, &sender);
+                if let Some(entry) = reselect {
+                    sender.input(EntryBrowserInput::SelectEntry { uuid: entry.uuid.clone(), entry });
+                }
             }
             EntryBrowserInput::SelectEntry { uuid, entry } => {
-                // Add entry to navigation
-                self.nav_path.push_entry(uuid, entry.title.clone());
+                // Show the entry's full ancestry, however it was selected
+                match self.root_group.as_ref().and_then(|root| NavigationPath::to_entry(root, &uuid)) {
+                    Some(path) => self.nav_path = path,
+                    None => {
+                        if let Some(NavigationStep::Entry { .. }) = self.nav_path.steps.last() {
+                            self.nav_path.steps.pop();
+                        }
+                        self.nav_path.push_entry(uuid, entry.title.clone());
+                    }
+                }
                 self.selected_entry = Some(entry.clone());
+                let inherited_policy = self
+                    .root_group
+                    .as_ref()
+                    .and_then(|root| root.inherited_password_policy(&entry.uuid))
+                    .map(str::to_string);
+                let resolved = self.root_group.as_ref().map(|root| root.resolved_entry(&entry));
+                self.detail_view.emit(EntryDetailViewInput::SetInheritedPasswordPolicy(inherited_policy));
+                self.detail_view.emit(EntryDetailViewInput::SetResolvedEntry(resolved));
                 self.detail_view.emit(EntryDetailViewInput::UpdateEntry(Some(entry)));
                 
                 self.refresh_breadcrumbs(widgets, &sender);
@@ -260,6 +510,13 @@ impl Component for EntryBrowser {
             }
             EntryBrowserInput::NavigateToDepth(depth) => {
                 self.nav_path.truncate(depth);
+                if let Some(NavigationStep::Group { uuid, .. }) = self.nav_path.steps.last() {
+                    // An ancestor of the listed group: let the app switch to it
+                    if self.list_group_uuid.as_ref() != Some(uuid) {
+                        let _ = sender.output(EntryBrowserOutput::GroupSelected(uuid.clone()));
+                        return;
+                    }
+                }
                 if depth == 0 {
                     self.current_entries.clear();
                     self.selected_entry = None;
@@ -283,6 +540,50 @@ impl Component for EntryBrowser {
                 self.update_selection(widgets);
             }
 
+            EntryBrowserInput::NavigateBack => {
+                let depth = self.nav_path.depth();
+                // The first step is the top of the path; there's nothing above it
+                if depth < 2 {
+                    return;
+                }
+                if let Some(step) = self.nav_path.steps.last() {
+                    self.forward_steps.push(step.clone());
+                }
+                sender.input(EntryBrowserInput::NavigateToDepth(depth - 1));
+            }
+            EntryBrowserInput::NavigateForward => {
+                let Some(step) = self.forward_steps.pop() else {
+                    return;
+                };
+                let Some(ref root) = self.root_group else {
+                    return;
+                };
+                let target = match &step {
+                    NavigationStep::Group { uuid, .. } => NavigationPath::to_group(root, uuid),
+                    NavigationStep::Entry { uuid, .. } => NavigationPath::to_entry(root, uuid),
+                };
+                // Only step down from where we went back to; anything else is stale history
+                let leads_here = target
+                    .as_ref()
+                    .and_then(|path| path.steps.split_last())
+                    .is_some_and(|(_, parent)| parent == self.nav_path.steps.as_slice());
+                if !leads_here {
+                    self.forward_steps.clear();
+                    return;
+                }
+
+                match step {
+                    NavigationStep::Group { uuid, .. } => {
+                        let _ = sender.output(EntryBrowserOutput::GroupSelected(uuid));
+                    }
+                    NavigationStep::Entry { uuid, .. } => {
+                        if let Some(entry) = self.current_entries.iter().find(|e| e.uuid == uuid).cloned() {
+                            sender.input(EntryBrowserInput::SelectEntry { uuid, entry });
+                        }
+                    }
+                }
+            }
+
             EntryBrowserInput::AddEntry => {
                 let _ = sender.output(EntryBrowserOutput::AddEntry);
             }
@@ -291,27 +592,136 @@ impl Component for EntryBrowser {
             EntryBrowserInput::SetTrashMode(is_trash) => {
                 self.trash_mode = is_trash;
                 self.detail_view.emit(EntryDetailViewInput::SetTrashMode(is_trash));
-                // No need to rebuild, just detail view update?
-                // Actually if list shows trash status? No.
+                // Rebuild so rows show (or drop) their deletion time
+                self.refresh_list(widgets, &sender);
             }
 
 
+            EntryBrowserInput::OpenAndFill => {
+                let Some(ref entry) = self.selected_entry else {
+                    return;
+                };
+                let entry = match self.root_group {
+                    Some(ref root) => root.resolved_entry(entry),
+                    None => entry.clone(),
+                };
+                let Some(url) = entry.launch_url() else {
+                    let _ = sender.output(EntryBrowserOutput::Notify(
+                        "Entry has no URL".to_string(),
+                        NotificationKind::Error,
+                    ));
+                    return;
+                };
+
+                // Copy first so the password is ready by the time the page loads
+                ClipboardGuard::copy(&entry.password);
+                Self::open_url(url, &sender);
+                let _ = sender.output(EntryBrowserOutput::Notify(
+                    "Password copied, opening URL".to_string(),
+                    NotificationKind::Info,
+                ));
+            }
+
+            EntryBrowserInput::MaskSecrets => {
+                self.detail_view.emit(EntryDetailViewInput::MaskSecrets);
+            }
+
+            EntryBrowserInput::Clear => {
+                self.root_group = None;
+                self.nav_path = NavigationPath::new();
+                self.list_group_uuid = None;
+                self.forward_steps.clear();
+                self.current_entries.clear();
+                self.filter_query.clear();
+                widgets.filter_entry.set_text("");
+                self.selected_entry = None;
+                self.trash_mode = false;
+                self.group_paths = None;
+                self.strength_cache.clear();
+                self.detail_view.emit(EntryDetailViewInput::UpdateEntry(None));
+
+                self.refresh_breadcrumbs(widgets, &sender);
+                self.refresh_list(widgets, &sender);
+            }
+
+            EntryBrowserInput::MoveSelectedUp | EntryBrowserInput::MoveSelectedDown => {
+                let Some((group_uuid, index)) = self.selected_position() else {
+                    return;
+                };
+                let new_index = match message {
+                    EntryBrowserInput::MoveSelectedUp => index.saturating_sub(1),
+                    _ => index + 1,
+                };
+                if let Some(ref entry) = self.selected_entry {
+                    let _ = sender.output(EntryBrowserOutput::ReorderEntry {
+                        group_uuid,
+                        entry_uuid: entry.uuid.clone(),
+                        new_index,
+                    });
+                }
+            }
+
+            EntryBrowserInput::RestoreAll => {
+                let _ = sender.output(EntryBrowserOutput::RestoreAllEntries);
+            }
+
+            EntryBrowserInput::EmptyTrash => {
+                if let Some(NavigationStep::Group { uuid, .. }) = self.nav_path.steps.first() {
+                    let _ = sender.output(EntryBrowserOutput::EmptyRecycleBin(uuid.clone()));
+                }
+            }
+
+            EntryBrowserInput::SelectAll => {
+                widgets.entry_menu.popdown();
+                widgets._entry_list_box.select_all();
+            }
+            EntryBrowserInput::SetFilter(query) => {
+                if query != self.filter_query {
+                    self.filter_query = query;
+                    self.refresh_list(widgets, &sender);
+                }
+            }
+
+            EntryBrowserInput::ExportSelected | EntryBrowserInput::MoveSelected => {
+                widgets.entry_menu.popdown();
+                let uuids: Vec<String> = widgets
+                    ._entry_list_box
+                    .selected_rows()
+                    .iter()
+                    .filter_map(|row| row.widget_name().as_str().strip_prefix("entry-").map(str::to_string))
+                    .collect();
+
+                if uuids.is_empty() {
+                    let _ = sender.output(EntryBrowserOutput::Notify(
+                        "No entries selected".to_string(),
+                        NotificationKind::Info,
+                    ));
+                } else if matches!(message, EntryBrowserInput::MoveSelected) {
+                    let _ = sender.output(EntryBrowserOutput::MoveEntries(uuids));
+                } else {
+                    let _ = sender.output(EntryBrowserOutput::ExportEntries(uuids));
+                }
+            }
+
             EntryBrowserInput::DetailViewMessage(msg) => {
                 match msg {
                     EntryDetailViewOutput::EntryEdited(entry) => {
                          // Update selected_entry with edits
+                        self.strength_cache.invalidate(&entry.uuid);
                         self.selected_entry = Some(entry.clone());
                         let _ = sender.output(EntryBrowserOutput::EntryEdited(entry));
                     }
                     EntryDetailViewOutput::OpenUrl(url) => {
-                        std::thread::spawn(move || {
-                            if let Err(e) = std::process::Command::new("xdg-open")
-                                .arg(&url)
-                                .spawn()
-                            {
-                                tracing::error!("Failed to open URL {}: {}", url, e);
-                            }
-                        });
+                        Self::open_url(url, &sender);
+                    }
+                    EntryDetailViewOutput::OpenAndFill => {
+                        sender.input(EntryBrowserInput::OpenAndFill);
+                    }
+                    EntryDetailViewOutput::ShowHistory(uuid) => {
+                        let _ = sender.output(EntryBrowserOutput::ShowHistory(uuid));
+                    }
+                    EntryDetailViewOutput::Notify(message, kind) => {
+                        let _ = sender.output(EntryBrowserOutput::Notify(message, kind));
                     }
                     EntryDetailViewOutput::RestoreEntry(uuid) => {
                 let _ = sender.output(EntryBrowserOutput::RestoreEntry(uuid));
@@ -322,6 +732,9 @@ impl Component for EntryBrowser {
                     EntryDetailViewOutput::OpenAttachment { filename, data } => {
                         let _ = sender.output(EntryBrowserOutput::OpenAttachment { filename, data });
                     }
+                    EntryDetailViewOutput::AddAttachment(uuid) => {
+                        let _ = sender.output(EntryBrowserOutput::AddAttachment(uuid));
+                    }
                     EntryDetailViewOutput::DeleteEntry(uuid) => {
                          let _ = sender.output(EntryBrowserOutput::DeleteEntry(uuid));
                     }
@@ -335,6 +748,74 @@ impl Component for EntryBrowser {
 }
 
 impl EntryBrowser {
+    /// Open a URL with the desktop handler, reporting failures as a notification.
+    fn open_url(url: String, sender: &ComponentSender<Self>) {
+        let sender_clone = sender.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = crate::desktop::open(&url) {
+                tracing::error!("Failed to open URL {}: {}", url, e);
+                let _ = sender_clone.output(EntryBrowserOutput::Notify(
+                    format!("Failed to open URL: {}", e),
+                    NotificationKind::Error,
+                ));
+            }
+        });
+    }
+
+    /// The listed group and the selected entry's index in it, when the list
+    /// shows a real group whose order can be changed.
+    fn selected_position(&self) -> Option<(String, usize)> {
+        if self.trash_mode || self.read_only {
+            return None;
+        }
+        let group_uuid = self.list_group_uuid.as_ref()?;
+        // Virtual views (favorites, tags, ...) aren't groups of the tree
+        self.root_group.as_ref()?.group_path(group_uuid)?;
+        let selected = self.selected_entry.as_ref()?;
+        let index = self.current_entries.iter().position(|e| e.uuid == selected.uuid)?;
+        Some((group_uuid.clone(), index))
+    }
+
+    /// Show the right-click menu of the entry row `widget`.
+    fn show_context_menu(
+        widget: &gtk4::Widget,
+        x: f64,
+        y: f64,
+        uuid: &str,
+        sender: &ComponentSender<Self>,
+        popover: &gtk4::PopoverMenu,
+    ) {
+        let menu_model = gtk4::gio::Menu::new();
+        menu_model.append(Some("Duplicate"), Some("entry.duplicate"));
+        menu_model.append(Some("Delete"), Some("entry.delete"));
+        popover.set_menu_model(Some(&menu_model));
+
+        popover.unparent();
+        popover.set_parent(widget);
+        popover.set_pointing_to(Some(&gtk4::gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
+
+        let action_group = gtk4::gio::SimpleActionGroup::new();
+
+        let sender_clone = sender.clone();
+        let uuid_clone = uuid.to_string();
+        let action = gtk4::gio::SimpleAction::new("duplicate", None);
+        action.connect_activate(move |_, _| {
+            let _ = sender_clone.output(EntryBrowserOutput::DuplicateEntry(uuid_clone.clone()));
+        });
+        action_group.add_action(&action);
+
+        let sender_clone = sender.clone();
+        let uuid_clone = uuid.to_string();
+        let action = gtk4::gio::SimpleAction::new("delete", None);
+        action.connect_activate(move |_, _| {
+            let _ = sender_clone.output(EntryBrowserOutput::DeleteEntry(uuid_clone.clone()));
+        });
+        action_group.add_action(&action);
+
+        popover.insert_action_group("entry", Some(&action_group));
+        popover.popup();
+    }
+
     fn refresh_breadcrumbs(&self, widgets: &EntryBrowserWidgets, sender: &ComponentSender<Self>) {
         // Clear and rebuild breadcrumbs
         while let Some(child) = widgets.breadcrumb_bar.first_child() {
@@ -365,24 +846,37 @@ impl EntryBrowser {
         }
     }
 
-    fn refresh_list(&self, widgets: &EntryBrowserWidgets, _sender: &ComponentSender<Self>) {
+    fn refresh_list(&self, widgets: &EntryBrowserWidgets, sender: &ComponentSender<Self>) {
         // Clear list
         while let Some(child) = widgets._entry_list_box.first_child() {
             widgets._entry_list_box.remove(&child);
         }
 
         // Rebuild rows
-        for entry in &self.current_entries {
+        for entry in self.current_entries.iter().filter(|e| e.matches(&self.filter_query, FieldMask::ALL)) {
             let row = gtk4::ListBoxRow::new();
             row.set_widget_name(&format!("entry-{}", entry.uuid));
 
-            let hbox = gtk4::Box::new(gtk4::Orientation::Horizontal, 12);
-            hbox.set_margin_all(12);
+            let compact = self.density == EntryListDensity::Compact;
+            let hbox = gtk4::Box::new(gtk4::Orientation::Horizontal, if compact { 8 } else { 12 });
+            if compact {
+                hbox.set_margin_start(8);
+                hbox.set_margin_end(8);
+                hbox.set_margin_top(4);
+                hbox.set_margin_bottom(4);
+            } else {
+                hbox.set_margin_all(12);
+            }
+
+            let accent = entry.background_color.as_deref().or(entry.foreground_color.as_deref());
+            if let Some(stripe) = accent.and_then(common::create_color_stripe) {
+                hbox.append(&stripe);
+            }
 
             let icon = gtk4::Image::from_icon_name("dialog-password-symbolic");
             hbox.append(&icon);
 
-            let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 4);
+            let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, if compact { 0 } else { 4 });
             vbox.set_hexpand(true);
 
             let title = gtk4::Label::new(Some(&entry.title));
@@ -391,15 +885,79 @@ impl EntryBrowser {
             title.add_css_class("heading");
             vbox.append(&title);
 
-            if !entry.username.is_empty() {
+            if compact {
+                if !entry.username.is_empty() {
+                    row.set_tooltip_text(Some(&entry.username));
+                }
+            } else if !entry.username.is_empty() {
                 let username = gtk4::Label::new(Some(&entry.username));
                 username.set_halign(gtk4::Align::Start);
+                username.set_ellipsize(gtk4::pango::EllipsizeMode::End);
                 username.add_css_class("dim-label");
                 vbox.append(&username);
             }
 
+            if let Some(path) = self.group_paths.as_ref().and_then(|paths| paths.get(&entry.uuid)) {
+                let group_path = gtk4::Label::new(Some(path));
+                group_path.set_halign(gtk4::Align::Start);
+                group_path.set_ellipsize(gtk4::pango::EllipsizeMode::Middle);
+                group_path.add_css_class("dim-label");
+                group_path.add_css_class("caption");
+                vbox.append(&group_path);
+            }
+
+            if self.trash_mode {
+                if let Some(days) = entry.days_since_location_change() {
+                    let deleted = gtk4::Label::new(Some(&format_deleted_ago(days)));
+                    if let Some(time) = entry.location_changed {
+                        let local = time.and_utc().with_timezone(&chrono::Local);
+                        deleted.set_tooltip_text(Some(&format_timestamp(local, &self.datetime_format)));
+                    }
+                    deleted.set_halign(gtk4::Align::Start);
+                    deleted.add_css_class("dim-label");
+                    deleted.add_css_class("caption");
+                    vbox.append(&deleted);
+                }
+
+                let origin = entry
+                    .original_parent_uuid()
+                    .and_then(|uuid| self.root_group.as_ref()?.group_path(uuid));
+                if let Some(path) = origin {
+                    let from = gtk4::Label::new(Some(&format!("From {}", path)));
+                    from.set_halign(gtk4::Align::Start);
+                    from.set_ellipsize(gtk4::pango::EllipsizeMode::Middle);
+                    from.add_css_class("dim-label");
+                    from.add_css_class("caption");
+                    vbox.append(&from);
+                }
+            }
+
             hbox.append(&vbox);
 
+            if self.trash_mode && !self.read_only {
+                let restore_btn = gtk4::Button::from_icon_name("edit-undo-symbolic");
+                restore_btn.add_css_class("flat");
+                restore_btn.set_valign(gtk4::Align::Center);
+                restore_btn.set_tooltip_text(Some("Restore Entry"));
+                let sender_clone = sender.clone();
+                let uuid = entry.uuid.clone();
+                restore_btn.connect_clicked(move |_| {
+                    let _ = sender_clone.output(EntryBrowserOutput::RestoreEntry(uuid.clone()));
+                });
+                hbox.append(&restore_btn);
+            }
+
+            // Password strength dot (only if enabled in config)
+            if self.show_entropy_bar && !entry.password.is_empty() {
+                let (_, _, strength_label, strength_class) = self.strength_cache.get(entry);
+                let dot = gtk4::Label::new(Some("●"));
+                dot.add_css_class("strength-dot");
+                dot.add_css_class(strength_class);
+                dot.set_tooltip_text(Some(strength_label));
+                dot.set_valign(gtk4::Align::Center);
+                hbox.append(&dot);
+            }
+
             // Chevron (maybe only if selected? or always?)
             // Original code likely had it always or handled visibility
             let chevron = gtk4::Image::from_icon_name("go-next-symbolic");
@@ -411,12 +969,57 @@ impl EntryBrowser {
             hbox.append(&chevron);
 
             row.set_child(Some(&hbox));
+
+            // Right-click menu (every action changes the database)
+            if !self.trash_mode && !self.read_only {
+                let gesture = gtk4::GestureClick::new();
+                gesture.set_button(3);
+                let sender_clone = sender.clone();
+                let uuid = entry.uuid.clone();
+                let context_menu = self.context_menu.clone();
+                gesture.connect_released(move |gesture, _n_press, x, y| {
+                    if let Some(widget) = gesture.widget() {
+                        Self::show_context_menu(&widget, x, y, &uuid, &sender_clone, &context_menu);
+                    }
+                });
+                row.add_controller(gesture);
+            }
+
             widgets._entry_list_box.append(&row);
         }
         
         self.update_selection(widgets);
     }
 
+    /// Start fresh navigation from a group, showing where it sits in the tree.
+    fn select_group(
+        &mut self,
+        uuid: String,
+        name: String,
+        group: &Group,
+        widgets: &EntryBrowserWidgets,
+        sender: &ComponentSender<Self>,
+    ) {
+        self.nav_path = self
+            .root_group
+            .as_ref()
+            .and_then(|root| NavigationPath::to_group(root, &uuid))
+            .unwrap_or_else(|| {
+                // Virtual views aren't part of the tree
+                let mut path = NavigationPath::new();
+                path.push_group(uuid.clone(), name);
+                path
+            });
+        self.list_group_uuid = Some(uuid);
+        self.current_entries = group.entries.clone();
+        self.selected_entry = None;
+        self.trash_mode = false;
+
+        self.refresh_breadcrumbs(widgets, sender);
+        self.refresh_list(widgets, sender);
+        self.detail_view.emit(EntryDetailViewInput::UpdateEntry(None));
+    }
+
     fn update_selection(&self, widgets: &EntryBrowserWidgets) {
         if let Some(ref selected) = self.selected_entry {
              // Find row by name
@@ -424,6 +1027,7 @@ impl EntryBrowser {
              while let Some(row) = widgets._entry_list_box.row_at_index(i) {
                  if let Some(name) = row.widget_name().as_str().strip_prefix("entry-") {
                      if name == selected.uuid {
+                         widgets._entry_list_box.unselect_all();
                          widgets._entry_list_box.select_row(Some(&row));
                          
                          // SCROLL TO VIEW / FOCUS
@@ -440,9 +1044,18 @@ impl EntryBrowser {
                  i += 1;
              }
         } else {
-            widgets._entry_list_box.select_row(None::<&gtk4::ListBoxRow>);
+            widgets._entry_list_box.unselect_all();
         }
     }
 
 
 }
+
+/// Human readable deletion age for recycle bin rows.
+fn format_deleted_ago(days: i64) -> String {
+    match days {
+        0 => "Deleted today".to_string(),
+        1 => "Deleted yesterday".to_string(),
+        n => format!("Deleted {} days ago", n),
+    }
+}
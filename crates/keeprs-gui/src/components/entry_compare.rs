@@ -0,0 +1,269 @@
+//! Entry compare dialog component.
+//!
+//! Shows two entries side by side with the fields that differ highlighted,
+//! and lets the user keep one of them (the other goes to the recycle bin) or
+//! both.
+
+use crate::components::entry_history::compare_entries;
+use crate::components::info_bar::format_timestamp;
+use gtk4::prelude::*;
+use keeprs_core::Entry;
+use relm4::prelude::*;
+
+/// Messages for the compare dialog.
+#[derive(Debug)]
+pub enum EntryCompareInput {
+    /// Compare two entries; `*_path` is the folder each one is in.
+    Show { left: Entry, left_path: String, right: Entry, right_path: String },
+    /// Toggle showing secrets in clear text.
+    ToggleReveal,
+    /// Keep the left entry and discard the right one.
+    KeepLeft,
+    /// Keep the right entry and discard the left one.
+    KeepRight,
+    /// Keep both entries.
+    KeepBoth,
+    /// Close the dialog.
+    Cancel,
+}
+
+/// Output messages from the compare dialog.
+#[derive(Debug, Clone)]
+pub enum EntryCompareOutput {
+    /// Move the entry with the given UUID to the recycle bin.
+    Discard(String),
+    /// Both entries stay.
+    KeepBoth,
+    /// Dialog was closed without a choice.
+    Cancelled,
+}
+
+/// Entry compare dialog model.
+pub struct EntryCompare {
+    datetime_format: String,
+    left: Option<(Entry, String)>,
+    right: Option<(Entry, String)>,
+    reveal: bool,
+    visible: bool,
+}
+
+#[relm4::component(pub)]
+impl Component for EntryCompare {
+    type Init = String; // datetime_format
+    type Input = EntryCompareInput;
+    type Output = EntryCompareOutput;
+    type CommandOutput = ();
+
+    view! {
+        #[name = "dialog"]
+        gtk4::Window {
+            set_modal: true,
+            set_title: Some("Compare Entries"),
+            set_default_width: 720,
+            set_default_height: 480,
+            #[watch]
+            set_visible: model.visible,
+            connect_close_request[sender] => move |_| {
+                sender.input(EntryCompareInput::Cancel);
+                gtk4::glib::Propagation::Stop
+            },
+
+            gtk4::Box {
+                set_orientation: gtk4::Orientation::Vertical,
+                set_spacing: 0,
+
+                gtk4::HeaderBar {
+                    set_show_title_buttons: true,
+
+                    #[wrap(Some)]
+                    set_title_widget = &gtk4::Label {
+                        set_text: "Compare Entries",
+                        add_css_class: "title",
+                    },
+
+                    pack_end = &gtk4::Button {
+                        #[watch]
+                        set_icon_name: if model.reveal { "view-conceal-symbolic" } else { "view-reveal-symbolic" },
+                        #[watch]
+                        set_tooltip_text: Some(if model.reveal { "Hide Passwords" } else { "Show Passwords" }),
+                        connect_clicked => EntryCompareInput::ToggleReveal,
+                    },
+                },
+
+                gtk4::ScrolledWindow {
+                    set_hscrollbar_policy: gtk4::PolicyType::Never,
+                    set_vexpand: true,
+
+                    #[name = "fields_grid"]
+                    gtk4::Grid {
+                        set_row_spacing: 12,
+                        set_column_spacing: 18,
+                        set_column_homogeneous: false,
+                        set_margin_all: 18,
+                    },
+                },
+
+                gtk4::Box {
+                    set_orientation: gtk4::Orientation::Horizontal,
+                    set_spacing: 10,
+                    set_halign: gtk4::Align::End,
+                    set_margin_all: 12,
+
+                    gtk4::Button {
+                        set_label: "Cancel",
+                        connect_clicked => EntryCompareInput::Cancel,
+                    },
+
+                    gtk4::Button {
+                        set_label: "Keep Both",
+                        connect_clicked => EntryCompareInput::KeepBoth,
+                    },
+
+                    gtk4::Button {
+                        set_label: "Keep Left",
+                        set_tooltip_text: Some("Move the right entry to the Recycle Bin"),
+                        connect_clicked => EntryCompareInput::KeepLeft,
+                    },
+
+                    gtk4::Button {
+                        set_label: "Keep Right",
+                        set_tooltip_text: Some("Move the left entry to the Recycle Bin"),
+                        connect_clicked => EntryCompareInput::KeepRight,
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        datetime_format: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = EntryCompare {
+            datetime_format,
+            left: None,
+            right: None,
+            reveal: false,
+            visible: false,
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            EntryCompareInput::Show { left, left_path, right, right_path } => {
+                self.left = Some((left, left_path));
+                self.right = Some((right, right_path));
+                self.reveal = false;
+                self.visible = true;
+            }
+            EntryCompareInput::ToggleReveal => {
+                self.reveal = !self.reveal;
+            }
+            EntryCompareInput::KeepLeft => {
+                if let Some((right, _)) = self.right.as_ref() {
+                    let _ = sender.output(EntryCompareOutput::Discard(right.uuid.clone()));
+                }
+                self.close();
+            }
+            EntryCompareInput::KeepRight => {
+                if let Some((left, _)) = self.left.as_ref() {
+                    let _ = sender.output(EntryCompareOutput::Discard(left.uuid.clone()));
+                }
+                self.close();
+            }
+            EntryCompareInput::KeepBoth => {
+                let _ = sender.output(EntryCompareOutput::KeepBoth);
+                self.close();
+            }
+            EntryCompareInput::Cancel => {
+                if self.visible {
+                    let _ = sender.output(EntryCompareOutput::Cancelled);
+                }
+                self.close();
+            }
+        }
+
+        self.rebuild_fields(&widgets.fields_grid);
+        self.update_view(widgets, sender);
+    }
+}
+
+impl EntryCompare {
+    /// Hide the dialog and drop the entry data it held.
+    fn close(&mut self) {
+        self.visible = false;
+        self.left = None;
+        self.right = None;
+    }
+
+    /// Fill the grid with one row per field, highlighting the ones that differ.
+    fn rebuild_fields(&self, grid: &gtk4::Grid) {
+        while let Some(child) = grid.first_child() {
+            grid.remove(&child);
+        }
+
+        let (Some((left, left_path)), Some((right, right_path))) = (self.left.as_ref(), self.right.as_ref()) else {
+            return;
+        };
+
+        let modified = |entry: &Entry| {
+            entry
+                .last_modified
+                .map(|time| format_timestamp(time.and_utc().with_timezone(&chrono::Local), &self.datetime_format))
+                .unwrap_or_default()
+        };
+        let mut fields = vec![
+            ("Folder".to_string(), left_path.clone(), right_path.clone(), false),
+            ("Modified".to_string(), modified(left), modified(right), false),
+        ];
+        fields.extend(compare_entries(left, right));
+
+        for (row, (name, left_value, right_value, secret)) in fields.into_iter().enumerate() {
+            let changed = left_value != right_value;
+            let show = |value: &str| {
+                if value.is_empty() {
+                    "(empty)".to_string()
+                } else if secret && !self.reveal {
+                    "••••••••".to_string()
+                } else {
+                    value.to_string()
+                }
+            };
+
+            let name_label = gtk4::Label::new(Some(&name));
+            name_label.set_halign(gtk4::Align::Start);
+            name_label.set_valign(gtk4::Align::Start);
+            name_label.add_css_class("caption-heading");
+            if changed {
+                name_label.add_css_class("warning");
+            }
+            grid.attach(&name_label, 0, row as i32, 1, 1);
+
+            for (column, value) in [(1, &left_value), (2, &right_value)] {
+                let value_label = gtk4::Label::new(Some(&show(value)));
+                value_label.set_halign(gtk4::Align::Start);
+                value_label.set_valign(gtk4::Align::Start);
+                value_label.set_hexpand(true);
+                value_label.set_xalign(0.0);
+                value_label.set_wrap(true);
+                value_label.set_wrap_mode(gtk4::pango::WrapMode::WordChar);
+                value_label.set_selectable(!secret || self.reveal);
+                if value.is_empty() || !changed {
+                    value_label.add_css_class("dim-label");
+                }
+                grid.attach(&value_label, column, row as i32, 1, 1);
+            }
+        }
+    }
+}
@@ -1,14 +1,18 @@
 //! Entry edit dialog component.
 
-use keeprs_core::Entry;
+use keeprs_core::{normalize_url, title_from_url, Entry};
 use gtk4::prelude::*;
 use relm4::prelude::*;
 
 /// Messages for entry edit dialog.
 #[derive(Debug)]
 pub enum EntryEditInput {
-    /// Open dialog to add a new entry.
-    AddNew,
+    /// Open dialog to add a new entry to the given group (UUID and display path).
+    AddNew { group_uuid: String, group_path: String },
+    /// Group picked for the new entry.
+    SetGroup { uuid: String, path: String },
+    /// User wants to pick another group for the new entry.
+    ChooseGroup,
     /// Open dialog to edit an existing entry.
     Edit(Entry),
     /// Title changed.
@@ -32,6 +36,10 @@ pub enum EntryEditInput {
 pub enum EntryEditOutput {
     /// Entry was saved.
     Saved(Entry),
+    /// New entry was created in the group with the given UUID.
+    Created { entry: Entry, group_uuid: String },
+    /// User wants to pick the group for the new entry.
+    ChooseGroup,
     /// Dialog was cancelled.
     Cancelled,
 }
@@ -40,12 +48,18 @@ pub enum EntryEditOutput {
 pub struct EntryEdit {
     entry: Entry,
     is_new: bool,
+    /// Target group (UUID, display path) of a new entry.
+    group: Option<(String, String)>,
+    /// Suggest a title from the URL of new entries.
+    suggest_titles: bool,
+    /// Title suggested from the URL, used if the title is left empty.
+    suggested_title: Option<String>,
     visible: bool,
 }
 
 #[relm4::component(pub)]
 impl Component for EntryEdit {
-    type Init = ();
+    type Init = bool; // suggest titles from URLs
     type Input = EntryEditInput;
     type Output = EntryEditOutput;
     type CommandOutput = ();
@@ -97,6 +111,40 @@ impl Component for EntryEdit {
                         set_spacing: 16,
                         set_margin_all: 24,
 
+                        // Group (new entries only)
+                        #[name = "group_box"]
+                        gtk4::Box {
+                            set_orientation: gtk4::Orientation::Vertical,
+                            set_spacing: 4,
+
+                            gtk4::Label {
+                                set_text: "Group",
+                                set_halign: gtk4::Align::Start,
+                                add_css_class: "dim-label",
+                            },
+
+                            gtk4::Button {
+                                set_tooltip_text: Some("Choose Group"),
+                                connect_clicked => EntryEditInput::ChooseGroup,
+
+                                gtk4::Box {
+                                    set_orientation: gtk4::Orientation::Horizontal,
+                                    set_spacing: 8,
+
+                                    gtk4::Image {
+                                        set_icon_name: Some("folder-symbolic"),
+                                    },
+
+                                    #[name = "group_label"]
+                                    gtk4::Label {
+                                        set_hexpand: true,
+                                        set_halign: gtk4::Align::Start,
+                                        set_ellipsize: gtk4::pango::EllipsizeMode::Start,
+                                    },
+                                },
+                            },
+                        },
+
                         // Title field
                         gtk4::Box {
                             set_orientation: gtk4::Orientation::Vertical,
@@ -117,6 +165,15 @@ impl Component for EntryEdit {
                                     sender.input(EntryEditInput::TitleChanged(entry.text().to_string()));
                                 },
                             },
+
+                            #[name = "title_hint"]
+                            gtk4::Label {
+                                set_text: "Leave empty to use the title suggested from the URL",
+                                set_halign: gtk4::Align::Start,
+                                set_visible: false,
+                                add_css_class: "dim-label",
+                                add_css_class: "caption",
+                            },
                         },
 
                         // Username field
@@ -181,6 +238,7 @@ impl Component for EntryEdit {
                                 #[watch]
                                 set_text: &model.entry.url,
                                 connect_changed[sender] => move |entry| {
+                                    crate::components::common::validate_url_entry(entry);
                                     sender.input(EntryEditInput::UrlChanged(entry.text().to_string()));
                                 },
                             },
@@ -214,13 +272,16 @@ impl Component for EntryEdit {
     }
 
     fn init(
-        _init: Self::Init,
+        suggest_titles: Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
         let model = EntryEdit {
             entry: Entry::new(),
             is_new: true,
+            group: None,
+            suggest_titles,
+            suggested_title: None,
             visible: false,
         };
 
@@ -245,9 +306,14 @@ impl Component for EntryEdit {
         _root: &Self::Root,
     ) {
         match message {
-            EntryEditInput::AddNew => {
+            EntryEditInput::AddNew { group_uuid, group_path } => {
                 self.entry = Entry::new();
                 self.is_new = true;
+                widgets.group_label.set_text(&group_path);
+                widgets.group_box.set_visible(true);
+                self.group = Some((group_uuid, group_path));
+                self.suggested_title = None;
+                self.update_title_suggestion(widgets);
                 self.visible = true;
                 widgets._notes_view.buffer().set_text("");
                 widgets.title_entry.set_text("");
@@ -264,11 +330,25 @@ impl Component for EntryEdit {
                 widgets.url_entry.set_text(&entry.url);
                 self.entry = entry;
                 self.is_new = false;
+                self.group = None;
+                widgets.group_box.set_visible(false);
+                self.suggested_title = None;
+                self.update_title_suggestion(widgets);
                 self.visible = true;
                 widgets.dialog.present();
             }
+            EntryEditInput::SetGroup { uuid, path } => {
+                if self.is_new {
+                    widgets.group_label.set_text(&path);
+                    self.group = Some((uuid, path));
+                }
+            }
+            EntryEditInput::ChooseGroup => {
+                let _ = sender.output(EntryEditOutput::ChooseGroup);
+            }
             EntryEditInput::TitleChanged(title) => {
                 self.entry.title = title;
+                self.update_title_suggestion(widgets);
             }
             EntryEditInput::UsernameChanged(username) => {
                 self.entry.username = username;
@@ -277,15 +357,34 @@ impl Component for EntryEdit {
                 self.entry.password = password;
             }
             EntryEditInput::UrlChanged(url) => {
+                self.suggested_title = if self.is_new && self.suggest_titles {
+                    title_from_url(&url)
+                } else {
+                    None
+                };
                 self.entry.url = url;
+                self.update_title_suggestion(widgets);
             }
             EntryEditInput::NotesChanged(notes) => {
                 self.entry.notes = notes;
             }
             EntryEditInput::Save => {
+                self.entry.url = normalize_url(&self.entry.url);
+                // A typed title always wins over the suggestion
+                if self.entry.title.trim().is_empty() {
+                    if let Some(title) = self.suggested_title.take() {
+                        self.entry.title = title;
+                    }
+                }
                 self.visible = false;
                 widgets.dialog.set_visible(false);
-                let _ = sender.output(EntryEditOutput::Saved(self.entry.clone()));
+                let _ = sender.output(match self.group.take() {
+                    Some((group_uuid, _)) if self.is_new => EntryEditOutput::Created {
+                        entry: self.entry.clone(),
+                        group_uuid,
+                    },
+                    _ => EntryEditOutput::Saved(self.entry.clone()),
+                });
             }
             EntryEditInput::Cancel => {
                 self.visible = false;
@@ -295,3 +394,12 @@ impl Component for EntryEdit {
         }
     }
 }
+
+impl EntryEdit {
+    /// Show the title suggested from the URL as placeholder while the title is empty.
+    fn update_title_suggestion(&self, widgets: &EntryEditWidgets) {
+        let suggestion = self.suggested_title.as_deref().filter(|_| self.entry.title.is_empty());
+        widgets.title_entry.set_placeholder_text(Some(suggestion.unwrap_or("Entry title")));
+        widgets.title_hint.set_visible(suggestion.is_some());
+    }
+}
@@ -0,0 +1,352 @@
+//! Entry history dialog component.
+//!
+//! Lists the previous versions of an entry, shows each one read-only with the
+//! fields that differ from the current entry highlighted, and lets the user
+//! restore a version.
+
+use crate::components::info_bar::format_timestamp;
+use gtk4::prelude::*;
+use keeprs_core::{Entry, FAVORITE_FIELD};
+use relm4::prelude::*;
+
+/// Messages for the history dialog.
+#[derive(Debug)]
+pub enum EntryHistoryInput {
+    /// Show the history of `current`; `versions` are ordered newest first.
+    Show { current: Entry, versions: Vec<Entry> },
+    /// A version row was selected.
+    Select(usize),
+    /// Toggle showing secrets in clear text.
+    ToggleReveal,
+    /// Restore the selected version.
+    Restore,
+    /// Close the dialog.
+    Close,
+}
+
+/// Output messages from the history dialog.
+#[derive(Debug, Clone)]
+pub enum EntryHistoryOutput {
+    /// Restore version `index` of the entry with the given UUID.
+    Restore { uuid: String, index: usize },
+}
+
+/// One compared field: (name, first value, second value, is secret).
+pub(crate) type FieldDiff = (String, String, String, bool);
+
+/// Entry history dialog model.
+pub struct EntryHistory {
+    datetime_format: String,
+    current: Option<Entry>,
+    versions: Vec<Entry>,
+    selected: Option<usize>,
+    reveal: bool,
+    visible: bool,
+}
+
+#[relm4::component(pub)]
+impl Component for EntryHistory {
+    type Init = String; // datetime_format
+    type Input = EntryHistoryInput;
+    type Output = EntryHistoryOutput;
+    type CommandOutput = ();
+
+    view! {
+        #[name = "dialog"]
+        gtk4::Window {
+            set_modal: true,
+            set_title: Some("Entry History"),
+            set_default_width: 720,
+            set_default_height: 480,
+            #[watch]
+            set_visible: model.visible,
+            connect_close_request[sender] => move |_| {
+                sender.input(EntryHistoryInput::Close);
+                gtk4::glib::Propagation::Stop
+            },
+
+            gtk4::Box {
+                set_orientation: gtk4::Orientation::Vertical,
+                set_spacing: 0,
+
+                gtk4::HeaderBar {
+                    set_show_title_buttons: true,
+
+                    #[wrap(Some)]
+                    set_title_widget = &gtk4::Label {
+                        #[watch]
+                        set_text: &model.current.as_ref().map(|e| format!("History of {}", e.title)).unwrap_or_default(),
+                        add_css_class: "title",
+                    },
+
+                    pack_end = &gtk4::Button {
+                        #[watch]
+                        set_icon_name: if model.reveal { "view-conceal-symbolic" } else { "view-reveal-symbolic" },
+                        #[watch]
+                        set_tooltip_text: Some(if model.reveal { "Hide Passwords" } else { "Show Passwords" }),
+                        connect_clicked => EntryHistoryInput::ToggleReveal,
+                    },
+                },
+
+                gtk4::Paned {
+                    set_orientation: gtk4::Orientation::Horizontal,
+                    set_position: 240,
+                    set_vexpand: true,
+
+                    #[wrap(Some)]
+                    set_start_child = &gtk4::ScrolledWindow {
+                        set_hscrollbar_policy: gtk4::PolicyType::Never,
+
+                        #[name = "version_list"]
+                        gtk4::ListBox {
+                            add_css_class: "navigation-sidebar",
+                            set_selection_mode: gtk4::SelectionMode::Single,
+
+                            connect_row_selected[sender] => move |_, row| {
+                                if let Some(row) = row {
+                                    sender.input(EntryHistoryInput::Select(row.index() as usize));
+                                }
+                            },
+                        },
+                    },
+
+                    #[wrap(Some)]
+                    set_end_child = &gtk4::ScrolledWindow {
+                        set_hscrollbar_policy: gtk4::PolicyType::Never,
+
+                        #[name = "details_box"]
+                        gtk4::Box {
+                            set_orientation: gtk4::Orientation::Vertical,
+                            set_spacing: 12,
+                            set_margin_all: 18,
+                        },
+                    },
+                },
+
+                gtk4::Box {
+                    set_orientation: gtk4::Orientation::Horizontal,
+                    set_spacing: 10,
+                    set_halign: gtk4::Align::End,
+                    set_margin_all: 12,
+
+                    gtk4::Button {
+                        set_label: "Close",
+                        connect_clicked => EntryHistoryInput::Close,
+                    },
+
+                    gtk4::Button {
+                        set_label: "Restore This Version",
+                        add_css_class: "suggested-action",
+                        #[watch]
+                        set_sensitive: model.selected.is_some(),
+                        connect_clicked => EntryHistoryInput::Restore,
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        datetime_format: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = EntryHistory {
+            datetime_format,
+            current: None,
+            versions: Vec::new(),
+            selected: None,
+            reveal: false,
+            visible: false,
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            EntryHistoryInput::Show { current, versions } => {
+                self.current = Some(current);
+                self.versions = versions;
+                self.selected = None;
+                self.reveal = false;
+
+                while let Some(row) = widgets.version_list.row_at_index(0) {
+                    widgets.version_list.remove(&row);
+                }
+                for version in &self.versions {
+                    widgets.version_list.append(&self.build_version_row(version));
+                }
+                // Selecting the newest version fills in the details pane
+                if let Some(first) = widgets.version_list.row_at_index(0) {
+                    widgets.version_list.select_row(Some(&first));
+                }
+
+                self.visible = true;
+            }
+            EntryHistoryInput::Select(index) => {
+                self.selected = (index < self.versions.len()).then_some(index);
+            }
+            EntryHistoryInput::ToggleReveal => {
+                self.reveal = !self.reveal;
+            }
+            EntryHistoryInput::Restore => {
+                if let (Some(index), Some(current)) = (self.selected, self.current.as_ref()) {
+                    let _ = sender.output(EntryHistoryOutput::Restore {
+                        uuid: current.uuid.clone(),
+                        index,
+                    });
+                    self.close();
+                }
+            }
+            EntryHistoryInput::Close => {
+                self.close();
+            }
+        }
+
+        self.rebuild_details(&widgets.details_box);
+        self.update_view(widgets, sender);
+    }
+}
+
+impl EntryHistory {
+    /// Hide the dialog and drop the entry data it held.
+    fn close(&mut self) {
+        self.visible = false;
+        self.current = None;
+        self.versions.clear();
+        self.selected = None;
+    }
+
+    fn build_version_row(&self, version: &Entry) -> gtk4::Box {
+        let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 2);
+        vbox.set_margin_top(6);
+        vbox.set_margin_bottom(6);
+        vbox.set_margin_start(6);
+
+        let time = match version.last_modified {
+            Some(time) => format_timestamp(time.and_utc().with_timezone(&chrono::Local), &self.datetime_format),
+            None => "Unknown time".to_string(),
+        };
+        let time_label = gtk4::Label::new(Some(&time));
+        time_label.set_halign(gtk4::Align::Start);
+        vbox.append(&time_label);
+
+        let title_label = gtk4::Label::new(Some(&version.title));
+        title_label.set_halign(gtk4::Align::Start);
+        title_label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+        title_label.add_css_class("dim-label");
+        title_label.add_css_class("caption");
+        vbox.append(&title_label);
+
+        vbox
+    }
+
+    /// Show the selected version's fields, marking the ones that differ from the current entry.
+    fn rebuild_details(&self, details_box: &gtk4::Box) {
+        while let Some(child) = details_box.first_child() {
+            details_box.remove(&child);
+        }
+
+        let (Some(current), Some(version)) = (
+            self.current.as_ref(),
+            self.selected.and_then(|index| self.versions.get(index)),
+        ) else {
+            let placeholder = gtk4::Label::new(Some("Select a version"));
+            placeholder.add_css_class("dim-label");
+            placeholder.set_vexpand(true);
+            details_box.append(&placeholder);
+            return;
+        };
+
+        for (name, old, now, secret) in compare_entries(version, current) {
+            let changed = old != now;
+            let show = |value: &str| {
+                if secret && !self.reveal && !value.is_empty() {
+                    "••••••••".to_string()
+                } else {
+                    value.to_string()
+                }
+            };
+
+            let field_box = gtk4::Box::new(gtk4::Orientation::Vertical, 4);
+
+            let name_label = gtk4::Label::new(Some(&if changed { format!("{} (changed)", name) } else { name }));
+            name_label.set_halign(gtk4::Align::Start);
+            name_label.add_css_class("caption-heading");
+            if changed {
+                name_label.add_css_class("warning");
+            }
+            field_box.append(&name_label);
+
+            let value_label = gtk4::Label::new(Some(&show(&old)));
+            value_label.set_halign(gtk4::Align::Start);
+            value_label.set_wrap(true);
+            value_label.set_selectable(true);
+            if old.is_empty() {
+                value_label.set_text("(empty)");
+                value_label.add_css_class("dim-label");
+            }
+            field_box.append(&value_label);
+
+            if changed {
+                let current_label = gtk4::Label::new(Some(&format!(
+                    "Current: {}",
+                    if now.is_empty() { "(empty)".to_string() } else { show(&now) }
+                )));
+                current_label.set_halign(gtk4::Align::Start);
+                current_label.set_wrap(true);
+                current_label.add_css_class("dim-label");
+                current_label.add_css_class("caption");
+                field_box.append(&current_label);
+            }
+
+            details_box.append(&field_box);
+        }
+    }
+}
+
+/// Pair up the fields of two entries, such as a history version and the
+/// current entry.
+pub(crate) fn compare_entries(version: &Entry, current: &Entry) -> Vec<FieldDiff> {
+    let mut fields: Vec<FieldDiff> = vec![
+        ("Title".to_string(), version.title.clone(), current.title.clone(), false),
+        ("Username".to_string(), version.username.clone(), current.username.clone(), false),
+        ("Password".to_string(), version.password.clone(), current.password.clone(), true),
+        ("URL".to_string(), version.url.clone(), current.url.clone(), false),
+        ("Notes".to_string(), version.notes.clone(), current.notes.clone(), false),
+        ("Tags".to_string(), version.tags.join(", "), current.tags.join(", "), false),
+    ];
+
+    let mut keys: Vec<&String> = version
+        .custom_fields
+        .keys()
+        .chain(current.custom_fields.keys())
+        .filter(|key| key.as_str() != FAVORITE_FIELD)
+        .collect();
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        fields.push((
+            key.clone(),
+            version.custom_fields.get(key).cloned().unwrap_or_default(),
+            current.custom_fields.get(key).cloned().unwrap_or_default(),
+            key.eq_ignore_ascii_case("otp"),
+        ));
+    }
+
+    let attachment_names = |entry: &Entry| {
+        entry.attachments.iter().map(|a| a.filename.as_str()).collect::<Vec<_>>().join(", ")
+    };
+    fields.push(("Attachments".to_string(), attachment_names(version), attachment_names(current), false));
+
+    fields
+}
@@ -2,17 +2,23 @@
 //!
 //! Simple dialog to creating/editing groups (folders).
 
-use keeprs_core::Group;
+use super::common;
+use keeprs_core::{Group, PasswordPolicy};
 use gtk4::prelude::*;
 use relm4::prelude::*;
 
 /// Messages for group edit dialog.
 #[derive(Debug)]
 pub enum GroupEditInput {
-    /// Open dialog to add a new group.
-    AddNew,
+    /// Open dialog to add a new group. `inherited_policy` is the password
+    /// policy the new group gets from its ancestors.
+    AddNew { inherited_policy: Option<String> },
+    /// Open dialog to edit an existing group.
+    Edit { group: Group, inherited_policy: Option<String> },
     /// Name changed.
     NameChanged(String),
+    /// Default password policy changed (text form, empty to inherit).
+    PolicyChanged(String),
     /// Save the group.
     Save,
     /// Cancel editing.
@@ -22,7 +28,7 @@ pub enum GroupEditInput {
 /// Output messages from group edit dialog.
 #[derive(Debug, Clone)]
 pub enum GroupEditOutput {
-    /// Group was saved.
+    /// Group was saved (with an empty UUID when it is new).
     Saved(Group),
     /// Dialog was cancelled.
     Cancelled,
@@ -60,6 +66,7 @@ impl Component for GroupEdit {
                     set_show_title_buttons: false,
 
                     #[wrap(Some)]
+                    #[name = "title_label"]
                     set_title_widget = &gtk4::Label {
                         set_text: "Add Folder",
                         add_css_class: "title",
@@ -106,6 +113,30 @@ impl Component for GroupEdit {
                             }
                         },
                     },
+
+                    // Default password policy for entries in the folder
+                    gtk4::Box {
+                        set_orientation: gtk4::Orientation::Vertical,
+                        set_spacing: 4,
+
+                        gtk4::Label {
+                            set_text: "Password Policy",
+                            set_halign: gtk4::Align::Start,
+                            add_css_class: "dim-label",
+                        },
+
+                        #[name = "policy_entry"]
+                        gtk4::Entry {
+                            set_tooltip_text: Some("Used when generating passwords for entries in this folder; leave empty to inherit"),
+                            connect_changed[sender] => move |entry| {
+                                common::validate_password_policy_entry(entry);
+                                sender.input(GroupEditInput::PolicyChanged(entry.text().to_string()));
+                            },
+                            connect_activate[sender] => move |_| {
+                                sender.input(GroupEditInput::Save);
+                            }
+                        },
+                    },
                 },
             },
         }
@@ -117,13 +148,7 @@ impl Component for GroupEdit {
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
         let model = GroupEdit {
-            group: Group {
-                uuid: String::new(),
-                name: String::new(),
-                children: Vec::new(),
-                entries: Vec::new(),
-                is_recycle_bin: false,
-            },
+            group: Group::default(),
             visible: false,
         };
 
@@ -140,24 +165,24 @@ impl Component for GroupEdit {
         _root: &Self::Root,
     ) {
         match message {
-            GroupEditInput::AddNew => {
-                self.group = Group {
-                    uuid: String::new(), // new uuid will be assigned by backend
-                    name: String::new(),
-                    children: Vec::new(),
-                    entries: Vec::new(),
-                    is_recycle_bin: false,
-                };
-                self.visible = true;
-                widgets.name_entry.set_text("");
-                widgets.dialog.present();
-                widgets.name_entry.grab_focus();
+            GroupEditInput::AddNew { inherited_policy } => {
+                // The new uuid will be assigned by the backend
+                self.group = Group::default();
+                self.show(widgets, "Add Folder", inherited_policy);
+            }
+            GroupEditInput::Edit { group, inherited_policy } => {
+                self.group = group;
+                self.show(widgets, "Edit Folder", inherited_policy);
             }
             GroupEditInput::NameChanged(name) => {
                 self.group.name = name;
             }
+            GroupEditInput::PolicyChanged(policy) => {
+                self.group.password_policy = (!policy.trim().is_empty()).then_some(policy);
+            }
             GroupEditInput::Save => {
-                if !self.group.name.is_empty() {
+                let policy_valid = self.group.password_policy.as_deref().is_none_or(|p| p.parse::<PasswordPolicy>().is_ok());
+                if !self.group.name.is_empty() && policy_valid {
                     self.visible = false;
                     widgets.dialog.set_visible(false);
                     let _ = sender.output(GroupEditOutput::Saved(self.group.clone()));
@@ -171,3 +196,19 @@ impl Component for GroupEdit {
         }
     }
 }
+
+impl GroupEdit {
+    /// Fill the fields from `self.group` and present the dialog.
+    fn show(&mut self, widgets: &GroupEditWidgets, title: &str, inherited_policy: Option<String>) {
+        let placeholder = inherited_policy.unwrap_or_else(|| PasswordPolicy::default().to_string());
+
+        self.visible = true;
+        widgets.dialog.set_title(Some(title));
+        widgets.title_label.set_text(title);
+        widgets.name_entry.set_text(&self.group.name);
+        widgets.policy_entry.set_text(self.group.password_policy.as_deref().unwrap_or_default());
+        widgets.policy_entry.set_placeholder_text(Some(&placeholder));
+        widgets.dialog.present();
+        widgets.name_entry.grab_focus();
+    }
+}
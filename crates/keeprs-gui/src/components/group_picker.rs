@@ -0,0 +1,256 @@
+//! Group picker dialog component.
+//!
+//! Modal dialog listing the group tree so the user can choose a target group.
+
+use crate::components::common;
+use gtk4::prelude::*;
+use keeprs_core::Group;
+use relm4::prelude::*;
+use std::fmt;
+use std::rc::Rc;
+
+/// Predicate deciding which groups may be picked.
+///
+/// Groups rejected by the filter are hidden together with their subtree.
+#[derive(Clone)]
+pub struct GroupFilter(Rc<dyn Fn(&Group) -> bool>);
+
+impl GroupFilter {
+    /// Create a filter from a predicate returning `true` for allowed groups.
+    pub fn new(predicate: impl Fn(&Group) -> bool + 'static) -> Self {
+        Self(Rc::new(predicate))
+    }
+
+    /// Exclude the group with the given UUID and everything below it.
+    ///
+    /// Used when moving a group, which can't become its own descendant.
+    pub fn excluding_subtree(uuid: impl Into<String>) -> Self {
+        let uuid = uuid.into();
+        Self::new(move |group| group.uuid != uuid)
+    }
+
+    fn allows(&self, group: &Group) -> bool {
+        (self.0)(group)
+    }
+}
+
+impl fmt::Debug for GroupFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("GroupFilter(..)")
+    }
+}
+
+/// Messages for the group picker.
+#[derive(Debug)]
+pub enum GroupPickerInput {
+    /// Show the picker for the given tree, optionally restricted by a filter.
+    Show {
+        root: Group,
+        title: String,
+        filter: Option<GroupFilter>,
+    },
+    /// A group row was selected.
+    Select(String),
+    /// Confirm the selected group.
+    Confirm,
+    /// Close without choosing.
+    Cancel,
+}
+
+/// Output messages from the group picker.
+#[derive(Debug, Clone)]
+pub enum GroupPickerOutput {
+    /// User chose a group.
+    Picked(String),
+    /// Dialog was cancelled.
+    Cancelled,
+}
+
+/// Group picker model.
+pub struct GroupPicker {
+    title: String,
+    selected_uuid: Option<String>,
+    visible: bool,
+}
+
+#[relm4::component(pub)]
+impl Component for GroupPicker {
+    type Init = ();
+    type Input = GroupPickerInput;
+    type Output = GroupPickerOutput;
+    type CommandOutput = ();
+
+    view! {
+        #[name = "dialog"]
+        gtk4::Window {
+            set_modal: true,
+            set_default_width: 360,
+            set_default_height: 420,
+            #[watch]
+            set_title: Some(&model.title),
+            #[watch]
+            set_visible: model.visible,
+            connect_close_request[sender] => move |_| {
+                sender.input(GroupPickerInput::Cancel);
+                gtk4::glib::Propagation::Stop
+            },
+
+            gtk4::Box {
+                set_orientation: gtk4::Orientation::Vertical,
+                set_spacing: 0,
+
+                gtk4::HeaderBar {
+                    set_show_title_buttons: true,
+
+                    #[wrap(Some)]
+                    set_title_widget = &gtk4::Label {
+                        #[watch]
+                        set_text: &model.title,
+                        add_css_class: "title",
+                    },
+                },
+
+                gtk4::ScrolledWindow {
+                    set_hscrollbar_policy: gtk4::PolicyType::Never,
+                    set_vexpand: true,
+
+                    #[name = "list_box"]
+                    gtk4::ListBox {
+                        add_css_class: "navigation-sidebar",
+                        set_selection_mode: gtk4::SelectionMode::Single,
+
+                        connect_row_selected[sender] => move |_, row| {
+                            if let Some(row) = row {
+                                sender.input(GroupPickerInput::Select(row.widget_name().to_string()));
+                            }
+                        },
+                        connect_row_activated[sender] => move |_, _| {
+                            sender.input(GroupPickerInput::Confirm);
+                        },
+                    },
+                },
+
+                gtk4::Box {
+                    set_orientation: gtk4::Orientation::Horizontal,
+                    set_spacing: 10,
+                    set_halign: gtk4::Align::End,
+                    set_margin_all: 12,
+
+                    gtk4::Button {
+                        set_label: "Cancel",
+                        connect_clicked => GroupPickerInput::Cancel,
+                    },
+
+                    gtk4::Button {
+                        set_label: "Select",
+                        add_css_class: "suggested-action",
+                        #[watch]
+                        set_sensitive: model.selected_uuid.is_some(),
+                        connect_clicked => GroupPickerInput::Confirm,
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = GroupPicker {
+            title: "Choose Group".to_string(),
+            selected_uuid: None,
+            visible: false,
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            GroupPickerInput::Show { root, title, filter } => {
+                self.title = title;
+                self.selected_uuid = None;
+
+                while let Some(row) = widgets.list_box.row_at_index(0) {
+                    widgets.list_box.remove(&row);
+                }
+                Self::add_group_rows(&widgets.list_box, &root, filter.as_ref(), &[], true);
+
+                self.visible = true;
+            }
+            GroupPickerInput::Select(uuid) => {
+                self.selected_uuid = Some(uuid);
+            }
+            GroupPickerInput::Confirm => {
+                if let Some(uuid) = self.selected_uuid.take() {
+                    self.visible = false;
+                    let _ = sender.output(GroupPickerOutput::Picked(uuid));
+                }
+            }
+            GroupPickerInput::Cancel => {
+                self.visible = false;
+                self.selected_uuid = None;
+                let _ = sender.output(GroupPickerOutput::Cancelled);
+            }
+        }
+        self.update_view(widgets, sender);
+    }
+}
+
+impl GroupPicker {
+    /// Append a row for `group` and its children, drawn like the sidebar tree.
+    ///
+    /// The recycle bin is left out since it isn't a valid target, as is any
+    /// group rejected by `filter`.
+    fn add_group_rows(
+        list_box: &gtk4::ListBox,
+        group: &Group,
+        filter: Option<&GroupFilter>,
+        levels: &[bool],
+        is_last: bool,
+    ) {
+        let row = gtk4::ListBoxRow::new();
+        row.set_widget_name(&group.uuid);
+
+        let hbox = gtk4::Box::new(gtk4::Orientation::Horizontal, 0);
+        hbox.append(&common::create_tree_lines(levels, is_last));
+
+        let icon = gtk4::Image::from_icon_name("folder-symbolic");
+        icon.set_margin_start(4);
+        icon.set_margin_end(8);
+        hbox.append(&icon);
+
+        let label = gtk4::Label::new(Some(&group.name));
+        label.set_halign(gtk4::Align::Start);
+        label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+        hbox.append(&label);
+
+        row.set_child(Some(&hbox));
+        list_box.append(&row);
+
+        let children: Vec<&Group> = group
+            .children
+            .iter()
+            .filter(|child| !child.is_recycle_bin)
+            .filter(|child| filter.is_none_or(|f| f.allows(child)))
+            .collect();
+
+        let mut child_levels = levels.to_vec();
+        child_levels.push(is_last);
+
+        for (i, child) in children.iter().enumerate() {
+            let child_is_last = i == children.len() - 1;
+            Self::add_group_rows(list_box, child, filter, &child_levels, child_is_last);
+        }
+    }
+}
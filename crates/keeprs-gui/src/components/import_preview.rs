@@ -0,0 +1,194 @@
+//! Import preview dialog component.
+//!
+//! Modal dialog showing what an import would create (from a dry run) and
+//! asking before anything is written to the database.
+
+use gtk4::prelude::*;
+use keeprs_core::ImportReport;
+use relm4::prelude::*;
+
+/// Messages for the import preview dialog.
+#[derive(Debug)]
+pub enum ImportPreviewInput {
+    /// Show the dry-run report for the file named `source`, imported into `target`.
+    Show { source: String, target: String, report: ImportReport },
+    /// Go ahead with the import.
+    Import,
+    /// Cancel.
+    Cancel,
+}
+
+/// Output messages.
+#[derive(Debug, Clone)]
+pub enum ImportPreviewOutput {
+    /// User confirmed the import.
+    Confirmed,
+    /// Dialog was cancelled.
+    Cancelled,
+}
+
+/// Component model.
+pub struct ImportPreviewDialog {
+    summary: String,
+    details: String,
+    can_import: bool,
+    visible: bool,
+}
+
+impl ImportPreviewDialog {
+    fn summarize(source: &str, target: &str, report: &ImportReport) -> String {
+        let mut summary = format!("{} will add {} entries to {}", source, report.entries_imported, target);
+        if report.groups_created > 0 {
+            summary.push_str(&format!(" and create {} folders", report.groups_created));
+        }
+        summary.push('.');
+        if !report.skipped.is_empty() {
+            summary.push_str(&format!(" {} records can't be imported and will be skipped.", report.skipped.len()));
+        }
+        summary
+    }
+
+    fn describe(report: &ImportReport) -> String {
+        let mut lines = Vec::new();
+        if !report.new_groups.is_empty() {
+            lines.push("New folders:".to_string());
+            lines.extend(report.new_groups.iter().map(|path| format!("  {}", path)));
+        }
+        if !report.skipped.is_empty() {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push("Skipped:".to_string());
+            lines.extend(report.skipped.iter().map(|reason| format!("  {}", reason)));
+        }
+        lines.join("\n")
+    }
+}
+
+#[relm4::component(pub)]
+impl Component for ImportPreviewDialog {
+    type Init = ();
+    type Input = ImportPreviewInput;
+    type Output = ImportPreviewOutput;
+    type CommandOutput = ();
+
+    view! {
+        #[name = "dialog"]
+        gtk4::Window {
+            set_modal: true,
+            set_default_width: 480,
+            set_title: Some("Import Entries"),
+            #[watch]
+            set_visible: model.visible,
+            connect_close_request[sender] => move |_| {
+                sender.input(ImportPreviewInput::Cancel);
+                gtk4::glib::Propagation::Stop
+            },
+
+            gtk4::Box {
+                set_orientation: gtk4::Orientation::Vertical,
+                set_spacing: 0,
+
+                // Header bar
+                gtk4::HeaderBar {
+                    set_show_title_buttons: true,
+
+                    #[wrap(Some)]
+                    set_title_widget = &gtk4::Label {
+                        set_text: "Import Entries",
+                        add_css_class: "title",
+                    },
+                },
+
+                gtk4::Box {
+                    set_orientation: gtk4::Orientation::Vertical,
+                    set_spacing: 16,
+                    set_margin_all: 24,
+
+                    gtk4::Label {
+                        #[watch]
+                        set_text: &model.summary,
+                        set_wrap: true,
+                        set_halign: gtk4::Align::Start,
+                    },
+
+                    gtk4::ScrolledWindow {
+                        #[watch]
+                        set_visible: !model.details.is_empty(),
+                        set_hscrollbar_policy: gtk4::PolicyType::Never,
+                        set_min_content_height: 80,
+                        set_max_content_height: 240,
+                        set_propagate_natural_height: true,
+
+                        gtk4::Label {
+                            #[watch]
+                            set_text: &model.details,
+                            set_wrap: true,
+                            set_selectable: true,
+                            set_xalign: 0.0,
+                            set_yalign: 0.0,
+                            add_css_class: "dim-label",
+                        },
+                    },
+
+                    // Buttons
+                    gtk4::Box {
+                        set_orientation: gtk4::Orientation::Horizontal,
+                        set_spacing: 10,
+                        set_halign: gtk4::Align::End,
+                        set_margin_top: 10,
+
+                        gtk4::Button {
+                            set_label: "Cancel",
+                            connect_clicked => ImportPreviewInput::Cancel,
+                        },
+
+                        gtk4::Button {
+                            set_label: "Import",
+                            add_css_class: "suggested-action",
+                            #[watch]
+                            set_sensitive: model.can_import,
+                            connect_clicked => ImportPreviewInput::Import,
+                        },
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = ImportPreviewDialog {
+            summary: String::new(),
+            details: String::new(),
+            can_import: false,
+            visible: false,
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, _root: &Self::Root) {
+        match message {
+            ImportPreviewInput::Show { source, target, report } => {
+                self.summary = Self::summarize(&source, &target, &report);
+                self.details = Self::describe(&report);
+                self.can_import = report.entries_imported > 0 || report.groups_created > 0;
+                self.visible = true;
+            }
+            ImportPreviewInput::Import => {
+                self.visible = false;
+                let _ = sender.output(ImportPreviewOutput::Confirmed);
+            }
+            ImportPreviewInput::Cancel => {
+                self.visible = false;
+                let _ = sender.output(ImportPreviewOutput::Cancelled);
+            }
+        }
+    }
+}
@@ -38,10 +38,27 @@ fn get_system_locale() -> chrono::Locale {
 }
 
 /// Format the current time for display in the info bar.
-pub fn format_save_time() -> String {
-    chrono::Local::now()
-        .format_localized("%X", get_system_locale())
-        .to_string()
+///
+/// Uses the configured `datetime_format`, or the locale's time (with seconds) when it is empty.
+pub fn format_save_time(format: &str) -> String {
+    format_datetime(chrono::Local::now(), if format.is_empty() { "%X" } else { format })
+}
+
+/// Format a timestamp with the configured `datetime_format`, or the locale's date and time when it is empty.
+pub fn format_timestamp(time: chrono::DateTime<chrono::Local>, format: &str) -> String {
+    format_datetime(time, if format.is_empty() { "%x %X" } else { format })
+}
+
+/// Format with a strftime-style pattern, falling back to ISO 8601 if the pattern is invalid.
+fn format_datetime(time: chrono::DateTime<chrono::Local>, format: &str) -> String {
+    use std::fmt::Write;
+
+    let mut out = String::new();
+    if write!(out, "{}", time.format_localized(format, get_system_locale())).is_err() {
+        tracing::warn!("Invalid datetime_format '{}', using ISO 8601", format);
+        out = time.format("%Y-%m-%d %H:%M:%S").to_string();
+    }
+    out
 }
 
 /// Input messages for the info bar.
@@ -63,8 +80,23 @@ pub enum InfoBarInput {
     SetLastSaveTime(String),
 }
 
+/// Settings for the info bar.
+#[derive(Debug, Clone)]
+pub struct InfoBarInit {
+    /// Changes are only saved with the save shortcut, so unsaved changes
+    /// are shown prominently.
+    pub manual_save: bool,
+    /// Save shortcut to mention in the unsaved changes notice.
+    pub save_shortcut: String,
+    /// The database is open read-only.
+    pub read_only: bool,
+}
+
 /// Info bar model state.
 pub struct InfoBar {
+    manual_save: bool,
+    save_shortcut: String,
+    read_only: bool,
     db_filename: Option<String>,
     db_full_path: String,
     entry_count: usize,
@@ -76,7 +108,7 @@ pub struct InfoBar {
 
 #[relm4::component(pub)]
 impl Component for InfoBar {
-    type Init = ();
+    type Init = InfoBarInit;
     type Input = InfoBarInput;
     type Output = ();
     type CommandOutput = ();
@@ -153,6 +185,23 @@ impl Component for InfoBar {
                             add_css_class: "dim-label",
                         },
                     },
+
+                    // Read-only mode
+                    gtk4::Box {
+                        set_orientation: gtk4::Orientation::Horizontal,
+                        set_spacing: 6,
+                        set_visible: model.read_only,
+                        set_tooltip_text: Some("Changes can't be made or saved"),
+
+                        gtk4::Image {
+                            set_icon_name: Some("changes-prevent-symbolic"),
+                            add_css_class: "warning",
+                        },
+                        gtk4::Label {
+                            set_label: "Read-only",
+                            add_css_class: "warning",
+                        },
+                    },
                 },
 
                 // Right: Unsaved Indicator + Last saved status
@@ -161,11 +210,12 @@ impl Component for InfoBar {
                     set_orientation: gtk4::Orientation::Horizontal,
                     set_spacing: 12,
 
-                    // Unsaved changes indicator (only show dot when there are unsaved changes)
+                    // Unsaved changes indicator: a dot, or a notice when saving is manual
                     gtk4::Label {
                         #[watch]
-                        set_label: if model.unsaved_changes { "●" } else { "" },
-                        add_css_class: "dim-label",
+                        set_label: &model.unsaved_label(),
+                        #[watch]
+                        set_css_classes: if model.manual_save && model.unsaved_changes { &["warning"] } else { &["dim-label"] },
                     },
 
                     // Last Save Status - always visible
@@ -211,11 +261,14 @@ impl Component for InfoBar {
     }
 
     fn init(
-        _init: Self::Init,
+        init: Self::Init,
         root: Self::Root,
         _sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
         let model = InfoBar {
+            manual_save: init.manual_save,
+            save_shortcut: init.save_shortcut,
+            read_only: init.read_only,
             db_filename: None,
             db_full_path: String::new(),
             entry_count: 0,
@@ -255,3 +308,13 @@ impl Component for InfoBar {
         }
     }
 }
+
+impl InfoBar {
+    fn unsaved_label(&self) -> String {
+        match (self.unsaved_changes, self.manual_save) {
+            (false, _) => String::new(),
+            (true, false) => "●".to_string(),
+            (true, true) => format!("● Unsaved changes ({} to save)", self.save_shortcut),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Minimal markdown to Pango markup conversion for entry notes.
+//!
+//! Covers what notes typically use: headings, bullet and numbered lists,
+//! quotes, fenced code, `code`, **bold**, *italic*, [links](https://…) and
+//! bare URLs. Anything else is shown as written.
+
+use gtk4::glib;
+
+/// Convert markdown `text` to Pango markup.
+pub fn to_pango(text: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            lines.push(format!("<tt>{}</tt>", glib::markup_escape_text(line)));
+            continue;
+        }
+        lines.push(block(line, trimmed));
+    }
+
+    lines.join("\n")
+}
+
+/// Markup for one line outside code blocks.
+fn block(line: &str, trimmed: &str) -> String {
+    let indent = "    ".repeat((line.len() - trimmed.len()) / 2);
+
+    let level = trimmed.chars().take_while(|&c| c == '#').count();
+    if (1..=6).contains(&level) {
+        if let Some(title) = trimmed[level..].strip_prefix(' ') {
+            let size = match level {
+                1 => "x-large",
+                2 => "large",
+                _ => "medium",
+            };
+            return format!("<span size=\"{}\" weight=\"bold\">{}</span>", size, inline(title.trim()));
+        }
+    }
+
+    if matches!(trimmed, "---" | "***" | "___") {
+        return "<span alpha=\"50%\">――――――――――</span>".to_string();
+    }
+
+    if let Some(item) = ["- ", "* ", "+ "].iter().find_map(|marker| trimmed.strip_prefix(marker)) {
+        return format!("{}  •  {}", indent, inline(item));
+    }
+
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        if let Some(item) = trimmed[digits..].strip_prefix(". ") {
+            return format!("{}  {}.  {}", indent, &trimmed[..digits], inline(item));
+        }
+    }
+
+    if let Some(quote) = trimmed.strip_prefix('>') {
+        return format!("<span alpha=\"70%\"><i>│ {}</i></span>", inline(quote.trim_start()));
+    }
+
+    format!("{}{}", indent, inline(trimmed))
+}
+
+/// Markup for inline formatting within a line.
+fn inline(text: &str) -> String {
+    inline_with(text, true)
+}
+
+/// Markup for inline formatting; `links` is off inside link labels, which
+/// can't hold another link.
+fn inline_with(text: &str, links: bool) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    let mut prev: Option<char> = None;
+
+    while let Some(c) = rest.chars().next() {
+        let at_word_start = !prev.is_some_and(char::is_alphanumeric);
+
+        if c == '`' {
+            if let Some(end) = rest[1..].find('`') {
+                out.push_str(&format!("<tt>{}</tt>", glib::markup_escape_text(&rest[1..1 + end])));
+                rest = &rest[end + 2..];
+                prev = Some('`');
+                continue;
+            }
+        }
+
+        if let Some(marker) = ["**", "__"].into_iter().find(|m| rest.starts_with(m)) {
+            let after = &rest[2..];
+            if let Some(end) = after.find(marker).filter(|&end| end > 0) {
+                out.push_str(&format!("<b>{}</b>", inline_with(&after[..end], links)));
+                rest = &after[end + 2..];
+                prev = Some('*');
+                continue;
+            }
+        }
+
+        if (c == '*' || c == '_') && at_word_start {
+            let after = &rest[1..];
+            if let Some(end) = after.find(c).filter(|&end| end > 0 && !after.starts_with(' ')) {
+                out.push_str(&format!("<i>{}</i>", inline_with(&after[..end], links)));
+                rest = &after[end + 1..];
+                prev = Some(c);
+                continue;
+            }
+        }
+
+        if c == '[' && links {
+            if let Some((label, url, len)) = link(rest) {
+                out.push_str(&format!("<a href=\"{}\">{}</a>", glib::markup_escape_text(url), inline_with(label, false)));
+                rest = &rest[len..];
+                prev = Some(')');
+                continue;
+            }
+        }
+
+        if links && at_word_start && is_linkable(rest) {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            // Sentence punctuation after a URL isn't part of it
+            let url = rest[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')']);
+            let escaped = glib::markup_escape_text(url);
+            out.push_str(&format!("<a href=\"{}\">{}</a>", escaped, escaped));
+            rest = &rest[url.len()..];
+            prev = Some('/');
+            continue;
+        }
+
+        out.push_str(&glib::markup_escape_text(&rest[..c.len_utf8()]));
+        rest = &rest[c.len_utf8()..];
+        prev = Some(c);
+    }
+
+    out
+}
+
+/// Parse `[label](url)` at the start of `text`: (label, url, length consumed).
+fn link(text: &str) -> Option<(&str, &str, usize)> {
+    let close = text.find("](")?;
+    let url_start = close + 2;
+    let url_len = text[url_start..].find(')')?;
+    let url = &text[url_start..url_start + url_len];
+    is_linkable(url).then(|| (&text[1..close], url, url_start + url_len + 1))
+}
+
+/// Only web and mail links are made clickable.
+fn is_linkable(url: &str) -> bool {
+    ["https://", "http://", "mailto:"].iter().any(|scheme| url.starts_with(scheme))
+}
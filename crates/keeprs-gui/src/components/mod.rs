@@ -1,11 +1,19 @@
 
 pub mod sidebar;
 pub mod entry_browser;
+pub mod entry_compare;
 pub mod entry_edit;
+pub mod entry_history;
 pub mod group_edit;
+pub mod group_picker;
+pub mod import_preview;
 pub mod search_palette;
 pub mod info_bar;
 pub mod unlock;
 pub mod password_confirmation;
+pub mod new_password;
 pub mod common;
+pub mod markdown;
 pub mod entry_detail_view;
+pub mod toast;
+pub mod unsaved_changes;
@@ -0,0 +1,232 @@
+//! New password dialog component.
+//!
+//! Modal dialog asking for a new password twice, e.g. for a re-keyed copy
+//! of the database.
+
+use gtk4::prelude::*;
+use relm4::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Messages for the new password dialog.
+#[derive(Debug)]
+pub enum NewPasswordInput {
+    /// Show the dialog with a message.
+    Show { title: String, message: String },
+    /// Password text changed.
+    PasswordChanged(String),
+    /// Confirmation text changed.
+    ConfirmChanged(String),
+    /// Accept the password.
+    Accept,
+    /// Cancel.
+    Cancel,
+}
+
+/// Output messages.
+#[derive(Debug, Clone)]
+pub enum NewPasswordOutput {
+    /// User chose a password (wiped from memory when dropped).
+    Chosen(Zeroizing<String>),
+    /// Dialog was cancelled.
+    Cancelled,
+}
+
+/// Component model.
+pub struct NewPasswordDialog {
+    password: Zeroizing<String>,
+    confirm: Zeroizing<String>,
+    title: String,
+    message: String,
+    visible: bool,
+}
+
+impl NewPasswordDialog {
+    fn error(&self) -> Option<&'static str> {
+        if !self.confirm.is_empty() && self.password != self.confirm {
+            Some("Passwords do not match")
+        } else {
+            None
+        }
+    }
+
+    fn can_accept(&self) -> bool {
+        !self.password.is_empty() && self.password == self.confirm
+    }
+
+    fn clear(&mut self, widgets: &NewPasswordDialogWidgets) {
+        self.password.zeroize();
+        self.confirm.zeroize();
+        // Manually clear the widgets to avoid recursive signal loops with #[watch]
+        widgets.password_entry.set_text("");
+        widgets.confirm_entry.set_text("");
+    }
+}
+
+#[relm4::component(pub)]
+impl Component for NewPasswordDialog {
+    type Init = ();
+    type Input = NewPasswordInput;
+    type Output = NewPasswordOutput;
+    type CommandOutput = ();
+
+    view! {
+        #[name = "dialog"]
+        gtk4::Window {
+            set_modal: true,
+            set_default_width: 400,
+            set_resizable: false,
+            #[watch]
+            set_title: Some(&model.title),
+            #[watch]
+            set_visible: model.visible,
+            connect_close_request[sender] => move |_| {
+                sender.input(NewPasswordInput::Cancel);
+                gtk4::glib::Propagation::Stop
+            },
+
+            gtk4::Box {
+                set_orientation: gtk4::Orientation::Vertical,
+                set_spacing: 0,
+
+                // Header bar
+                gtk4::HeaderBar {
+                    set_show_title_buttons: true,
+
+                    #[wrap(Some)]
+                    set_title_widget = &gtk4::Label {
+                        #[watch]
+                        set_text: &model.title,
+                        add_css_class: "title",
+                    },
+                },
+
+                gtk4::Box {
+                    set_orientation: gtk4::Orientation::Vertical,
+                    set_spacing: 16,
+                    set_margin_all: 24,
+
+                    // Message
+                    gtk4::Label {
+                        #[watch]
+                        set_text: &model.message,
+                        set_wrap: true,
+                        set_halign: gtk4::Align::Start,
+                    },
+
+                    gtk4::Box {
+                        set_orientation: gtk4::Orientation::Vertical,
+                        set_spacing: 8,
+
+                        #[name = "password_entry"]
+                        gtk4::PasswordEntry {
+                            set_show_peek_icon: true,
+                            set_placeholder_text: Some("New Password"),
+                            connect_changed[sender] => move |entry| {
+                                sender.input(NewPasswordInput::PasswordChanged(entry.text().to_string()));
+                            },
+                        },
+
+                        #[name = "confirm_entry"]
+                        gtk4::PasswordEntry {
+                            set_show_peek_icon: true,
+                            set_placeholder_text: Some("Confirm Password"),
+                            connect_changed[sender] => move |entry| {
+                                sender.input(NewPasswordInput::ConfirmChanged(entry.text().to_string()));
+                            },
+                            connect_activate[sender] => move |_| {
+                                sender.input(NewPasswordInput::Accept);
+                            }
+                        },
+                    },
+
+                    // Error label
+                    gtk4::Label {
+                        #[watch]
+                        set_visible: model.error().is_some(),
+                        #[watch]
+                        set_text: model.error().unwrap_or(""),
+                        add_css_class: "error",
+                        set_halign: gtk4::Align::Start,
+                    },
+
+                    // Buttons
+                    gtk4::Box {
+                        set_orientation: gtk4::Orientation::Horizontal,
+                        set_spacing: 10,
+                        set_halign: gtk4::Align::End,
+                        set_margin_top: 10,
+
+                        gtk4::Button {
+                            set_label: "Cancel",
+                            connect_clicked => NewPasswordInput::Cancel,
+                        },
+
+                        gtk4::Button {
+                            set_label: "OK",
+                            add_css_class: "suggested-action",
+                            #[watch]
+                            set_sensitive: model.can_accept(),
+                            connect_clicked => NewPasswordInput::Accept,
+                        },
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = NewPasswordDialog {
+            password: Zeroizing::new(String::new()),
+            confirm: Zeroizing::new(String::new()),
+            title: String::new(),
+            message: String::new(),
+            visible: false,
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            NewPasswordInput::Show { title, message } => {
+                self.title = title;
+                self.message = message;
+                self.clear(widgets);
+                self.visible = true;
+                widgets.password_entry.grab_focus();
+            }
+            NewPasswordInput::PasswordChanged(password) => {
+                self.password = Zeroizing::new(password);
+            }
+            NewPasswordInput::ConfirmChanged(confirm) => {
+                self.confirm = Zeroizing::new(confirm);
+            }
+            NewPasswordInput::Accept => {
+                if self.can_accept() {
+                    let password = self.password.clone();
+                    self.clear(widgets);
+                    self.visible = false;
+                    let _ = sender.output(NewPasswordOutput::Chosen(password));
+                }
+            }
+            NewPasswordInput::Cancel => {
+                self.clear(widgets);
+                self.visible = false;
+                let _ = sender.output(NewPasswordOutput::Cancelled);
+            }
+        }
+        self.update_view(widgets, sender);
+    }
+}
@@ -1,15 +1,17 @@
 //! Password confirmation dialog component.
 //!
-//! Modal dialog to confirm actions with master password.
+//! Modal dialog to confirm actions with master password
+//! (or with a plain yes/no when the password isn't required).
 
 use gtk4::prelude::*;
 use relm4::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
 
 /// Messages for the password confirmation dialog.
 #[derive(Debug)]
 pub enum PasswordConfirmationInput {
     /// Show the dialog with a message.
-    Show { message: String, action_id: String },
+    Show { message: String, action_id: String, require_password: bool },
     /// Password text changed.
     PasswordChanged(String),
     /// Confirm action.
@@ -23,20 +25,22 @@ pub enum PasswordConfirmationInput {
 /// Output messages.
 #[derive(Debug, Clone)]
 pub enum PasswordConfirmationOutput {
-    /// User confirmed with password.
-    Confirmed { password: String, action_id: String },
+    /// User confirmed with password (wiped from memory when dropped).
+    Confirmed { password: Zeroizing<String>, action_id: String },
     /// Dialog was cancelled.
     Cancelled,
 }
 
 /// Component model.
 pub struct PasswordConfirmation {
-    password: String,
+    password: Zeroizing<String>,
     message: String,
     action_id: String,
     error: Option<String>,
     visible: bool,
     processing: bool,
+    /// Whether the master password must be entered to confirm.
+    require_password: bool,
 }
 
 #[relm4::component(pub)]
@@ -92,6 +96,8 @@ impl Component for PasswordConfirmation {
                     gtk4::Box {
                         set_orientation: gtk4::Orientation::Vertical,
                         set_spacing: 4,
+                        #[watch]
+                        set_visible: model.require_password,
 
                         gtk4::Label {
                             set_text: "Master Password",
@@ -140,7 +146,7 @@ impl Component for PasswordConfirmation {
                             set_label: "Confirm",
                             add_css_class: "destructive-action", // Usually these represent delete
                             #[watch]
-                            set_sensitive: !model.processing && !model.password.is_empty(),
+                            set_sensitive: !model.processing && (!model.require_password || !model.password.is_empty()),
                             connect_clicked => PasswordConfirmationInput::Confirm,
                         },
                     },
@@ -155,12 +161,13 @@ impl Component for PasswordConfirmation {
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
         let model = PasswordConfirmation {
-            password: String::new(),
+            password: Zeroizing::new(String::new()),
             message: String::new(),
             action_id: String::new(),
             error: None,
             visible: false,
             processing: false,
+            require_password: true,
         };
 
         let widgets = view_output!();
@@ -176,10 +183,11 @@ impl Component for PasswordConfirmation {
         _root: &Self::Root,
     ) {
         match message {
-            PasswordConfirmationInput::Show { message, action_id } => {
+            PasswordConfirmationInput::Show { message, action_id, require_password } => {
                 self.message = message;
                 self.action_id = action_id;
-                self.password.clear();
+                self.require_password = require_password;
+                self.password.zeroize();
                 self.error = None;
                 self.visible = true;
                 self.processing = false;
@@ -188,11 +196,11 @@ impl Component for PasswordConfirmation {
                 widgets._password_entry.set_text("");
             }
             PasswordConfirmationInput::PasswordChanged(pwd) => {
-                self.password = pwd;
+                self.password = Zeroizing::new(pwd);
                 self.error = None;
             }
             PasswordConfirmationInput::Confirm => {
-                if !self.password.is_empty() {
+                if !self.require_password || !self.password.is_empty() {
                     self.processing = true;
                     // Send output
                     let _ = sender.output(PasswordConfirmationOutput::Confirmed {
@@ -203,7 +211,7 @@ impl Component for PasswordConfirmation {
             }
             PasswordConfirmationInput::Cancel => {
                 self.visible = false;
-                self.password.clear();
+                self.password.zeroize();
                 // Manually clear widget
                 widgets._password_entry.set_text("");
                 let _ = sender.output(PasswordConfirmationOutput::Cancelled);
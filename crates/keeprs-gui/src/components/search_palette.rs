@@ -116,7 +116,7 @@ impl Component for SearchPalette {
                     // Search entry
                     #[name = "_search_entry"]
                     gtk4::SearchEntry {
-                        set_placeholder_text: Some("Search entries and folders..."),
+                        set_placeholder_text: Some("Search entries and folders (tag:name to filter)..."),
                         set_margin_all: 12,
 
                         connect_search_changed[sender] => move |entry| {
@@ -297,13 +297,18 @@ impl SearchPalette {
             return;
         };
 
+        // A leading `tag:name` token narrows the results to tagged entries
+        let (tag, text) = split_tag_filter(&self.query);
+        let tag = tag.map(str::to_lowercase);
+
         // Collect all searchable items
         let mut items = Vec::new();
-        self.collect_items(root, "", &mut items);
+        self.collect_items(root, "", tag.as_deref(), &mut items);
 
         // If query is empty, show recent/all items (limited)
-        if self.query.is_empty() {
-            self.results = items.into_iter().take(10).collect();
+        if text.is_empty() {
+            let limit = if tag.is_some() { 15 } else { 10 };
+            self.results = items.into_iter().take(limit).collect();
             return;
         }
 
@@ -318,7 +323,7 @@ impl SearchPalette {
                     }
                 };
 
-                self.matcher.fuzzy_match(&search_text, &self.query).map(|score| {
+                self.matcher.fuzzy_match(&search_text, text).map(|score| {
                     match item {
                         SearchResult::Group { uuid, name, path, .. } => {
                             SearchResult::Group { uuid, name, path, score }
@@ -339,7 +344,16 @@ impl SearchPalette {
     }
 
     /// Collect all groups and entries recursively.
-    fn collect_items(&self, group: &Group, path: &str, items: &mut Vec<SearchResult>) {
+    ///
+    /// With a (lowercase) `tag` filter, groups are skipped and only entries
+    /// carrying that tag are collected.
+    fn collect_items(
+        &self,
+        group: &Group,
+        path: &str,
+        tag: Option<&str>,
+        items: &mut Vec<SearchResult>,
+    ) {
         let current_path = if path.is_empty() {
             group.name.clone()
         } else {
@@ -352,18 +366,25 @@ impl SearchPalette {
         }
 
         // Add this group
-        items.push(SearchResult::Group {
-            uuid: group.uuid.clone(),
-            name: group.name.clone(),
-            path: path.to_string(),
-            score: 0,
-        });
+        if tag.is_none() {
+            items.push(SearchResult::Group {
+                uuid: group.uuid.clone(),
+                name: group.name.clone(),
+                path: path.to_string(),
+                score: 0,
+            });
+        }
 
         // Add entries
         for entry in &group.entries {
             if self.hidden_groups.contains(&entry.title) {
                 continue;
             }
+            if let Some(tag) = tag {
+                if !entry.tags.iter().any(|t| t.to_lowercase() == tag) {
+                    continue;
+                }
+            }
             items.push(SearchResult::Entry {
                 uuid: entry.uuid.clone(),
                 title: entry.title.clone(),
@@ -376,7 +397,7 @@ impl SearchPalette {
 
         // Recurse into children
         for child in &group.children {
-            self.collect_items(child, &current_path, items);
+            self.collect_items(child, &current_path, tag, items);
         }
     }
 
@@ -501,3 +522,23 @@ impl SearchPalette {
         None
     }
 }
+
+/// Split a leading `tag:name` token off a search query.
+///
+/// Returns the tag (if any) and the remaining text to fuzzy-match.
+fn split_tag_filter(query: &str) -> (Option<&str>, &str) {
+    let trimmed = query.trim_start();
+    let has_prefix = trimmed
+        .get(..4)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("tag:"));
+    if !has_prefix {
+        return (None, query.trim());
+    }
+
+    let rest = &trimmed[4..];
+    let (tag, text) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    if tag.is_empty() {
+        return (None, text.trim());
+    }
+    (Some(tag), text.trim())
+}
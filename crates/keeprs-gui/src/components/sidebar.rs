@@ -1,12 +1,16 @@
 //! Sidebar component with folder tree.
 
-use crate::components::common::create_composite_button;
+use crate::components::common::{self, create_composite_button};
 use gtk4::prelude::*;
 use keeprs_core::{Entry, Group};
 use relm4::prelude::*;
 
-use gtk4::cairo::Context;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
+
+/// Row name of the Favorites shortcut at the top of the tree.
+const FAVORITES_ROW: &str = "virtual-favorites";
+/// Row name of the All Entries shortcut at the top of the tree.
+const ALL_ENTRIES_ROW: &str = "virtual-all";
 
 /// Messages for the sidebar.
 #[derive(Debug)]
@@ -17,12 +21,18 @@ pub enum SidebarInput {
     SelectGroup(String),
     /// An entry was selected.
     SelectEntry(String),
+    /// The Favorites shortcut was selected.
+    SelectFavorites,
+    /// The All Entries shortcut was selected.
+    SelectAllEntries,
     /// Update visual selection.
     UpdateSelection(String),
     /// Toggle expansion of a group.
     ToggleExpand(String),
     /// Request to add a new group.
     AddGroup,
+    /// Request to edit a group's name and password policy.
+    EditGroup(String),
     /// Request to delete a group.
     DeleteGroup(String),
     DeleteEntry(String),
@@ -32,6 +42,40 @@ pub enum SidebarInput {
     PermanentDeleteGroup(String),
     /// Request to permanently delete an entry.
     PermanentDeleteEntry(String),
+    /// Request to move a group out of the recycle bin.
+    RestoreGroup(String),
+    /// Request to move an entry out of the recycle bin.
+    RestoreEntry(String),
+    /// Request a copy of an entry.
+    DuplicateEntry(String),
+    /// Toggle a tag in the tag filter.
+    ToggleTag(String),
+    /// Switch between matching all selected tags or any of them.
+    SetTagMatchAll(bool),
+    /// Deselect all tags (without notifying the parent).
+    ClearTagSelection,
+    /// Request a database integrity check.
+    CheckIntegrity,
+    /// Request a review of duplicate entries.
+    FindDuplicates,
+    /// Request the list of passwords used by several entries.
+    FindReusedPasswords,
+    /// Request the list of weak and old passwords.
+    PasswordAudit,
+    /// Request a re-keyed copy of the database.
+    SaveCopy,
+    /// Request a plain-text CSV export of all entries.
+    ExportCsv,
+    /// Request a plain-text JSON export of the whole group tree.
+    ExportJson,
+    /// Request a new master password.
+    ChangeMasterPassword,
+    /// Request an import of exported entries.
+    ImportEntries,
+    /// Show the database file in the file manager.
+    RevealDatabase,
+    /// Show the last saved attachment in the file manager.
+    RevealAttachment,
 }
 
 /// Output messages from the sidebar.
@@ -41,8 +85,14 @@ pub enum SidebarOutput {
     GroupSelected(String),
     /// User selected an entry.
     EntrySelected(String),
+    /// User selected the Favorites shortcut.
+    FavoritesSelected,
+    /// User selected the All Entries shortcut.
+    AllEntriesSelected,
     /// User requested to add a group.
     RequestAddGroup,
+    /// User requested to edit a group.
+    RequestEditGroup(String),
     /// User requested to delete a group.
     RequestDeleteGroup(String),
     /// User requested to delete an entry.
@@ -52,6 +102,36 @@ pub enum SidebarOutput {
     RequestPermanentDeleteGroup(String),
     /// User requested to permanently delete an entry.
     RequestPermanentDeleteEntry(String),
+    /// User requested to restore a group from the recycle bin.
+    RequestRestoreGroup(String),
+    /// User requested to restore an entry from the recycle bin.
+    RequestRestoreEntry(String),
+    /// User requested a copy of an entry.
+    RequestDuplicateEntry(String),
+    /// Tag filter changed (empty = no filter).
+    TagsSelected { tags: Vec<String>, match_all: bool },
+    /// User requested a database integrity check.
+    RequestCheckIntegrity,
+    /// User wants to review entries that look like duplicates.
+    RequestFindDuplicates,
+    /// User wants to see which entries share a password.
+    RequestFindReusedPasswords,
+    /// User wants to see entries with weak or old passwords.
+    RequestPasswordAudit,
+    /// User requested a copy of the database with its own password.
+    RequestSaveCopy,
+    /// User wants to export all entries to a CSV file.
+    RequestExportCsv,
+    /// User wants to export the whole group tree to a JSON file.
+    RequestExportJson,
+    /// User wants to change the master password.
+    RequestChangeMasterPassword,
+    /// User wants to import entries from a JSON export.
+    RequestImport,
+    /// User wants to see the database file in the file manager.
+    RequestRevealDatabase,
+    /// User wants to see the last saved attachment in the file manager.
+    RequestRevealAttachment,
 }
 
 /// Sidebar model.
@@ -60,6 +140,10 @@ pub struct Sidebar {
     selected_uuid: Option<String>,
     expanded_uuids: HashSet<String>,
     hidden_groups: HashSet<String>,
+    hide_empty_groups: bool,
+    read_only: bool,
+    selected_tags: BTreeSet<String>,
+    match_all_tags: bool,
     context_menu: gtk4::PopoverMenu,
 }
 
@@ -68,6 +152,9 @@ pub struct SidebarInit {
     pub initial_width: i32,
     pub min_width: i32,
     pub hidden_groups: Vec<String>,
+    pub hide_empty_groups: bool,
+    /// Hide adding, editing and deleting groups and entries.
+    pub read_only: bool,
 }
 
 #[relm4::component(pub)]
@@ -96,8 +183,95 @@ impl Component for Sidebar {
                                 3,
                                 3
                             ) {
+                                set_visible: !model.read_only,
                                 connect_clicked => SidebarInput::AddGroup,
-                            }
+                            },
+
+                gtk4::Box {
+                    set_hexpand: true,
+                },
+
+                gtk4::MenuButton {
+                    set_icon_name: "view-more-symbolic",
+                    add_css_class: "flat",
+                    set_tooltip_text: Some("Database actions"),
+
+                    #[wrap(Some)]
+                    set_popover: database_menu = &gtk4::Popover {
+                        gtk4::Box {
+                            set_orientation: gtk4::Orientation::Vertical,
+                            set_spacing: 2,
+
+                            gtk4::Button {
+                                set_label: "Check Database…",
+                                add_css_class: "flat",
+                                connect_clicked => SidebarInput::CheckIntegrity,
+                            },
+
+                            gtk4::Button {
+                                set_label: "Find Duplicates…",
+                                add_css_class: "flat",
+                                connect_clicked => SidebarInput::FindDuplicates,
+                            },
+
+                            gtk4::Button {
+                                set_label: "Reused Passwords…",
+                                add_css_class: "flat",
+                                connect_clicked => SidebarInput::FindReusedPasswords,
+                            },
+
+                            gtk4::Button {
+                                set_label: "Password Audit…",
+                                add_css_class: "flat",
+                                connect_clicked => SidebarInput::PasswordAudit,
+                            },
+
+                            gtk4::Button {
+                                set_label: "Save a Copy…",
+                                add_css_class: "flat",
+                                connect_clicked => SidebarInput::SaveCopy,
+                            },
+
+                            gtk4::Button {
+                                set_label: "Export as CSV…",
+                                add_css_class: "flat",
+                                connect_clicked => SidebarInput::ExportCsv,
+                            },
+
+                            gtk4::Button {
+                                set_label: "Export as JSON…",
+                                add_css_class: "flat",
+                                connect_clicked => SidebarInput::ExportJson,
+                            },
+
+                            gtk4::Button {
+                                set_label: "Change Master Password…",
+                                add_css_class: "flat",
+                                set_visible: !model.read_only,
+                                connect_clicked => SidebarInput::ChangeMasterPassword,
+                            },
+
+                            gtk4::Button {
+                                set_label: "Import Entries…",
+                                add_css_class: "flat",
+                                set_visible: !model.read_only,
+                                connect_clicked => SidebarInput::ImportEntries,
+                            },
+
+                            gtk4::Button {
+                                set_label: "Show Database File",
+                                add_css_class: "flat",
+                                connect_clicked => SidebarInput::RevealDatabase,
+                            },
+
+                            gtk4::Button {
+                                set_label: "Show Saved Attachment",
+                                add_css_class: "flat",
+                                connect_clicked => SidebarInput::RevealAttachment,
+                            },
+                        }
+                    },
+                },
             },
 
             gtk4::Separator {
@@ -123,11 +297,65 @@ impl Component for Sidebar {
                         sender.input(SidebarInput::SelectGroup(uuid.to_string()));
                     } else if let Some(uuid) = name.as_str().strip_prefix("entry-") {
                         sender.input(SidebarInput::SelectEntry(uuid.to_string()));
+                    } else if name == FAVORITES_ROW {
+                        sender.input(SidebarInput::SelectFavorites);
+                    } else if name == ALL_ENTRIES_ROW {
+                        sender.input(SidebarInput::SelectAllEntries);
                     }
                 },
             },
 
-            }
+            },
+
+            // Tags section (hidden when the database has no tags)
+            #[name = "_tags_section"]
+            gtk4::Box {
+                set_orientation: gtk4::Orientation::Vertical,
+                set_visible: false,
+
+                gtk4::Separator {
+                    set_orientation: gtk4::Orientation::Horizontal,
+                },
+
+                gtk4::Box {
+                    set_orientation: gtk4::Orientation::Horizontal,
+                    set_spacing: 8,
+                    set_margin_start: 8,
+                    set_margin_end: 8,
+                    set_margin_top: 8,
+
+                    gtk4::Label {
+                        set_label: "Tags",
+                        add_css_class: "heading",
+                        set_hexpand: true,
+                        set_halign: gtk4::Align::Start,
+                    },
+
+                    gtk4::ToggleButton {
+                        set_label: "Match all",
+                        add_css_class: "flat",
+                        set_tooltip_text: Some("Show entries carrying every selected tag instead of any of them"),
+                        connect_toggled[sender] => move |btn| {
+                            sender.input(SidebarInput::SetTagMatchAll(btn.is_active()));
+                        },
+                    },
+                },
+
+                gtk4::ScrolledWindow {
+                    set_hscrollbar_policy: gtk4::PolicyType::Never,
+                    set_propagate_natural_height: true,
+                    set_max_content_height: 150,
+
+                    #[name = "_tags_flow"]
+                    gtk4::FlowBox {
+                        set_selection_mode: gtk4::SelectionMode::None,
+                        set_margin_all: 8,
+                        set_column_spacing: 4,
+                        set_row_spacing: 4,
+                        set_max_children_per_line: 20,
+                    },
+                },
+            },
         }
     }
 
@@ -151,6 +379,10 @@ impl Component for Sidebar {
             selected_uuid: None,
             expanded_uuids: HashSet::new(),
             hidden_groups: init.hidden_groups.into_iter().collect(),
+            hide_empty_groups: init.hide_empty_groups,
+            read_only: init.read_only,
+            selected_tags: BTreeSet::new(),
+            match_all_tags: false,
             context_menu,
         };
 
@@ -176,8 +408,22 @@ impl Component for Sidebar {
                 // Ensure root is expanded if it wasn't?
                 if let Some(root) = &self.root_group {
                     self.expanded_uuids.insert(root.uuid.clone());
+
+                    // Drop selected tags that no longer exist
+                    let all_tags = root.all_tags();
+                    let before = self.selected_tags.len();
+                    self.selected_tags.retain(|t| all_tags.contains(t));
+
+                    // Keep the entry view's tag filter in sync with what is left
+                    if self.selected_tags.len() != before {
+                        let _ = sender.output(SidebarOutput::TagsSelected {
+                            tags: self.selected_tags.iter().cloned().collect(),
+                            match_all: self.match_all_tags,
+                        });
+                    }
                 }
 
+                self.rebuild_tags(widgets, &sender);
                 self.rebuild_list(widgets, sender);
             }
             SidebarInput::SelectGroup(uuid) => {
@@ -188,6 +434,14 @@ impl Component for Sidebar {
                 self.selected_uuid = Some(uuid.clone());
                 let _ = sender.output(SidebarOutput::EntrySelected(uuid));
             }
+            SidebarInput::SelectFavorites => {
+                self.selected_uuid = Some(FAVORITES_ROW.to_string());
+                let _ = sender.output(SidebarOutput::FavoritesSelected);
+            }
+            SidebarInput::SelectAllEntries => {
+                self.selected_uuid = Some(ALL_ENTRIES_ROW.to_string());
+                let _ = sender.output(SidebarOutput::AllEntriesSelected);
+            }
             SidebarInput::UpdateSelection(uuid) => {
                 self.selected_uuid = Some(uuid.clone());
 
@@ -215,6 +469,9 @@ impl Component for Sidebar {
             SidebarInput::AddGroup => {
                 let _ = sender.output(SidebarOutput::RequestAddGroup);
             }
+            SidebarInput::EditGroup(uuid) => {
+                let _ = sender.output(SidebarOutput::RequestEditGroup(uuid));
+            }
             SidebarInput::DeleteGroup(uuid) => {
                 let _ = sender.output(SidebarOutput::RequestDeleteGroup(uuid));
             }
@@ -230,6 +487,83 @@ impl Component for Sidebar {
             SidebarInput::PermanentDeleteEntry(uuid) => {
                 let _ = sender.output(SidebarOutput::RequestPermanentDeleteEntry(uuid));
             }
+            SidebarInput::RestoreGroup(uuid) => {
+                let _ = sender.output(SidebarOutput::RequestRestoreGroup(uuid));
+            }
+            SidebarInput::RestoreEntry(uuid) => {
+                let _ = sender.output(SidebarOutput::RequestRestoreEntry(uuid));
+            }
+            SidebarInput::DuplicateEntry(uuid) => {
+                let _ = sender.output(SidebarOutput::RequestDuplicateEntry(uuid));
+            }
+            SidebarInput::ToggleTag(tag) => {
+                if !self.selected_tags.remove(&tag) {
+                    self.selected_tags.insert(tag);
+                }
+                let _ = sender.output(SidebarOutput::TagsSelected {
+                    tags: self.selected_tags.iter().cloned().collect(),
+                    match_all: self.match_all_tags,
+                });
+            }
+            SidebarInput::SetTagMatchAll(match_all) => {
+                self.match_all_tags = match_all;
+                if !self.selected_tags.is_empty() {
+                    let _ = sender.output(SidebarOutput::TagsSelected {
+                        tags: self.selected_tags.iter().cloned().collect(),
+                        match_all,
+                    });
+                }
+            }
+            SidebarInput::CheckIntegrity => {
+                widgets.database_menu.popdown();
+                let _ = sender.output(SidebarOutput::RequestCheckIntegrity);
+            }
+            SidebarInput::FindDuplicates => {
+                widgets.database_menu.popdown();
+                let _ = sender.output(SidebarOutput::RequestFindDuplicates);
+            }
+            SidebarInput::FindReusedPasswords => {
+                widgets.database_menu.popdown();
+                let _ = sender.output(SidebarOutput::RequestFindReusedPasswords);
+            }
+            SidebarInput::PasswordAudit => {
+                widgets.database_menu.popdown();
+                let _ = sender.output(SidebarOutput::RequestPasswordAudit);
+            }
+            SidebarInput::SaveCopy => {
+                widgets.database_menu.popdown();
+                let _ = sender.output(SidebarOutput::RequestSaveCopy);
+            }
+            SidebarInput::ExportCsv => {
+                widgets.database_menu.popdown();
+                let _ = sender.output(SidebarOutput::RequestExportCsv);
+            }
+            SidebarInput::ExportJson => {
+                widgets.database_menu.popdown();
+                let _ = sender.output(SidebarOutput::RequestExportJson);
+            }
+            SidebarInput::ChangeMasterPassword => {
+                widgets.database_menu.popdown();
+                let _ = sender.output(SidebarOutput::RequestChangeMasterPassword);
+            }
+            SidebarInput::ImportEntries => {
+                widgets.database_menu.popdown();
+                let _ = sender.output(SidebarOutput::RequestImport);
+            }
+            SidebarInput::RevealDatabase => {
+                widgets.database_menu.popdown();
+                let _ = sender.output(SidebarOutput::RequestRevealDatabase);
+            }
+            SidebarInput::RevealAttachment => {
+                widgets.database_menu.popdown();
+                let _ = sender.output(SidebarOutput::RequestRevealAttachment);
+            }
+            SidebarInput::ClearTagSelection => {
+                if !self.selected_tags.is_empty() {
+                    self.selected_tags.clear();
+                    self.rebuild_tags(widgets, &sender);
+                }
+            }
         }
     }
 }
@@ -255,16 +589,27 @@ impl Sidebar {
                 root.entries.len(),
                 count
             );
+            let all_entries = root.all_entries().len();
+            if all_entries > 0 {
+                Self::add_shortcut_node(&widgets._list_box, ALL_ENTRIES_ROW, "view-list-symbolic", "All Entries", all_entries);
+            }
+            let favorites = root.favorite_entries().len();
+            if favorites > 0 {
+                Self::add_shortcut_node(&widgets._list_box, FAVORITES_ROW, "starred-symbolic", "Favorites", favorites);
+            }
+
             let mut levels = Vec::new();
             // Root itself is usually hidden in 2-pane abstract, but here we render children of root.
             // Wait, standard sidebar hides the root folder if it's just a container.
             // Previous implementation rendered children of root.
             // We need to iterate over BOTH children and entries of root.
 
-            let total_count = root.children.len() + root.entries.len();
+            let children: Vec<&Group> = root.children.iter().filter(|g| self.is_group_visible(g)).collect();
+            let entries: Vec<&Entry> = root.entries.iter().filter(|e| self.is_entry_visible(e)).collect();
+            let total_count = children.len() + entries.len();
             let mut current_idx = 0;
 
-            for child in &root.children {
+            for child in children {
                 let is_last = current_idx == total_count - 1;
                 // Root children not under recycle bin unless root IS recycle bin? (Unlikely for root)
                 let is_under_bin = root.is_recycle_bin;
@@ -279,7 +624,7 @@ impl Sidebar {
                 );
                 current_idx += 1;
             }
-            for entry in &root.entries {
+            for entry in entries {
                 let is_last = current_idx == total_count - 1;
                 let is_under_bin = root.is_recycle_bin;
                 self.add_entry_node(
@@ -300,6 +645,50 @@ impl Sidebar {
         }
     }
 
+    fn rebuild_tags(
+        &self,
+        widgets: &<Sidebar as Component>::Widgets,
+        sender: &ComponentSender<Sidebar>,
+    ) {
+        while let Some(child) = widgets._tags_flow.first_child() {
+            widgets._tags_flow.remove(&child);
+        }
+
+        let tags = self
+            .root_group
+            .as_ref()
+            .map(|root| root.all_tags())
+            .unwrap_or_default();
+        widgets._tags_section.set_visible(!tags.is_empty());
+
+        for tag in tags {
+            let chip = gtk4::ToggleButton::with_label(&tag);
+            chip.add_css_class("tag-chip");
+            // Set state before connecting so rebuilding doesn't emit toggles
+            chip.set_active(self.selected_tags.contains(&tag));
+
+            let sender_clone = sender.clone();
+            chip.connect_toggled(move |_| {
+                sender_clone.input(SidebarInput::ToggleTag(tag.clone()));
+            });
+            widgets._tags_flow.insert(&chip, -1);
+        }
+    }
+
+    /// Whether a group should be shown in the tree.
+    fn is_group_visible(&self, group: &Group) -> bool {
+        if self.hidden_groups.contains(&group.name) {
+            return false;
+        }
+        // The recycle bin stays visible even when empty
+        !(self.hide_empty_groups && !group.is_recycle_bin && group.is_empty())
+    }
+
+    /// Whether an entry should be shown in the tree.
+    fn is_entry_visible(&self, entry: &Entry) -> bool {
+        !self.hidden_groups.contains(&entry.title)
+    }
+
     fn select_row_by_uuid(&self, list_box: &gtk4::ListBox, uuid: &str) {
         let group_name = format!("group-{}", uuid);
         let entry_name = format!("entry-{}", uuid);
@@ -308,7 +697,7 @@ impl Sidebar {
         while let Some(widget) = child {
             if let Some(row) = widget.downcast_ref::<gtk4::ListBoxRow>() {
                 let name = row.widget_name();
-                if name == group_name || name == entry_name {
+                if name == group_name || name == entry_name || name == uuid {
                     list_box.select_row(Some(row));
                     row.grab_focus();
                     return;
@@ -351,7 +740,7 @@ impl Sidebar {
         is_under_recycle_bin: bool,
         context_menu: &gtk4::PopoverMenu,
     ) {
-        if self.hidden_groups.contains(&entry.title) {
+        if !self.is_entry_visible(entry) {
             return;
         }
 
@@ -362,47 +751,7 @@ impl Sidebar {
         let hbox = gtk4::Box::new(gtk4::Orientation::Horizontal, 0);
 
         // Lines
-        let depth = levels.len();
-        let indent_width = (depth + 1) * 24;
-
-        let drawing_area = gtk4::DrawingArea::new();
-        drawing_area.set_content_width(indent_width as i32);
-        drawing_area.set_content_height(32);
-        drawing_area.set_vexpand(true);
-
-        let levels_clone = levels.clone();
-        let is_last_clone = is_last;
-
-        drawing_area.set_draw_func(move |_area, cr: &Context, _width, height| {
-            cr.set_source_rgba(0.6, 0.6, 0.6, 0.5);
-            cr.set_line_width(1.0);
-            let indent = 24.0;
-            let half_indent = 12.0;
-
-            for (i, &parent_is_last) in levels_clone.iter().enumerate() {
-                if !parent_is_last {
-                    let x = i as f64 * indent + half_indent;
-                    cr.move_to(x, -2.0);
-                    cr.line_to(x, height as f64 + 2.0);
-                    cr.stroke().expect("Invalid cairo");
-                }
-            }
-
-            let current_x = depth as f64 * indent + half_indent;
-            cr.move_to(current_x, -2.0);
-            if is_last_clone {
-                cr.line_to(current_x, height as f64 / 2.0);
-            } else {
-                cr.line_to(current_x, height as f64 + 2.0);
-            }
-            cr.stroke().expect("Invalid cairo");
-
-            cr.move_to(current_x, height as f64 / 2.0);
-            cr.line_to(current_x + half_indent + 4.0, height as f64 / 2.0);
-            cr.stroke().expect("Invalid cairo");
-        });
-
-        hbox.append(&drawing_area);
+        hbox.append(&common::create_tree_lines(levels, is_last));
 
         // Icon
         let icon = gtk4::Image::from_icon_name("dialog-password-symbolic"); // Key icon
@@ -440,8 +789,40 @@ impl Sidebar {
                 );
             }
         });
-        row.add_controller(gesture);
+        // Every context menu action changes the database
+        if !self.read_only {
+            row.add_controller(gesture);
+        }
+
+        list_box.append(&row);
+    }
+
+    /// Add a flat shortcut row (e.g. Favorites) above the tree.
+    fn add_shortcut_node(list_box: &gtk4::ListBox, name: &str, icon_name: &str, title: &str, count: usize) {
+        let row = gtk4::ListBoxRow::new();
+        row.set_widget_name(name);
+        row.add_css_class("sidebar-row");
 
+        let hbox = gtk4::Box::new(gtk4::Orientation::Horizontal, 0);
+        hbox.set_margin_top(6);
+        hbox.set_margin_bottom(6);
+
+        let icon = gtk4::Image::from_icon_name(icon_name);
+        icon.set_margin_start(12);
+        icon.set_margin_end(8);
+        hbox.append(&icon);
+
+        let label = gtk4::Label::new(Some(title));
+        label.set_hexpand(true);
+        label.set_halign(gtk4::Align::Start);
+        hbox.append(&label);
+
+        let count_label = gtk4::Label::new(Some(&count.to_string()));
+        count_label.add_css_class("dim-label");
+        count_label.set_margin_end(8);
+        hbox.append(&count_label);
+
+        row.set_child(Some(&hbox));
         list_box.append(&row);
     }
 
@@ -454,43 +835,7 @@ impl Sidebar {
 
         let hbox = gtk4::Box::new(gtk4::Orientation::Horizontal, 0);
 
-        let depth = levels.len();
-        let indent_width = (depth + 1) * 24;
-
-        let drawing_area = gtk4::DrawingArea::new();
-        drawing_area.set_content_width(indent_width as i32);
-        drawing_area.set_content_height(32);
-        drawing_area.set_vexpand(true);
-
-        let levels_clone = levels.to_vec();
-
-        drawing_area.set_draw_func(move |_area, cr: &Context, _width, height| {
-            cr.set_source_rgba(0.6, 0.6, 0.6, 0.5);
-            cr.set_line_width(1.0);
-            let indent = 24.0;
-            let half_indent = 12.0;
-
-            for (i, &parent_is_last) in levels_clone.iter().enumerate() {
-                if !parent_is_last {
-                    let x = i as f64 * indent + half_indent;
-                    cr.move_to(x, -2.0);
-                    cr.line_to(x, height as f64 + 2.0);
-                    cr.stroke().expect("Invalid cairo");
-                }
-            }
-
-            // Last branch
-            let current_x = depth as f64 * indent + half_indent;
-            cr.move_to(current_x, -2.0);
-            cr.line_to(current_x, height as f64 / 2.0);
-            cr.stroke().expect("Invalid cairo");
-
-            cr.move_to(current_x, height as f64 / 2.0);
-            cr.line_to(current_x + half_indent + 4.0, height as f64 / 2.0);
-            cr.stroke().expect("Invalid cairo");
-        });
-
-        hbox.append(&drawing_area);
+        hbox.append(&common::create_tree_lines(levels, true));
 
         let label = gtk4::Label::new(Some("- Empty -"));
         label.add_css_class("dim-label");
@@ -513,7 +858,7 @@ impl Sidebar {
         is_under_recycle_bin: bool,
         context_menu: &gtk4::PopoverMenu,
     ) {
-        if self.hidden_groups.contains(&group.name) {
+        if !self.is_group_visible(group) {
             return;
         }
 
@@ -525,46 +870,7 @@ impl Sidebar {
         let hbox = gtk4::Box::new(gtk4::Orientation::Horizontal, 0);
 
         let depth = levels.len();
-        let indent_width = (depth + 1) * 24;
-
-        let drawing_area = gtk4::DrawingArea::new();
-        drawing_area.set_content_width(indent_width as i32);
-        drawing_area.set_content_height(32);
-        drawing_area.set_vexpand(true);
-
-        let levels_clone = levels.clone();
-        let is_last_clone = is_last;
-
-        drawing_area.set_draw_func(move |_area, cr: &Context, _width, height| {
-            cr.set_source_rgba(0.6, 0.6, 0.6, 0.5);
-            cr.set_line_width(1.0);
-            let indent = 24.0;
-            let half_indent = 12.0;
-
-            for (i, &parent_is_last) in levels_clone.iter().enumerate() {
-                if !parent_is_last {
-                    let x = i as f64 * indent + half_indent;
-                    cr.move_to(x, -2.0);
-                    cr.line_to(x, height as f64 + 2.0);
-                    cr.stroke().expect("Invalid cairo");
-                }
-            }
-
-            let current_x = depth as f64 * indent + half_indent;
-            cr.move_to(current_x, -2.0);
-            if is_last_clone {
-                cr.line_to(current_x, height as f64 / 2.0);
-            } else {
-                cr.line_to(current_x, height as f64 + 2.0);
-            }
-            cr.stroke().expect("Invalid cairo");
-
-            cr.move_to(current_x, height as f64 / 2.0);
-            cr.line_to(current_x + half_indent + 4.0, height as f64 / 2.0);
-            cr.stroke().expect("Invalid cairo");
-        });
-
-        hbox.append(&drawing_area);
+        hbox.append(&common::create_tree_lines(levels, is_last));
 
         let icon_name = if self.expanded_uuids.contains(&group.uuid)
             && (!group.children.is_empty() || !group.entries.is_empty())
@@ -655,13 +961,18 @@ impl Sidebar {
                 );
             }
         });
-        row.add_controller(gesture);
+        // Every context menu action changes the database
+        if !self.read_only {
+            row.add_controller(gesture);
+        }
 
         list_box.append(&row);
 
         if self.expanded_uuids.contains(&group.uuid) {
             levels.push(is_last);
-            let total_child_count = group.children.len() + group.entries.len();
+            let children: Vec<&Group> = group.children.iter().filter(|g| self.is_group_visible(g)).collect();
+            let entries: Vec<&Entry> = group.entries.iter().filter(|e| self.is_entry_visible(e)).collect();
+            let total_child_count = children.len() + entries.len();
 
             if total_child_count == 0 {
                 // Show placeholder
@@ -671,7 +982,7 @@ impl Sidebar {
 
                 let next_under_bin = is_under_recycle_bin || group.is_recycle_bin;
 
-                for child in &group.children {
+                for child in children {
                     let child_is_last = current_child_idx == total_child_count - 1;
                     self.add_group_node(
                         list_box,
@@ -684,7 +995,7 @@ impl Sidebar {
                     );
                     current_child_idx += 1;
                 }
-                for entry in &group.entries {
+                for entry in entries {
                     let child_is_last = current_child_idx == total_child_count - 1;
                     self.add_entry_node(
                         list_box,
@@ -717,8 +1028,14 @@ impl Sidebar {
         if is_recycle_bin {
             menu_model.append(Some("Empty Recycle Bin"), Some("ctx.empty"));
         } else if is_under_recycle_bin {
+            menu_model.append(Some("Restore"), Some("ctx.restore"));
             menu_model.append(Some("Delete Permanently"), Some("ctx.delete_perm"));
         } else {
+            if is_group {
+                menu_model.append(Some("Edit Folder…"), Some("ctx.edit"));
+            } else {
+                menu_model.append(Some("Duplicate"), Some("ctx.duplicate"));
+            }
             menu_model.append(Some("Delete"), Some("ctx.delete"));
         }
 
@@ -758,6 +1075,24 @@ impl Sidebar {
         });
         action_group.add_action(&action);
 
+        if is_group {
+            let sender_clone = sender.clone();
+            let uuid_clone = uuid.to_string();
+            let action = gtk4::gio::SimpleAction::new("edit", None);
+            action.connect_activate(move |_, _| {
+                sender_clone.input(SidebarInput::EditGroup(uuid_clone.clone()));
+            });
+            action_group.add_action(&action);
+        } else {
+            let sender_clone = sender.clone();
+            let uuid_clone = uuid.to_string();
+            let action = gtk4::gio::SimpleAction::new("duplicate", None);
+            action.connect_activate(move |_, _| {
+                sender_clone.input(SidebarInput::DuplicateEntry(uuid_clone.clone()));
+            });
+            action_group.add_action(&action);
+        }
+
         if is_recycle_bin || is_under_recycle_bin {
             let sender_clone = sender.clone();
             let uuid_clone = uuid.to_string();
@@ -769,6 +1104,18 @@ impl Sidebar {
                 });
                 action_group.add_action(&action);
             } else {
+                let restore_sender = sender_clone.clone();
+                let restore_uuid = uuid_clone.clone();
+                let action = gtk4::gio::SimpleAction::new("restore", None);
+                action.connect_activate(move |_, _| {
+                    if is_group {
+                        restore_sender.input(SidebarInput::RestoreGroup(restore_uuid.clone()));
+                    } else {
+                        restore_sender.input(SidebarInput::RestoreEntry(restore_uuid.clone()));
+                    }
+                });
+                action_group.add_action(&action);
+
                 let action = gtk4::gio::SimpleAction::new("delete_perm", None);
                 action.connect_activate(move |_, _| {
                     if is_group {
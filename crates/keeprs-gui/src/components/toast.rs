@@ -0,0 +1,147 @@
+//! Toast notification component.
+//!
+//! Shows short-lived feedback ("Copied", "Saved", errors) as an overlay
+//! at the bottom of the main window.
+
+use gtk4::prelude::*;
+use relm4::prelude::*;
+
+/// How long a regular toast stays visible.
+const TOAST_TIMEOUT_MS: u64 = 2500;
+/// Errors stay a bit longer so they can actually be read.
+const ERROR_TOAST_TIMEOUT_MS: u64 = 5000;
+
+/// Kind of notification, used for styling and timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    /// Neutral information (e.g. "Copied").
+    Info,
+    /// An operation completed successfully.
+    Success,
+    /// An operation failed.
+    Error,
+}
+
+impl NotificationKind {
+    fn css_class(self) -> &'static str {
+        match self {
+            NotificationKind::Info => "toast-info",
+            NotificationKind::Success => "toast-success",
+            NotificationKind::Error => "toast-error",
+        }
+    }
+
+    fn timeout(self) -> std::time::Duration {
+        match self {
+            NotificationKind::Error => std::time::Duration::from_millis(ERROR_TOAST_TIMEOUT_MS),
+            _ => std::time::Duration::from_millis(TOAST_TIMEOUT_MS),
+        }
+    }
+}
+
+/// Messages for the toast component.
+#[derive(Debug)]
+pub enum ToastInput {
+    /// Show a message, replacing any toast currently visible.
+    Show { message: String, kind: NotificationKind },
+    /// Hide the toast (only if `generation` is still the current one).
+    Hide(u64),
+    /// Dismiss immediately.
+    Dismiss,
+}
+
+/// Toast model.
+pub struct Toast {
+    message: String,
+    visible: bool,
+    /// Incremented on every show so stale hide timers are ignored.
+    generation: u64,
+}
+
+#[relm4::component(pub)]
+impl Component for Toast {
+    type Init = ();
+    type Input = ToastInput;
+    type Output = ();
+    type CommandOutput = ();
+
+    view! {
+        gtk4::Revealer {
+            set_halign: gtk4::Align::Center,
+            set_valign: gtk4::Align::End,
+            set_margin_bottom: 24,
+            set_transition_type: gtk4::RevealerTransitionType::SlideUp,
+            #[watch]
+            set_reveal_child: model.visible,
+
+            #[name = "toast_box"]
+            gtk4::Box {
+                set_orientation: gtk4::Orientation::Horizontal,
+                set_spacing: 12,
+                add_css_class: "toast",
+
+                gtk4::Label {
+                    #[watch]
+                    set_label: &model.message,
+                    set_wrap: true,
+                    set_max_width_chars: 60,
+                },
+
+                gtk4::Button {
+                    set_icon_name: "window-close-symbolic",
+                    add_css_class: "flat",
+                    add_css_class: "circular",
+                    set_tooltip_text: Some("Dismiss"),
+                    connect_clicked => ToastInput::Dismiss,
+                },
+            }
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = Toast {
+            message: String::new(),
+            visible: false,
+            generation: 0,
+        };
+
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            ToastInput::Show { message, kind } => {
+                self.generation += 1;
+                self.message = message;
+                self.visible = true;
+                widgets.toast_box.set_css_classes(&["toast", kind.css_class()]);
+
+                let generation = self.generation;
+                let sender_clone = sender.clone();
+                gtk4::glib::timeout_add_local_once(kind.timeout(), move || {
+                    sender_clone.input(ToastInput::Hide(generation));
+                });
+            }
+            ToastInput::Hide(generation) => {
+                if generation == self.generation {
+                    self.visible = false;
+                }
+            }
+            ToastInput::Dismiss => {
+                self.visible = false;
+            }
+        }
+        self.update_view(widgets, sender);
+    }
+}
@@ -1,36 +1,69 @@
 //! Password unlock dialog component.
 
 use gtk4::prelude::*;
+use keeprs_core::KeepassDatabase;
 use relm4::prelude::*;
+use std::path::PathBuf;
+use zeroize::{Zeroize, Zeroizing};
 
 /// Messages for the unlock dialog.
 #[derive(Debug)]
 pub enum UnlockInput {
     /// Password text changed.
     PasswordChanged(String),
+    /// Confirmation text changed (when creating a database).
+    ConfirmChanged(String),
     /// Attempt to unlock.
     Unlock,
     /// Show error message.
     ShowError(String),
+    /// Clear the password field and any error (e.g. after locking).
+    Reset,
+    /// Use another database file (`true` to create it there).
+    SetDatabasePath(PathBuf, bool),
+    /// User wants to pick another database file.
+    ChooseFile,
+    /// User wants to create a new database at the configured path.
+    CreateHere,
+    /// User wants to pick a key file.
+    ChooseKeyFile,
+    /// Use this key file (or none) when unlocking.
+    SetKeyFile(Option<PathBuf>),
 }
 
 /// Output messages from the unlock dialog.
 #[derive(Debug)]
 pub enum UnlockOutput {
-    /// User submitted password.
-    Unlocked(String),
+    /// User submitted credentials; the password is wiped from memory when dropped
+    /// and may be empty when a key file is given.
+    Unlocked {
+        password: Zeroizing<String>,
+        key_file: Option<PathBuf>,
+    },
+    /// User chose a password for a new database at the current path.
+    Create(Zeroizing<String>),
+    /// User wants to pick another database file.
+    ChooseDatabase,
 }
 
 /// Unlock dialog model.
 pub struct UnlockDialog {
-    password: String,
+    database_path: PathBuf,
+    /// Why the database file can't be opened, checked before asking for a password.
+    file_problem: Option<String>,
+    /// Creating a new database instead of unlocking one.
+    creating: bool,
+    password: Zeroizing<String>,
+    confirm: Zeroizing<String>,
+    /// Key file to unlock with, kept across locks.
+    key_file: Option<PathBuf>,
     error: Option<String>,
     unlocking: bool,
 }
 
 #[relm4::component(pub)]
 impl Component for UnlockDialog {
-    type Init = ();
+    type Init = PathBuf;
     type Input = UnlockInput;
     type Output = UnlockOutput;
     type CommandOutput = ();
@@ -50,55 +83,172 @@ impl Component for UnlockDialog {
             },
 
             gtk4::Label {
-                set_text: "Enter your master password to unlock the database",
+                #[watch]
+                set_text: if model.file_problem.is_some() {
+                    "The database can't be opened"
+                } else if model.creating {
+                    "Choose a master password for the new database"
+                } else {
+                    "Enter your master password to unlock the database"
+                },
                 set_wrap: true,
                 add_css_class: "dim-label",
             },
 
-            gtk4::PasswordEntry {
-                set_placeholder_text: Some("Master Password"),
-                set_show_peek_icon: true,
-                set_hexpand: true,
+            gtk4::Label {
+                #[watch]
+                set_text: &model.database_path.display().to_string(),
+                set_wrap: true,
+                set_wrap_mode: gtk4::pango::WrapMode::Char,
+                set_selectable: true,
+                add_css_class: "caption",
+                add_css_class: "dim-label",
+            },
 
-                connect_changed[sender] => move |entry| {
-                    sender.input(UnlockInput::PasswordChanged(entry.text().to_string()));
+            // Missing or unreadable file
+            gtk4::Box {
+                set_orientation: gtk4::Orientation::Vertical,
+                set_spacing: 12,
+                #[watch]
+                set_visible: model.file_problem.is_some(),
+
+                gtk4::Label {
+                    #[watch]
+                    set_text: model.file_problem.as_deref().unwrap_or(""),
+                    set_wrap: true,
+                    add_css_class: "error",
+                },
+
+                gtk4::Button {
+                    set_label: "Choose Another File…",
+                    add_css_class: "pill",
+                    connect_clicked => UnlockInput::ChooseFile,
                 },
 
-                connect_activate[sender] => move |_| {
-                    sender.input(UnlockInput::Unlock);
+                gtk4::Button {
+                    set_label: "Create New Database Here",
+                    add_css_class: "pill",
+                    #[watch]
+                    set_visible: !model.database_path.exists(),
+                    connect_clicked => UnlockInput::CreateHere,
                 },
             },
 
-            #[name = "error_label"]
-            gtk4::Label {
-                #[watch]
-                set_visible: model.error.is_some(),
+            // Password form
+            gtk4::Box {
+                set_orientation: gtk4::Orientation::Vertical,
+                set_spacing: 20,
                 #[watch]
-                set_text: model.error.as_deref().unwrap_or(""),
-                add_css_class: "error",
-            },
+                set_visible: model.file_problem.is_none(),
 
-            gtk4::Button {
-                set_label: "Unlock",
-                add_css_class: "suggested-action",
-                add_css_class: "pill",
-                set_height_request: 40,
+                #[name = "password_entry"]
+                gtk4::PasswordEntry {
+                    set_placeholder_text: Some("Master Password"),
+                    set_show_peek_icon: true,
+                    set_hexpand: true,
 
-                #[watch]
-                set_sensitive: !model.unlocking && !model.password.is_empty(),
+                    connect_changed[sender] => move |entry| {
+                        sender.input(UnlockInput::PasswordChanged(entry.text().to_string()));
+                    },
+
+                    connect_activate[sender] => move |_| {
+                        sender.input(UnlockInput::Unlock);
+                    },
+                },
+
+                #[name = "confirm_entry"]
+                gtk4::PasswordEntry {
+                    set_placeholder_text: Some("Confirm Master Password"),
+                    set_show_peek_icon: true,
+                    set_hexpand: true,
+                    #[watch]
+                    set_visible: model.creating,
+
+                    connect_changed[sender] => move |entry| {
+                        sender.input(UnlockInput::ConfirmChanged(entry.text().to_string()));
+                    },
+
+                    connect_activate[sender] => move |_| {
+                        sender.input(UnlockInput::Unlock);
+                    },
+                },
+
+                // Key file (unlocking only)
+                gtk4::Box {
+                    set_orientation: gtk4::Orientation::Horizontal,
+                    set_spacing: 6,
+                    #[watch]
+                    set_visible: !model.creating,
+
+                    gtk4::Button {
+                        set_icon_name: "document-open-symbolic",
+                        set_tooltip_text: Some("Choose Key File"),
+                        connect_clicked => UnlockInput::ChooseKeyFile,
+                    },
+
+                    gtk4::Label {
+                        #[watch]
+                        set_text: &model
+                            .key_file
+                            .as_ref()
+                            .and_then(|path| path.file_name())
+                            .map(|name| format!("Key file: {}", name.to_string_lossy()))
+                            .unwrap_or_else(|| "No key file".to_string()),
+                        set_hexpand: true,
+                        set_halign: gtk4::Align::Start,
+                        set_ellipsize: gtk4::pango::EllipsizeMode::Middle,
+                        add_css_class: "dim-label",
+                    },
+
+                    gtk4::Button {
+                        set_icon_name: "edit-clear-symbolic",
+                        set_tooltip_text: Some("Don't Use a Key File"),
+                        add_css_class: "flat",
+                        #[watch]
+                        set_visible: model.key_file.is_some(),
+                        connect_clicked => UnlockInput::SetKeyFile(None),
+                    },
+                },
 
-                connect_clicked => UnlockInput::Unlock,
+                #[name = "error_label"]
+                gtk4::Label {
+                    #[watch]
+                    set_visible: model.error.is_some(),
+                    #[watch]
+                    set_text: model.error.as_deref().unwrap_or(""),
+                    set_wrap: true,
+                    add_css_class: "error",
+                },
+
+                gtk4::Button {
+                    #[watch]
+                    set_label: if model.creating { "Create Database" } else { "Unlock" },
+                    add_css_class: "suggested-action",
+                    add_css_class: "pill",
+                    set_height_request: 40,
+
+                    #[watch]
+                    set_sensitive: model.can_submit(),
+
+                    connect_clicked => UnlockInput::Unlock,
+                },
             },
         }
     }
 
     fn init(
-        _init: Self::Init,
+        database_path: Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
+        let file_problem = Self::check_file(&database_path);
         let model = UnlockDialog {
-            password: String::new(),
+            database_path,
+            file_problem,
+            creating: false,
+            password: Zeroizing::new(String::new()),
+            confirm: Zeroizing::new(String::new()),
+            key_file: None,
             error: None,
             unlocking: false,
         };
@@ -108,22 +258,119 @@ impl Component for UnlockDialog {
         ComponentParts { model, widgets }
     }
 
-    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, _root: &Self::Root) {
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
         match message {
             UnlockInput::PasswordChanged(password) => {
-                self.password = password;
+                // Replacing the wrapper zeroes the previous buffer on drop
+                self.password = Zeroizing::new(password);
+                self.error = None;
+            }
+            UnlockInput::ConfirmChanged(confirm) => {
+                self.confirm = Zeroizing::new(confirm);
                 self.error = None;
             }
             UnlockInput::Unlock => {
-                if !self.password.is_empty() {
+                if self.creating && self.password != self.confirm {
+                    self.error = Some("Passwords don't match".to_string());
+                } else if !self.creating && self.password.is_empty() && self.key_file.is_none() {
+                    self.error = Some("Enter a password or choose a key file".to_string());
+                } else if self.can_submit() {
                     self.unlocking = true;
-                    let _ = sender.output(UnlockOutput::Unlocked(self.password.clone()));
+                    let password = self.password.clone();
+                    let _ = sender.output(if self.creating {
+                        UnlockOutput::Create(password)
+                    } else {
+                        UnlockOutput::Unlocked {
+                            password,
+                            key_file: self.key_file.clone(),
+                        }
+                    });
                 }
             }
             UnlockInput::ShowError(error) => {
                 self.error = Some(error);
                 self.unlocking = false;
             }
+            UnlockInput::Reset => {
+                self.clear_passwords(widgets);
+                // The file may have been moved or deleted while unlocked
+                self.file_problem = Self::check_file(&self.database_path);
+                self.creating = false;
+            }
+            UnlockInput::SetDatabasePath(path, create) => {
+                self.clear_passwords(widgets);
+                self.creating = create;
+                self.file_problem = if create { None } else { Self::check_file(&path) };
+                self.database_path = path;
+            }
+            UnlockInput::ChooseFile => {
+                let _ = sender.output(UnlockOutput::ChooseDatabase);
+            }
+            UnlockInput::CreateHere => {
+                self.clear_passwords(widgets);
+                self.file_problem = None;
+                self.creating = true;
+            }
+            UnlockInput::ChooseKeyFile => {
+                let parent = root.root().and_downcast::<gtk4::Window>();
+                let file_chooser = gtk4::FileChooserNative::new(
+                    Some("Choose Key File"),
+                    parent.as_ref(),
+                    gtk4::FileChooserAction::Open,
+                    Some("Choose"),
+                    Some("Cancel"),
+                );
+
+                let sender_clone = sender.clone();
+                file_chooser.connect_response(move |dialog, response| {
+                    if response == gtk4::ResponseType::Accept {
+                        if let Some(path) = dialog.file().and_then(|f| f.path()) {
+                            sender_clone.input(UnlockInput::SetKeyFile(Some(path)));
+                        }
+                    }
+                    dialog.destroy();
+                });
+
+                file_chooser.show();
+            }
+            UnlockInput::SetKeyFile(key_file) => {
+                self.key_file = key_file;
+                self.error = None;
+                widgets.password_entry.grab_focus();
+            }
+        }
+        self.update_view(widgets, sender);
+    }
+}
+
+impl UnlockDialog {
+    /// Describe why the database file can't be opened, if it can't.
+    fn check_file(path: &std::path::Path) -> Option<String> {
+        KeepassDatabase::check_file(path).err().map(|e| format!("{:#}", e))
+    }
+
+    fn can_submit(&self) -> bool {
+        if self.creating {
+            !self.unlocking && !self.password.is_empty() && !self.confirm.is_empty()
+        } else {
+            // A key file alone is enough to unlock
+            !self.unlocking && (!self.password.is_empty() || self.key_file.is_some())
         }
     }
+
+    fn clear_passwords(&mut self, widgets: &UnlockDialogWidgets) {
+        widgets.password_entry.set_text("");
+        widgets.confirm_entry.set_text("");
+        self.password.zeroize();
+        self.confirm.zeroize();
+        self.error = None;
+        self.unlocking = false;
+        widgets.password_entry.grab_focus();
+    }
 }
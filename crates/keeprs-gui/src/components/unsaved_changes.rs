@@ -0,0 +1,151 @@
+//! Unsaved changes dialog component.
+//!
+//! Modal dialog shown when closing the window or opening another database
+//! with unsaved changes.
+
+use gtk4::prelude::*;
+use relm4::prelude::*;
+
+/// Messages for the unsaved changes dialog.
+#[derive(Debug)]
+pub enum UnsavedChangesInput {
+    /// Show the dialog with the given question.
+    Show(String),
+    /// Save, then continue.
+    Save,
+    /// Continue without saving.
+    Discard,
+    /// Abort the pending action.
+    Cancel,
+}
+
+/// Output messages.
+#[derive(Debug, Clone)]
+pub enum UnsavedChangesOutput {
+    /// User wants to save before continuing.
+    Save,
+    /// User wants to continue and drop the changes.
+    Discard,
+    /// User cancelled the pending action.
+    Cancelled,
+}
+
+/// Component model.
+pub struct UnsavedChangesDialog {
+    visible: bool,
+    message: String,
+}
+
+#[relm4::component(pub)]
+impl Component for UnsavedChangesDialog {
+    type Init = ();
+    type Input = UnsavedChangesInput;
+    type Output = UnsavedChangesOutput;
+    type CommandOutput = ();
+
+    view! {
+        #[name = "dialog"]
+        gtk4::Window {
+            set_modal: true,
+            set_default_width: 400,
+            set_resizable: false,
+            set_title: Some("Unsaved Changes"),
+            #[watch]
+            set_visible: model.visible,
+            connect_close_request[sender] => move |_| {
+                sender.input(UnsavedChangesInput::Cancel);
+                gtk4::glib::Propagation::Stop
+            },
+
+            gtk4::Box {
+                set_orientation: gtk4::Orientation::Vertical,
+                set_spacing: 0,
+
+                // Header bar
+                gtk4::HeaderBar {
+                    set_show_title_buttons: true,
+
+                    #[wrap(Some)]
+                    set_title_widget = &gtk4::Label {
+                        set_text: "Unsaved Changes",
+                        add_css_class: "title",
+                    },
+                },
+
+                gtk4::Box {
+                    set_orientation: gtk4::Orientation::Vertical,
+                    set_spacing: 16,
+                    set_margin_all: 24,
+
+                    gtk4::Label {
+                        #[watch]
+                        set_text: &model.message,
+                        set_wrap: true,
+                        set_halign: gtk4::Align::Start,
+                    },
+
+                    // Buttons
+                    gtk4::Box {
+                        set_orientation: gtk4::Orientation::Horizontal,
+                        set_spacing: 10,
+                        set_halign: gtk4::Align::End,
+                        set_margin_top: 10,
+
+                        gtk4::Button {
+                            set_label: "Cancel",
+                            connect_clicked => UnsavedChangesInput::Cancel,
+                        },
+
+                        gtk4::Button {
+                            set_label: "Discard",
+                            add_css_class: "destructive-action",
+                            connect_clicked => UnsavedChangesInput::Discard,
+                        },
+
+                        gtk4::Button {
+                            set_label: "Save",
+                            add_css_class: "suggested-action",
+                            connect_clicked => UnsavedChangesInput::Save,
+                        },
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = UnsavedChangesDialog {
+            visible: false,
+            message: String::new(),
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, _root: &Self::Root) {
+        match message {
+            UnsavedChangesInput::Show(message) => {
+                self.message = message;
+                self.visible = true;
+            }
+            UnsavedChangesInput::Save => {
+                self.visible = false;
+                let _ = sender.output(UnsavedChangesOutput::Save);
+            }
+            UnsavedChangesInput::Discard => {
+                self.visible = false;
+                let _ = sender.output(UnsavedChangesOutput::Discard);
+            }
+            UnsavedChangesInput::Cancel => {
+                self.visible = false;
+                let _ = sender.output(UnsavedChangesOutput::Cancelled);
+            }
+        }
+    }
+}
@@ -6,7 +6,10 @@ use anyhow::{Context, Result};
 use gtk4::gdk;
 use gtk4::glib::translate::FromGlib;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Commented default configuration, written by `--generate-config`.
+const CONFIG_TEMPLATE: &str = include_str!("../../../default.toml");
 
 /// Configurable keyboard shortcuts.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +17,9 @@ pub struct Keybindings {
     /// Save database shortcut (e.g., "Ctrl+S")
     #[serde(default = "default_save_database")]
     pub save_database: String,
+    /// Lock the database shortcut (e.g., "Ctrl+L")
+    #[serde(default = "default_lock_database")]
+    pub lock_database: String,
     /// Toggle search palette shortcut (e.g., "Ctrl+P")
     #[serde(default = "default_toggle_search")]
     pub toggle_search: String,
@@ -29,24 +35,36 @@ pub struct Keybindings {
     /// Confirm/select action (e.g., "Return")
     #[serde(default = "default_confirm")]
     pub confirm: String,
+    /// Go up one level in the entry browser (e.g., "Alt+Left"; Backspace always works too)
+    #[serde(default = "default_navigate_back")]
+    pub navigate_back: String,
+    /// Return to the level left with `navigate_back` (e.g., "Alt+Right")
+    #[serde(default = "default_navigate_forward")]
+    pub navigate_forward: String,
 }
 
 fn default_save_database() -> String { "Ctrl+S".to_string() }
+fn default_lock_database() -> String { "Ctrl+L".to_string() }
 fn default_toggle_search() -> String { "Ctrl+P".to_string() }
 fn default_navigate_up() -> String { "Up".to_string() }
 fn default_navigate_down() -> String { "Down".to_string() }
 fn default_close() -> String { "Escape".to_string() }
 fn default_confirm() -> String { "Return".to_string() }
+fn default_navigate_back() -> String { "Alt+Left".to_string() }
+fn default_navigate_forward() -> String { "Alt+Right".to_string() }
 
 impl Default for Keybindings {
     fn default() -> Self {
         Self {
             save_database: default_save_database(),
+            lock_database: default_lock_database(),
             toggle_search: default_toggle_search(),
             navigate_up: default_navigate_up(),
             navigate_down: default_navigate_down(),
             close: default_close(),
             confirm: default_confirm(),
+            navigate_back: default_navigate_back(),
+            navigate_forward: default_navigate_forward(),
         }
     }
 }
@@ -163,6 +181,28 @@ impl Keybindings {
     }
 }
 
+/// Action taken when the main window loses focus.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FocusLossAction {
+    /// Hide revealed passwords and TOTP codes.
+    Mask,
+    /// Lock the database and return to the unlock screen.
+    #[default]
+    Lock,
+}
+
+/// How much room each row of the entry list takes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryListDensity {
+    /// Tight rows showing only the title (the username moves to the tooltip).
+    Compact,
+    /// Roomy rows with the username under the title.
+    #[default]
+    Comfortable,
+}
+
 /// Application configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -174,15 +214,80 @@ pub struct Config {
     /// Minimum width the sidebar can be shrunk to.
     #[serde(default = "default_sidebar_min_width")]
     pub sidebar_min_width: i32,
+    /// Minimum width of the entry list and entry detail columns; narrower
+    /// windows scroll horizontally instead of squeezing them.
+    #[serde(default = "default_column_min_width")]
+    pub column_min_width: i32,
     /// Whether to show the password entropy bar.
     #[serde(default = "default_show_entropy_bar")]
     pub show_entropy_bar: bool,
     /// Whether to show TOTP codes by default (visible) or hidden.
     #[serde(default = "default_show_totp_visible")]
     pub show_totp_visible: bool,
+    /// Render entry notes as markdown (editing stays plain text).
+    #[serde(default)]
+    pub render_notes_markdown: bool,
+    /// Row spacing of the entry list.
+    #[serde(default)]
+    pub entry_list_density: EntryListDensity,
     /// List of group/entry names to hide from the UI.
     #[serde(default)]
     pub hidden_groups: Vec<String>,
+    /// Hide groups that contain no entries (recursively) from the sidebar.
+    #[serde(default)]
+    pub hide_empty_groups: bool,
+    /// Permanently delete recycle bin items older than this many days on unlock (0 = never).
+    #[serde(default)]
+    pub recycle_bin_auto_purge_days: u64,
+    /// Save after every change. When off, changes wait for the save shortcut.
+    #[serde(default = "default_auto_save")]
+    pub auto_save: bool,
+    /// Keep this many timestamped backups of the database file, made before
+    /// each save (0 = no backups).
+    #[serde(default)]
+    pub backup_count: usize,
+    /// Ask for the master password before permanently deleting items.
+    /// When disabled a plain yes/no confirmation is shown instead.
+    #[serde(default = "default_confirm_permanent_delete_with_password")]
+    pub confirm_permanent_delete_with_password: bool,
+    /// React when the main window loses focus (see `focus_loss_action`).
+    #[serde(default)]
+    pub lock_on_focus_loss: bool,
+    /// What to do when the window loses focus and `lock_on_focus_loss` is set.
+    #[serde(default)]
+    pub focus_loss_action: FocusLossAction,
+    /// Lock after this many minutes without keyboard or mouse activity (0 = never).
+    #[serde(default)]
+    pub auto_lock_minutes: u32,
+    /// strftime-style format for displayed times (empty = locale default).
+    #[serde(default)]
+    pub datetime_format: String,
+    /// Never access the network (no favicon fetching).
+    #[serde(default)]
+    pub offline: bool,
+    /// Open the database read-only: nothing can be changed or saved.
+    #[serde(default)]
+    pub read_only: bool,
+    /// Suggest a title from the URL when adding an entry (e.g. `github.com` → `GitHub`).
+    #[serde(default = "default_suggest_title_from_url")]
+    pub suggest_title_from_url: bool,
+    /// Group new entries go to when no group is selected, by UUID or path
+    /// (e.g. `Work/Email`); empty for the root group.
+    #[serde(default)]
+    pub default_new_entry_group: String,
+    /// Command that opens URLs and attachments, `{}` standing for the target
+    /// (empty = the platform default, e.g. `xdg-open`).
+    #[serde(default)]
+    pub open_command: String,
+    /// Clear copied values from the clipboard after this many seconds (0 = never).
+    #[serde(default = "default_clipboard_clear_seconds")]
+    pub clipboard_clear_seconds: u64,
+    /// The password audit lists entries not modified in this many days (0 = never).
+    #[serde(default = "default_password_max_age_days")]
+    pub password_max_age_days: u64,
+    /// Largest file that can be attached to an entry, in bytes (0 = no limit).
+    #[serde(default = "default_max_attachment_bytes")]
+    pub max_attachment_bytes: u64,
     /// Keyboard shortcuts.
     #[serde(default)]
     pub keybindings: Keybindings,
@@ -196,6 +301,10 @@ fn default_sidebar_min_width() -> i32 {
     150
 }
 
+fn default_column_min_width() -> i32 {
+    250
+}
+
 fn default_show_entropy_bar() -> bool {
     true
 }
@@ -204,15 +313,59 @@ fn default_show_totp_visible() -> bool {
     false
 }
 
+fn default_auto_save() -> bool {
+    true
+}
+
+fn default_confirm_permanent_delete_with_password() -> bool {
+    true
+}
+
+fn default_suggest_title_from_url() -> bool {
+    true
+}
+
+fn default_clipboard_clear_seconds() -> u64 {
+    12
+}
+
+fn default_password_max_age_days() -> u64 {
+    365
+}
+
+fn default_max_attachment_bytes() -> u64 {
+    keeprs_core::DEFAULT_MAX_ATTACHMENT_BYTES
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             database_path: PathBuf::from("database.kdbx"),
             sidebar_initial_width: default_sidebar_initial_width(),
             sidebar_min_width: default_sidebar_min_width(),
+            column_min_width: default_column_min_width(),
             show_entropy_bar: default_show_entropy_bar(),
             show_totp_visible: default_show_totp_visible(),
+            render_notes_markdown: false,
+            entry_list_density: EntryListDensity::default(),
             hidden_groups: Vec::new(),
+            hide_empty_groups: false,
+            recycle_bin_auto_purge_days: 0,
+            auto_save: default_auto_save(),
+            backup_count: 0,
+            confirm_permanent_delete_with_password: default_confirm_permanent_delete_with_password(),
+            lock_on_focus_loss: false,
+            focus_loss_action: FocusLossAction::default(),
+            auto_lock_minutes: 0,
+            datetime_format: String::new(),
+            offline: false,
+            read_only: false,
+            suggest_title_from_url: default_suggest_title_from_url(),
+            default_new_entry_group: String::new(),
+            open_command: String::new(),
+            clipboard_clear_seconds: default_clipboard_clear_seconds(),
+            password_max_age_days: default_password_max_age_days(),
+            max_attachment_bytes: default_max_attachment_bytes(),
             keybindings: Keybindings::default(),
         }
     }
@@ -270,8 +423,25 @@ impl Config {
             .with_context(|| format!("Failed to write config file: {}", config_path.display()))
     }
 
+    /// Write the commented default configuration to `path`.
+    ///
+    /// An existing file is only replaced with `force`.
+    pub fn write_template(path: &Path, force: bool) -> Result<()> {
+        if path.exists() && !force {
+            anyhow::bail!("Config file already exists: {} (use --force to overwrite it)", path.display());
+        }
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create config directory: {}", parent.display()))?;
+        }
+
+        std::fs::write(path, CONFIG_TEMPLATE)
+            .with_context(|| format!("Failed to write config file: {}", path.display()))
+    }
+
     /// Get the path to the config file.
-    fn config_path() -> Result<PathBuf> {
+    pub fn config_path() -> Result<PathBuf> {
         let config_dir = dirs::config_dir()
             .context("Could not determine config directory")?;
 
@@ -0,0 +1,143 @@
+//! Handing files and URLs to the desktop.
+//!
+//! Thin wrappers around the platform's "open" command (or the one set with
+//! [`set_open_command`]), so callers don't hardcode `xdg-open`. Both
+//! functions block until the helper has been started (and for `reveal` on
+//! Linux, until the file manager answered), so call them off the main thread.
+
+use std::ffi::OsStr;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Program and arguments from the `open_command` setting.
+static OPEN_COMMAND: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Open files and URLs with `command` instead of the platform default.
+///
+/// `{}` in an argument is replaced by the target; without one the target is
+/// appended. Quoting follows the shell, e.g. `flatpak-spawn --host xdg-open {}`.
+pub fn set_open_command(command: &str) -> anyhow::Result<()> {
+    let Some(args) = shlex::split(command).filter(|args| !args.is_empty()) else {
+        anyhow::bail!("Invalid open_command '{}'", command);
+    };
+    if OPEN_COMMAND.set(args).is_err() {
+        anyhow::bail!("The open command is already set");
+    }
+    Ok(())
+}
+
+/// Open a file or URL with its default application.
+pub fn open(target: impl AsRef<OsStr>) -> io::Result<()> {
+    let target = target.as_ref();
+    let mut command = match OPEN_COMMAND.get() {
+        Some(args) => configured_command(args, target),
+        None => open_command(target),
+    };
+    command
+        .spawn()
+        .map(drop)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", command.get_program().to_string_lossy(), e)))
+}
+
+/// Show `path` in the file manager, selected if the file manager supports it.
+pub fn reveal(path: &Path) -> io::Result<()> {
+    #[cfg(target_os = "macos")]
+    {
+        Command::new("open").arg("-R").arg(path).spawn().map(drop)
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let mut select = std::ffi::OsString::from("/select,");
+        select.push(path);
+        Command::new("explorer").arg(select).spawn().map(drop)
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        if show_items(path) {
+            return Ok(());
+        }
+        // No FileManager1 service: settle for opening the folder
+        let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        open(parent)
+    }
+}
+
+fn configured_command(args: &[String], target: &OsStr) -> Command {
+    let mut command = Command::new(&args[0]);
+    let mut substituted = false;
+    for arg in &args[1..] {
+        if arg.contains("{}") {
+            let mut replaced = std::ffi::OsString::new();
+            let mut parts = arg.split("{}");
+            replaced.push(parts.next().unwrap_or_default());
+            for part in parts {
+                replaced.push(target);
+                replaced.push(part);
+            }
+            command.arg(replaced);
+            substituted = true;
+        } else {
+            command.arg(arg);
+        }
+    }
+    if !substituted {
+        command.arg(target);
+    }
+    command
+}
+
+fn open_command(target: &OsStr) -> Command {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        // The empty argument is the window title `start` would otherwise take from a quoted target
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = Command::new("xdg-open");
+
+    command.arg(target);
+    command
+}
+
+/// Ask the file manager to show the item through the freedesktop
+/// `org.freedesktop.FileManager1` D-Bus interface.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn show_items(path: &Path) -> bool {
+    use gtk4::gio;
+    use gtk4::prelude::*;
+    use std::process::Stdio;
+
+    let uri = gio::File::for_path(path).uri();
+    let status = Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+        ])
+        .arg(format!("array:string:{}", uri))
+        .arg("string:")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+
+    match status {
+        Ok(status) if status.success() => true,
+        Ok(status) => {
+            tracing::debug!(%status, "FileManager1.ShowItems failed");
+            false
+        }
+        Err(e) => {
+            tracing::debug!("dbus-send unavailable: {}", e);
+            false
+        }
+    }
+}
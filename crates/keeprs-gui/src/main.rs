@@ -3,20 +3,30 @@
 //! Built with GTK4 and Relm4.
 
 mod app;
+mod clipboard;
 mod components;
 mod config;
+mod desktop;
+mod network;
 mod widgets;
 
 use anyhow::Result;
 use clap::Parser;
+use gtk4::gio;
+use gtk4::prelude::*;
 use relm4::prelude::*;
 use std::path::PathBuf;
+use std::time::Duration;
 use tracing_subscriber::EnvFilter;
 
 /// Keeprs - A minimalist KeePass database manager
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    /// Database to open: a path or file:// URI. Handed to the running instance if there is one
+    #[arg(value_name = "FILE")]
+    file: Option<String>,
+
     /// Path to the configuration file
     #[arg(short, long, value_name = "FILE")]
     config: Option<PathBuf>,
@@ -24,6 +34,22 @@ struct Args {
     /// Path to the KeePass database file (overrides config)
     #[arg(short, long, value_name = "FILE")]
     database: Option<PathBuf>,
+
+    /// Disable all network access (e.g. favicon fetching)
+    #[arg(long)]
+    offline: bool,
+
+    /// Open the database read-only (no changes are saved)
+    #[arg(long)]
+    read_only: bool,
+
+    /// Write a commented default config (to the default location unless FILE is given) and exit
+    #[arg(long, value_name = "FILE", num_args = 0..=1)]
+    generate_config: Option<Option<PathBuf>>,
+
+    /// Overwrite an existing file with --generate-config
+    #[arg(long, requires = "generate_config")]
+    force: bool,
 }
 
 fn main() -> Result<()> {
@@ -31,6 +57,16 @@ fn main() -> Result<()> {
     // This consumes the args so GTK won't see them
     let args = Args::parse();
 
+    if let Some(path) = args.generate_config {
+        let path = match path {
+            Some(path) => path,
+            None => config::Config::config_path()?,
+        };
+        config::Config::write_template(&path, args.force)?;
+        println!("Wrote default configuration to {}", path.display());
+        return Ok(());
+    }
+
     // Initialize logging
     tracing_subscriber::fmt()
         .with_env_filter(EnvFilter::from_default_env().add_directive("keeprs=info".parse()?))
@@ -47,12 +83,60 @@ fn main() -> Result<()> {
         config.database_path = database_path;
     }
     
+    if let Some(file) = &args.file {
+        match gio::File::for_commandline_arg(file).path() {
+            Some(path) => config.database_path = path,
+            None => anyhow::bail!("Not a local file: {}", file),
+        }
+    }
+
     tracing::info!("Database path: {}", config.database_path.display());
 
-    // Run GTK application with empty args to prevent GTK from seeing our CLI args
-    let app = RelmApp::new("io.github.keeprs");
-    // Set empty args so GTK doesn't complain about unknown options
-    app.with_args(Vec::<String>::new()).run::<app::App>(config);
+    if args.offline {
+        config.offline = true;
+    }
+    if config.offline {
+        tracing::info!("Offline mode: network access disabled");
+    }
+    network::set_offline(config.offline);
+    if args.read_only {
+        config.read_only = true;
+    }
+    if config.read_only {
+        tracing::info!("Read-only mode: the database will not be modified");
+    }
+    if !config.open_command.trim().is_empty() {
+        if let Err(e) = desktop::set_open_command(&config.open_command) {
+            tracing::warn!("{:#}, using the platform default", e);
+        }
+    }
+    clipboard::ClipboardGuard::set_clear_after(
+        (config.clipboard_clear_seconds > 0).then(|| Duration::from_secs(config.clipboard_clear_seconds)),
+    );
+
+    // HANDLES_OPEN makes a second invocation forward its file to the running
+    // instance (see `connect_open` in the app) instead of starting a new window
+    let gtk_app = gtk4::Application::builder()
+        .application_id("io.github.keeprs")
+        .flags(gio::ApplicationFlags::HANDLES_OPEN)
+        .build();
+    // GTK emits `open` instead of `activate` when given a file, and the window is
+    // only built on `activate`. The first instance already has the file in the
+    // config, so only hand it to GTK when it goes to a running instance.
+    let forward_file = match gtk_app.register(gio::Cancellable::NONE) {
+        Ok(()) => gtk_app.is_remote(),
+        Err(e) => {
+            tracing::warn!("Failed to register the application: {}", e);
+            false
+        }
+    };
+    let app = RelmApp::from_app(gtk_app);
+    // Only hand GTK the file to open so it doesn't complain about our other options
+    let gtk_args: Vec<String> = std::env::args()
+        .take(1)
+        .chain(args.file.filter(|_| forward_file))
+        .collect();
+    app.with_args(gtk_args).run::<app::App>(config);
 
     Ok(())
 }
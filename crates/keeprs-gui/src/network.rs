@@ -0,0 +1,31 @@
+//! Outgoing network requests.
+//!
+//! Every network call goes through here so offline mode can block all of them.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable offline mode for the whole process.
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Whether network access is disabled.
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Fetch a URL and return the response body (blocking).
+///
+/// Returns `None` on failure, and without touching the network in offline mode.
+pub fn fetch_bytes(url: &str) -> Option<Vec<u8>> {
+    if is_offline() {
+        tracing::debug!("Offline mode: skipping request to {}", url);
+        return None;
+    }
+    reqwest::blocking::get(url)
+        .ok()
+        .and_then(|resp| resp.bytes().ok())
+        .map(|b| b.to_vec())
+}
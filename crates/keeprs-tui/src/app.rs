@@ -1,8 +1,24 @@
 //! Application state management.
 
-use keeprs_core::{Entry, Group, KeepassDatabase};
+use crate::clipboard;
+use crate::handlers::Key;
+use keeprs_core::{Entry, Group, KeepassDatabase, Totp};
+use ratatui::layout::Rect;
+use std::cell::Cell;
 use std::collections::HashSet;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Preallocated size of the password buffer, so typing doesn't reallocate
+/// and leave un-wiped copies of the password behind.
+const PASSWORD_CAPACITY: usize = 256;
+
+/// Entries expiring within this many days are highlighted as expiring soon.
+const EXPIRY_WARNING_DAYS: i64 = 14;
+
+/// How long a transient status message (e.g. "Password copied") stays visible.
+const FLASH_DURATION: Duration = Duration::from_secs(2);
 
 /// Application state.
 #[derive(Debug, Clone, PartialEq)]
@@ -43,8 +59,16 @@ pub struct App {
     pub input_mode: InputMode,
     /// Current focus area.
     pub focus: Focus,
+    /// Whether the key help overlay is shown.
+    pub show_help: bool,
+    /// Keys typed so far of a multi-key binding (e.g. the first `g` of `gg`).
+    pub pending_keys: Vec<Key>,
     /// Path to the database file.
     pub database_path: PathBuf,
+    /// Key file to unlock the database with, if any.
+    pub key_file: Option<PathBuf>,
+    /// Never write the database back to disk.
+    pub read_only: bool,
     /// The opened database (if unlocked).
     pub database: Option<KeepassDatabase>,
     /// Root group of the database.
@@ -52,6 +76,26 @@ pub struct App {
     /// Currently selected entry.
     pub selected_entry: Option<Entry>,
 
+    // Status line state
+    /// Number of entries in the database.
+    pub entry_count: usize,
+    /// Human readable size of the database file.
+    pub db_size: String,
+    /// Whether there are changes not yet written to disk.
+    pub unsaved_changes: bool,
+    /// Time of the last successful save.
+    pub last_save_time: Option<String>,
+    /// Transient message for the status line (save result, errors).
+    pub status_message: Option<String>,
+    /// When the current status message disappears (`None` keeps it until replaced).
+    pub status_expires_at: Option<Instant>,
+
+    // Clipboard state
+    /// Seconds after which copied values are cleared from the clipboard (0 = never).
+    pub clipboard_clear_seconds: u64,
+    /// When the clipboard should be cleared next.
+    pub clipboard_clear_at: Option<Instant>,
+
     // Sidebar state
     /// UUIDs of expanded groups.
     pub expanded_groups: HashSet<String>,
@@ -59,10 +103,18 @@ pub struct App {
     pub sidebar_selected_index: usize,
     /// Cached flattened tree items for rendering.
     pub tree_items: Vec<TreeItem>,
+    /// Only show expired or soon expiring entries in the tree.
+    pub expiring_only: bool,
+    /// Where the sidebar list was last drawn (for mouse hit-testing).
+    pub sidebar_area: Cell<Rect>,
+    /// First visible tree item in the sidebar (updated while rendering).
+    pub sidebar_offset: Cell<usize>,
+    /// Where the entry view was last drawn (for mouse hit-testing).
+    pub entry_view_area: Cell<Rect>,
 
     // Password entry state
-    /// Password being entered.
-    pub password_input: String,
+    /// Password being entered (wiped from memory when cleared or dropped).
+    pub password_input: Zeroizing<String>,
     /// Error message to display.
     pub error_message: Option<String>,
 
@@ -90,6 +142,8 @@ pub struct TreeItem {
     pub is_expanded: bool,
     /// Whether group has children.
     pub has_children: bool,
+    /// Expiry of the entry (always `Valid` for groups).
+    pub expiry: ExpiryStatus,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -98,6 +152,48 @@ pub enum TreeItemKind {
     Entry,
 }
 
+/// How close an entry is to its expiry time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpiryStatus {
+    /// No expiry, or not within the warning window.
+    Valid,
+    /// Expires within `EXPIRY_WARNING_DAYS`.
+    ExpiringSoon,
+    /// The expiry time has passed.
+    Expired,
+}
+
+impl ExpiryStatus {
+    /// Expiry status of `entry` as of now.
+    pub fn of(entry: &Entry) -> Self {
+        if entry.is_expired() {
+            ExpiryStatus::Expired
+        } else if entry.expires_within(EXPIRY_WARNING_DAYS) {
+            ExpiryStatus::ExpiringSoon
+        } else {
+            ExpiryStatus::Valid
+        }
+    }
+}
+
+/// Entry field that can be copied to the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyField {
+    Username,
+    Password,
+    Totp,
+}
+
+impl CopyField {
+    fn label(self) -> &'static str {
+        match self {
+            CopyField::Username => "Username",
+            CopyField::Password => "Password",
+            CopyField::Totp => "TOTP code",
+        }
+    }
+}
+
 /// A search result.
 #[derive(Debug, Clone)]
 pub struct SearchResult {
@@ -114,19 +210,35 @@ pub struct SearchResult {
 
 impl App {
     /// Create a new application instance.
-    pub fn new(database_path: PathBuf) -> Self {
+    pub fn new(database_path: PathBuf, key_file: Option<PathBuf>, read_only: bool, clipboard_clear_seconds: u64) -> Self {
         Self {
             state: AppState::Locked,
             input_mode: InputMode::PasswordEntry,
             focus: Focus::Sidebar,
+            show_help: false,
+            pending_keys: Vec::new(),
             database_path,
+            key_file,
+            read_only,
             database: None,
             root_group: None,
             selected_entry: None,
+            entry_count: 0,
+            db_size: String::new(),
+            unsaved_changes: false,
+            last_save_time: None,
+            status_message: None,
+            status_expires_at: None,
+            clipboard_clear_seconds,
+            clipboard_clear_at: None,
             expanded_groups: HashSet::new(),
             sidebar_selected_index: 0,
             tree_items: Vec::new(),
-            password_input: String::new(),
+            expiring_only: false,
+            sidebar_area: Cell::new(Rect::default()),
+            sidebar_offset: Cell::new(0),
+            entry_view_area: Cell::new(Rect::default()),
+            password_input: Zeroizing::new(String::with_capacity(PASSWORD_CAPACITY)),
             error_message: None,
             search_query: String::new(),
             search_results: Vec::new(),
@@ -134,13 +246,28 @@ impl App {
         }
     }
 
-    /// Attempt to unlock the database with the current password.
+    /// Attempt to unlock the database with the current password and key file.
     pub fn try_unlock(&mut self) -> bool {
-        match KeepassDatabase::unlock(&self.database_path, &self.password_input) {
-            Ok(db) => {
+        if self.password_input.is_empty() && self.key_file.is_none() {
+            self.error_message = Some("Enter a password (or start with --key-file)".to_string());
+            return false;
+        }
+
+        // An empty password next to a key file means the database has no password
+        let password = (!self.password_input.is_empty() || self.key_file.is_none())
+            .then_some(self.password_input.as_str());
+        let result = KeepassDatabase::unlock_with_keyfile(&self.database_path, password, self.key_file.as_deref());
+        // The password is no longer needed either way
+        self.password_input.zeroize();
+
+        match result {
+            Ok(mut db) => {
+                db.set_read_only(self.read_only);
                 let root = db.root_group();
                 // Expand root by default
                 self.expanded_groups.insert(root.uuid.clone());
+                self.entry_count = count_entries(&root);
+                self.refresh_db_size();
                 self.root_group = Some(root);
                 self.database = Some(db);
                 self.state = AppState::Unlocked;
@@ -151,12 +278,133 @@ impl App {
             }
             Err(e) => {
                 self.error_message = Some(format!("Failed to unlock: {}", e));
-                self.password_input.clear();
                 false
             }
         }
     }
 
+    /// Whether a TOTP code (with its countdown) is currently on screen.
+    pub fn has_visible_totp(&self) -> bool {
+        self.state == AppState::Unlocked
+            && self.selected_entry.as_ref().is_some_and(|e| e.otp.is_some())
+    }
+
+    /// Database file name for display.
+    pub fn db_filename(&self) -> String {
+        self.database_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.database_path.display().to_string())
+    }
+
+    /// Write the database to disk and update the status line.
+    pub fn save(&mut self) {
+        let Some(ref db) = self.database else {
+            return;
+        };
+
+        match db.save() {
+            Ok(()) => {
+                self.unsaved_changes = false;
+                self.last_save_time = Some(chrono::Local::now().format("%H:%M:%S").to_string());
+                self.status_message = Some("Saved".to_string());
+                self.status_expires_at = None;
+                self.refresh_db_size();
+                tracing::info!("Database saved");
+            }
+            Err(e) => {
+                tracing::error!("Failed to save database: {:#}", e);
+                self.status_message = Some(format!("Save failed: {:#}", e));
+                self.status_expires_at = None;
+            }
+        }
+    }
+
+    /// Show a status message that disappears after a couple of seconds.
+    pub fn flash_status(&mut self, message: impl Into<String>) {
+        self.status_message = Some(message.into());
+        self.status_expires_at = Some(Instant::now() + FLASH_DURATION);
+    }
+
+    /// Copy a field of the selected entry to the clipboard.
+    ///
+    /// References are resolved first, and the clipboard is cleared again after
+    /// `clipboard_clear_seconds`.
+    pub fn copy_field(&mut self, field: CopyField) {
+        let Some(ref entry) = self.selected_entry else {
+            self.flash_status("No entry selected");
+            return;
+        };
+
+        let resolve = |raw: &str| match self.root_group {
+            Some(ref root) => root.resolve_field(&entry.uuid, raw),
+            None => raw.to_string(),
+        };
+        let value = match field {
+            CopyField::Username => resolve(&entry.username),
+            CopyField::Password => resolve(&entry.password),
+            CopyField::Totp => match entry.otp.as_deref().map(str::parse::<Totp>) {
+                Some(Ok(totp)) => totp.code_now(),
+                Some(Err(_)) => {
+                    self.flash_status("Invalid TOTP");
+                    return;
+                }
+                None => {
+                    self.flash_status("Entry has no TOTP");
+                    return;
+                }
+            },
+        };
+        let value = Zeroizing::new(value);
+
+        if value.is_empty() {
+            self.flash_status(format!("{} is empty", field.label()));
+            return;
+        }
+
+        match clipboard::copy(&value) {
+            Ok(()) => {
+                self.clipboard_clear_at = (self.clipboard_clear_seconds > 0)
+                    .then(|| Instant::now() + Duration::from_secs(self.clipboard_clear_seconds));
+                self.flash_status(format!("{} copied", field.label()));
+            }
+            Err(e) => {
+                tracing::error!("Failed to copy to clipboard: {}", e);
+                self.flash_status(format!("Copy failed: {}", e));
+            }
+        }
+    }
+
+    /// Earliest pending timer (status message expiry or clipboard clear).
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.status_expires_at.into_iter().chain(self.clipboard_clear_at).min()
+    }
+
+    /// Run timers that are due. Returns true if the screen needs a redraw.
+    pub fn tick(&mut self) -> bool {
+        let now = Instant::now();
+        let mut changed = false;
+
+        if self.clipboard_clear_at.is_some_and(|at| at <= now) {
+            self.clipboard_clear_at = None;
+            if let Err(e) = clipboard::clear() {
+                tracing::warn!("Failed to clear clipboard: {}", e);
+            }
+        }
+        if self.status_expires_at.is_some_and(|at| at <= now) {
+            self.status_expires_at = None;
+            self.status_message = None;
+            changed = true;
+        }
+        changed
+    }
+
+    fn refresh_db_size(&mut self) {
+        self.db_size = std::fs::metadata(&self.database_path)
+            .map(|m| format_size(m.len()))
+            .unwrap_or_else(|_| "Unknown".to_string());
+    }
+
     /// Rebuild the flattened tree items from the current group hierarchy.
     pub fn rebuild_tree(&mut self) {
         self.tree_items.clear();
@@ -165,9 +413,29 @@ impl App {
         }
     }
 
+    /// Toggle between the full tree and only expired or soon expiring entries.
+    pub fn toggle_expiring_filter(&mut self) {
+        self.expiring_only = !self.expiring_only;
+        self.sidebar_selected_index = 0;
+        self.rebuild_tree();
+        self.status_expires_at = None;
+        self.status_message = if !self.expiring_only {
+            None
+        } else if self.tree_items.is_empty() {
+            Some("No expired or expiring entries".to_string())
+        } else {
+            Some("Showing expired and expiring entries".to_string())
+        };
+    }
+
     fn flatten_group(&mut self, group: &Group, depth: usize) {
+        // The expiry filter shows every group leading to a matching entry, expanded
+        if self.expiring_only && !has_expiring_entries(group) {
+            return;
+        }
+
         // Add folder only if not root (depth > 0) or always for non-root
-        let is_expanded = self.expanded_groups.contains(&group.uuid);
+        let is_expanded = self.expiring_only || self.expanded_groups.contains(&group.uuid);
         let has_children = !group.children.is_empty() || !group.entries.is_empty();
 
         // Skip adding root group as an item but process its children
@@ -179,6 +447,7 @@ impl App {
                 name: group.name.clone(),
                 is_expanded,
                 has_children,
+                expiry: ExpiryStatus::Valid,
             });
         }
 
@@ -188,6 +457,10 @@ impl App {
                 self.flatten_group(child, depth + 1);
             }
             for entry in &group.entries {
+                let expiry = ExpiryStatus::of(entry);
+                if self.expiring_only && expiry == ExpiryStatus::Valid {
+                    continue;
+                }
                 self.tree_items.push(TreeItem {
                     depth: depth + 1,
                     kind: TreeItemKind::Entry,
@@ -195,11 +468,36 @@ impl App {
                     name: entry.title.clone(),
                     is_expanded: false,
                     has_children: false,
+                    expiry,
                 });
             }
         }
     }
 
+    /// Scroll offset keeping the selection visible in a sidebar of `height` rows.
+    ///
+    /// Clamped so a resize never leaves the list scrolled past its end.
+    pub fn sidebar_scroll_offset(&self, height: usize) -> usize {
+        let selected = self.sidebar_selected_index;
+        let mut offset = self.sidebar_offset.get();
+
+        if height == 0 {
+            return 0;
+        }
+        if selected < offset {
+            offset = selected;
+        } else if selected >= offset + height {
+            offset = selected + 1 - height;
+        }
+        offset.min(self.tree_items.len().saturating_sub(height))
+    }
+
+    /// Keep selections within bounds after the tree or the terminal changed.
+    pub fn clamp_selection(&mut self) {
+        self.sidebar_selected_index = self.sidebar_selected_index.min(self.tree_items.len().saturating_sub(1));
+        self.search_selected_index = self.search_selected_index.min(self.search_results.len().saturating_sub(1));
+    }
+
     /// Toggle expansion of a group.
     pub fn toggle_expand(&mut self, uuid: &str) {
         if self.expanded_groups.contains(uuid) {
@@ -295,40 +593,34 @@ impl App {
         self.search_results.clear();
     }
 
-    /// Perform fuzzy search on the database.
+    /// Search the database and rank the matches.
+    ///
+    /// Matching is done by [`KeepassDatabase::search`]; fuzzy scores on the
+    /// title, username and path only decide the order.
     pub fn perform_search(&mut self) {
         use fuzzy_matcher::skim::SkimMatcherV2;
         use fuzzy_matcher::FuzzyMatcher;
 
         self.search_results.clear();
 
-        let Some(ref root) = self.root_group else {
+        let Some(ref db) = self.database else {
             return;
         };
 
-        if self.search_query.trim().is_empty() {
-            return;
-        }
-
         let matcher = SkimMatcherV2::default();
 
-        // Collect all entries with their paths
-        let mut items = Vec::new();
-        self.collect_entries(root, "", &mut items);
-
         // Score and sort
-        let mut scored: Vec<_> = items
+        let mut scored: Vec<_> = db
+            .search(&self.search_query)
             .into_iter()
-            .filter_map(|(entry, path, group_uuid)| {
-                let search_text = format!("{} {} {}", entry.title, entry.username, path);
-                matcher
-                    .fuzzy_match(&search_text, &self.search_query)
-                    .map(|score| SearchResult {
-                        entry,
-                        path,
-                        group_uuid,
-                        score,
-                    })
+            .map(|found| {
+                let search_text = format!("{} {} {}", found.entry.title, found.entry.username, found.group_path);
+                SearchResult {
+                    score: matcher.fuzzy_match(&search_text, &self.search_query).unwrap_or(0),
+                    entry: found.entry,
+                    path: found.group_path,
+                    group_uuid: found.group_uuid,
+                }
             })
             .collect();
 
@@ -337,22 +629,6 @@ impl App {
         self.search_selected_index = 0;
     }
 
-    fn collect_entries(&self, group: &Group, path: &str, items: &mut Vec<(Entry, String, String)>) {
-        let current_path = if path.is_empty() {
-            group.name.clone()
-        } else {
-            format!("{} / {}", path, group.name)
-        };
-
-        for entry in &group.entries {
-            items.push((entry.clone(), current_path.clone(), group.uuid.clone()));
-        }
-
-        for child in &group.children {
-            self.collect_entries(child, &current_path, items);
-        }
-    }
-
     /// Select a search result.
     pub fn select_search_result(&mut self) {
         let Some(result) = self.search_results.get(self.search_selected_index).cloned() else {
@@ -404,3 +680,28 @@ impl App {
         false
     }
 }
+
+/// Whether the group or any subgroup holds an expired or soon expiring entry.
+fn has_expiring_entries(group: &Group) -> bool {
+    group.entries.iter().any(|e| e.expires_within(EXPIRY_WARNING_DAYS))
+        || group.children.iter().any(has_expiring_entries)
+}
+
+/// Count entries recursively.
+fn count_entries(group: &Group) -> usize {
+    group.entries.len() + group.children.iter().map(count_entries).sum::<usize>()
+}
+
+/// Format a byte count for display.
+fn format_size(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+
+    if bytes >= MB {
+        format!("{:.2} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.2} KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{} B", bytes)
+    }
+}
@@ -0,0 +1,24 @@
+//! Clipboard access through the terminal (OSC 52).
+//!
+//! The terminal emulator owns the clipboard, so copying also works over SSH
+//! and without a display server. Terminals that don't support OSC 52 ignore it.
+
+use base64::Engine;
+use std::io::{self, Write};
+
+/// Put `text` on the system clipboard.
+pub fn copy(text: &str) -> io::Result<()> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    write_selection(&encoded)
+}
+
+/// Empty the system clipboard.
+pub fn clear() -> io::Result<()> {
+    write_selection("")
+}
+
+fn write_selection(payload: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", payload)?;
+    stdout.flush()
+}
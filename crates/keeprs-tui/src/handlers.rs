@@ -1,7 +1,9 @@
-//! Keyboard event handling.
+//! Keyboard and mouse event handling.
 
-use crate::app::{App, AppState, Focus, InputMode};
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crate::app::{App, AppState, CopyField, Focus, InputMode, TreeItemKind};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+use zeroize::Zeroize;
 
 /// Handle a key event. Returns true if the app should quit.
 pub fn handle_key(app: &mut App, key: KeyEvent) -> bool {
@@ -18,6 +20,17 @@ fn handle_password_key(app: &mut App, key: KeyEvent) -> bool {
             app.try_unlock();
             false
         }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.password_input.zeroize();
+            false
+        }
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.state = AppState::Quit;
+            true
+        }
+        // Other Ctrl/Alt shortcuts aren't part of the password (AltGr symbols can
+        // arrive as Ctrl+Alt, so only letters are dropped)
+        KeyCode::Char(c) if c.is_ascii_alphabetic() && key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => false,
         KeyCode::Char(c) => {
             app.password_input.push(c);
             false
@@ -34,87 +47,206 @@ fn handle_password_key(app: &mut App, key: KeyEvent) -> bool {
     }
 }
 
-fn handle_normal_key(app: &mut App, key: KeyEvent) -> bool {
-    // Ctrl+C to quit
-    if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
-        app.state = AppState::Quit;
-        return true;
+/// A key press, as used in the keymap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    const fn plain(code: KeyCode) -> Self {
+        Self { code, modifiers: KeyModifiers::NONE }
     }
 
-    // Ctrl+P to search
-    if key.code == KeyCode::Char('p') && key.modifiers.contains(KeyModifiers::CONTROL) {
-        app.start_search();
-        return false;
+    const fn ctrl(c: char) -> Self {
+        Self { code: KeyCode::Char(c), modifiers: KeyModifiers::CONTROL }
     }
 
-    match key.code {
-        KeyCode::Char('q') => {
-            app.state = AppState::Quit;
-            true
-        }
-        KeyCode::Char('j') | KeyCode::Down => {
-            if app.focus == Focus::Sidebar {
-                app.move_down();
-            }
-            false
-        }
-        KeyCode::Char('k') | KeyCode::Up => {
-            if app.focus == Focus::Sidebar {
-                app.move_up();
-            }
-            false
-        }
-        KeyCode::Left => {
-            // Collapse current group or go to parent
-            if app.focus == Focus::Sidebar {
-                app.collapse_current();
-            }
-            false
+    fn from_event(event: KeyEvent) -> Self {
+        // Shift is already reflected in the character (e.g. 'G')
+        let modifiers = match event.code {
+            KeyCode::Char(_) => event.modifiers.difference(KeyModifiers::SHIFT),
+            _ => event.modifiers,
+        };
+        Self { code: event.code, modifiers }
+    }
+
+    /// Human readable name for the help overlay.
+    pub fn label(&self) -> String {
+        let code = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            other => format!("{:?}", other),
+        };
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            format!("Ctrl+{}", code)
+        } else {
+            code
         }
-        KeyCode::Right => {
-            // Expand current group or select entry
-            if app.focus == Focus::Sidebar {
-                app.expand_current();
-            }
-            false
+    }
+}
+
+/// Actions available in normal mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    MoveDown,
+    MoveUp,
+    JumpTop,
+    JumpBottom,
+    Expand,
+    Collapse,
+    Select,
+    ToggleFocus,
+    Back,
+    Search,
+    ToggleExpiringFilter,
+    CopyPassword,
+    CopyUsername,
+    CopyTotp,
+    Save,
+    ToggleHelp,
+    Quit,
+}
+
+impl Action {
+    /// Description shown in the help overlay.
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::MoveDown => "Move down",
+            Action::MoveUp => "Move up",
+            Action::JumpTop => "Jump to top",
+            Action::JumpBottom => "Jump to bottom",
+            Action::Expand => "Expand group / open entry",
+            Action::Collapse => "Collapse group",
+            Action::Select => "Toggle group / open entry",
+            Action::ToggleFocus => "Switch focus",
+            Action::Back => "Back to sidebar",
+            Action::Search => "Search",
+            Action::ToggleExpiringFilter => "Show only expired / expiring entries",
+            Action::CopyPassword => "Copy password",
+            Action::CopyUsername => "Copy username",
+            Action::CopyTotp => "Copy TOTP code",
+            Action::Save => "Save database",
+            Action::ToggleHelp => "Show this help",
+            Action::Quit => "Quit",
         }
-        KeyCode::Enter => {
-            if app.focus == Focus::Sidebar {
-                app.select_current_item();
-            }
-            false
+    }
+}
+
+/// A normal-mode binding: a key sequence mapped to an action.
+pub struct Binding {
+    pub keys: &'static [Key],
+    pub action: Action,
+}
+
+/// Default normal-mode keymap.
+///
+/// The help overlay is generated from this table, so it always matches.
+pub const KEYMAP: &[Binding] = &[
+    Binding { keys: &[Key::plain(KeyCode::Char('j'))], action: Action::MoveDown },
+    Binding { keys: &[Key::plain(KeyCode::Down)], action: Action::MoveDown },
+    Binding { keys: &[Key::plain(KeyCode::Char('k'))], action: Action::MoveUp },
+    Binding { keys: &[Key::plain(KeyCode::Up)], action: Action::MoveUp },
+    Binding { keys: &[Key::plain(KeyCode::Char('g')), Key::plain(KeyCode::Char('g'))], action: Action::JumpTop },
+    Binding { keys: &[Key::plain(KeyCode::Home)], action: Action::JumpTop },
+    Binding { keys: &[Key::plain(KeyCode::Char('G'))], action: Action::JumpBottom },
+    Binding { keys: &[Key::plain(KeyCode::End)], action: Action::JumpBottom },
+    Binding { keys: &[Key::plain(KeyCode::Char('l'))], action: Action::Expand },
+    Binding { keys: &[Key::plain(KeyCode::Right)], action: Action::Expand },
+    Binding { keys: &[Key::plain(KeyCode::Char('h'))], action: Action::Collapse },
+    Binding { keys: &[Key::plain(KeyCode::Left)], action: Action::Collapse },
+    Binding { keys: &[Key::plain(KeyCode::Enter)], action: Action::Select },
+    Binding { keys: &[Key::plain(KeyCode::Tab)], action: Action::ToggleFocus },
+    Binding { keys: &[Key::plain(KeyCode::Esc)], action: Action::Back },
+    Binding { keys: &[Key::plain(KeyCode::Char('/'))], action: Action::Search },
+    Binding { keys: &[Key::ctrl('p')], action: Action::Search },
+    Binding { keys: &[Key::plain(KeyCode::Char('x'))], action: Action::ToggleExpiringFilter },
+    Binding { keys: &[Key::plain(KeyCode::Char('c'))], action: Action::CopyPassword },
+    Binding { keys: &[Key::plain(KeyCode::Char('y'))], action: Action::CopyUsername },
+    Binding { keys: &[Key::plain(KeyCode::Char('Y'))], action: Action::CopyTotp },
+    Binding { keys: &[Key::ctrl('s')], action: Action::Save },
+    Binding { keys: &[Key::plain(KeyCode::Char('?'))], action: Action::ToggleHelp },
+    Binding { keys: &[Key::plain(KeyCode::Char('q'))], action: Action::Quit },
+    Binding { keys: &[Key::ctrl('c')], action: Action::Quit },
+];
+
+fn handle_normal_key(app: &mut App, key: KeyEvent) -> bool {
+    // Any key closes the help overlay
+    if app.show_help {
+        app.show_help = false;
+        return false;
+    }
+
+    app.pending_keys.push(Key::from_event(key));
+
+    if let Some(binding) = KEYMAP.iter().find(|b| b.keys == app.pending_keys.as_slice()) {
+        app.pending_keys.clear();
+        return run_action(app, binding.action);
+    }
+
+    // Wait for the rest of a multi-key sequence (e.g. "gg")
+    let is_prefix = KEYMAP
+        .iter()
+        .any(|b| b.keys.len() > app.pending_keys.len() && b.keys.starts_with(&app.pending_keys));
+    if is_prefix {
+        return false;
+    }
+
+    // Unknown sequence: drop it, but give the last key a chance on its own
+    let had_prefix = app.pending_keys.len() > 1;
+    app.pending_keys.clear();
+    if had_prefix {
+        return handle_normal_key(app, key);
+    }
+    false
+}
+
+/// Run a normal-mode action. Returns true if the app should quit.
+fn run_action(app: &mut App, action: Action) -> bool {
+    let in_sidebar = app.focus == Focus::Sidebar;
+
+    match action {
+        Action::MoveDown if in_sidebar => app.move_down(),
+        Action::MoveUp if in_sidebar => app.move_up(),
+        Action::JumpTop if in_sidebar => app.sidebar_selected_index = 0,
+        Action::JumpBottom if in_sidebar => {
+            app.sidebar_selected_index = app.tree_items.len().saturating_sub(1);
         }
-        KeyCode::Tab => {
-            // Toggle focus between sidebar and entry view
+        Action::Expand if in_sidebar => app.expand_current(),
+        Action::Collapse if in_sidebar => app.collapse_current(),
+        Action::Select if in_sidebar => app.select_current_item(),
+        Action::ToggleFocus => {
             app.focus = match app.focus {
                 Focus::Sidebar => Focus::EntryView,
                 Focus::EntryView => Focus::Sidebar,
             };
-            false
-        }
-        KeyCode::Char('h') => {
-            // Vim-style: collapse in sidebar
-            if app.focus == Focus::Sidebar {
-                app.collapse_current();
-            }
-            false
         }
-        KeyCode::Char('l') => {
-            // Vim-style: expand in sidebar
-            if app.focus == Focus::Sidebar {
-                app.expand_current();
-            }
-            false
-        }
-        KeyCode::Esc => {
-            // Go back to sidebar from entry view
-            if app.focus == Focus::EntryView {
-                app.focus = Focus::Sidebar;
-            }
-            false
+        Action::Back => app.focus = Focus::Sidebar,
+        Action::Search => app.start_search(),
+        Action::ToggleExpiringFilter => app.toggle_expiring_filter(),
+        Action::CopyPassword => app.copy_field(CopyField::Password),
+        Action::CopyUsername => app.copy_field(CopyField::Username),
+        Action::CopyTotp => app.copy_field(CopyField::Totp),
+        Action::Save => app.save(),
+        Action::ToggleHelp => app.show_help = !app.show_help,
+        Action::Quit => {
+            app.state = AppState::Quit;
+            return true;
         }
-        _ => false,
+        // Navigation actions only apply to the sidebar
+        _ => {}
     }
+    false
 }
 
 fn handle_search_key(app: &mut App, key: KeyEvent) -> bool {
@@ -152,3 +284,48 @@ fn handle_search_key(app: &mut App, key: KeyEvent) -> bool {
         _ => false,
     }
 }
+
+/// Handle a mouse event (only used in the unlocked view).
+pub fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    if app.state != AppState::Unlocked || app.input_mode != InputMode::Normal {
+        return;
+    }
+
+    let position = Position::new(mouse.column, mouse.row);
+    let sidebar = app.sidebar_area.get();
+    let in_sidebar = sidebar.contains(position);
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            if in_sidebar {
+                app.focus = Focus::Sidebar;
+                if let Some(index) = sidebar_row_at(app, sidebar, position) {
+                    click_tree_item(app, index);
+                }
+            } else if app.entry_view_area.get().contains(position) {
+                app.focus = Focus::EntryView;
+            }
+        }
+        MouseEventKind::ScrollDown if in_sidebar => app.move_down(),
+        MouseEventKind::ScrollUp if in_sidebar => app.move_up(),
+        _ => {}
+    }
+}
+
+/// Map a click position to a tree item index.
+fn sidebar_row_at(app: &App, area: Rect, position: Position) -> Option<usize> {
+    let index = app.sidebar_offset.get() + (position.y - area.y) as usize;
+    (index < app.tree_items.len()).then_some(index)
+}
+
+/// Clicking selects an item; clicking the selected group toggles it, clicking an entry opens it.
+fn click_tree_item(app: &mut App, index: usize) {
+    let already_selected = app.sidebar_selected_index == index;
+    app.sidebar_selected_index = index;
+
+    match app.tree_items[index].kind {
+        TreeItemKind::Group if already_selected => app.select_current_item(),
+        TreeItemKind::Group => {}
+        TreeItemKind::Entry => app.select_current_item(),
+    }
+}
@@ -3,6 +3,7 @@
 //! Built with Ratatui and crossterm.
 
 mod app;
+mod clipboard;
 mod handlers;
 mod ui;
 
@@ -16,11 +17,14 @@ use crossterm::{
 use ratatui::prelude::*;
 use std::io;
 use std::path::PathBuf;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tracing_subscriber::EnvFilter;
 
 use app::{App, AppState};
 
+/// How long to wait for input when nothing on screen changes over time.
+const IDLE_POLL: Duration = Duration::from_secs(5);
+
 /// Keeprs TUI - Terminal UI for KeePass databases
 #[derive(Parser, Debug)]
 #[command(name = "keeprs-tui")]
@@ -29,12 +33,32 @@ struct Args {
     /// Path to the KeePass database file
     #[arg(short, long)]
     database: PathBuf,
+
+    /// Key file protecting the database (leave the password empty if it has none)
+    #[arg(short, long)]
+    key_file: Option<PathBuf>,
+
+    /// Don't capture the mouse (keeps the terminal's own text selection working)
+    #[arg(long)]
+    no_mouse: bool,
+
+    /// Open the database read-only (saving is refused)
+    #[arg(long)]
+    read_only: bool,
+
+    /// Clear copied values from the clipboard after this many seconds (0 = never)
+    #[arg(long, default_value_t = 12)]
+    clipboard_clear_seconds: u64,
 }
 
 fn main() -> Result<()> {
     // Initialize logging
     tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env().add_directive("keeprs_tui=info".parse()?))
+        .with_env_filter(
+            EnvFilter::from_default_env()
+                .add_directive("keeprs_tui=info".parse()?)
+                .add_directive("keeprs_core=info".parse()?),
+        )
         .with_writer(std::io::stderr) // Write logs to stderr to not interfere with TUI
         .init();
 
@@ -44,23 +68,32 @@ fn main() -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen)?;
+    if !args.no_mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // Create app
-    let mut app = App::new(args.database);
+    let mut app = App::new(args.database, args.key_file, args.read_only, args.clipboard_clear_seconds);
 
     // Main loop
     let result = run_app(&mut terminal, &mut app);
 
+    // Don't leave a copied secret behind once the clear timer can no longer run
+    if app.clipboard_clear_at.is_some() {
+        if let Err(e) = clipboard::clear() {
+            tracing::warn!("Failed to clear clipboard: {}", e);
+        }
+    }
+
     // Restore terminal
     disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
+    if !args.no_mouse {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
     if let Err(e) = result {
@@ -71,17 +104,46 @@ fn main() -> Result<()> {
 }
 
 fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
+    let mut needs_redraw = true;
+
     loop {
-        terminal.draw(|frame| ui::render(frame, app))?;
+        if needs_redraw {
+            terminal.draw(|frame| ui::render(frame, app))?;
+            needs_redraw = false;
+        }
+
+        // A visible TOTP countdown needs a redraw every second; otherwise sleep until input
+        let mut timeout = if app.has_visible_totp() { until_next_second() } else { IDLE_POLL };
+        // Wake up for a fading status message or a pending clipboard clear
+        if let Some(deadline) = app.next_deadline() {
+            timeout = timeout.min(deadline.saturating_duration_since(Instant::now()));
+        }
 
-        // Poll for events with timeout for smooth updates
-        if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if handlers::handle_key(app, key) {
-                    break;
+        if event::poll(timeout)? {
+            match event::read()? {
+                Event::Key(key) => {
+                    needs_redraw = true;
+                    if handlers::handle_key(app, key) {
+                        break;
+                    }
                 }
+                // Plain pointer motion doesn't change anything on screen
+                Event::Mouse(mouse) if mouse.kind != event::MouseEventKind::Moved => {
+                    handlers::handle_mouse(app, mouse);
+                    needs_redraw = true;
+                }
+                Event::Resize(_, _) => {
+                    // Resize the buffers and redraw right away
+                    terminal.autoresize()?;
+                    app.clamp_selection();
+                    needs_redraw = true;
+                }
+                _ => {}
             }
+        } else {
+            needs_redraw = app.has_visible_totp();
         }
+        needs_redraw |= app.tick();
 
         // Check if we should quit
         if matches!(app.state, AppState::Quit) {
@@ -91,3 +153,12 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()>
 
     Ok(())
 }
+
+/// Time left until the next wall-clock second (when TOTP countdowns tick).
+fn until_next_second() -> Duration {
+    let subsec = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_millis() as u64;
+    Duration::from_millis(1000 - subsec)
+}
@@ -1,8 +1,8 @@
 //! UI rendering with Ratatui.
 
-use crate::app::{App, AppState, Focus, InputMode, TreeItemKind};
-use keepass::db::TOTP;
-use std::time::SystemTime;
+use crate::app::{App, AppState, ExpiryStatus, Focus, InputMode, TreeItemKind};
+use crate::handlers::{Action, KEYMAP};
+use keeprs_core::Totp;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     prelude::*,
@@ -12,6 +12,9 @@ use ratatui::{
     Frame,
 };
 
+/// Seconds before a TOTP code changes at which the countdown turns red.
+const TOTP_WARNING_SECS: u64 = 5;
+
 /// Main render function.
 pub fn render(frame: &mut Frame, app: &App) {
     match app.state {
@@ -50,12 +53,16 @@ fn render_locked(frame: &mut Frame, app: &App) {
         .split(inner);
 
     // Password prompt
-    let prompt = Paragraph::new("Enter master password:")
+    let prompt_text = match app.key_file.as_ref().and_then(|path| path.file_name()) {
+        Some(name) => format!("Master password (key file {}):", name.to_string_lossy()),
+        None => "Enter master password:".to_string(),
+    };
+    let prompt = Paragraph::new(prompt_text)
         .style(Style::default().fg(Color::White));
     frame.render_widget(prompt, chunks[0]);
 
     // Password input (masked)
-    let masked: String = "*".repeat(app.password_input.len());
+    let masked: String = "•".repeat(app.password_input.chars().count());
     let input = Paragraph::new(format!("▸ {}_", masked))
         .style(Style::default().fg(Color::Yellow));
     frame.render_widget(input, chunks[1]);
@@ -72,19 +79,68 @@ fn render_locked(frame: &mut Frame, app: &App) {
 fn render_unlocked(frame: &mut Frame, app: &App) {
     let area = frame.area();
 
+    // Main content above a one-line status bar
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(area);
+
     // Split into sidebar (30%) and main content (70%)
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
-        .split(area);
+        .split(rows[0]);
 
     render_sidebar(frame, app, chunks[0]);
     render_entry_view(frame, app, chunks[1]);
+    render_status_line(frame, app, rows[1]);
 
     // Render search overlay if in search mode
     if app.input_mode == InputMode::Search {
         render_search_overlay(frame, app, area);
     }
+
+    if app.show_help {
+        render_help_overlay(frame, area);
+    }
+}
+
+/// Render the bottom status line (mirrors the GUI info bar).
+fn render_status_line(frame: &mut Frame, app: &App, area: Rect) {
+    let separator = Span::styled(" │ ", Style::default().fg(Color::DarkGray));
+
+    let mut spans = vec![
+        Span::styled(
+            format!(" {}", app.db_filename()),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        ),
+    ];
+    if app.unsaved_changes {
+        spans.push(Span::styled("*", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
+    }
+    if app.read_only {
+        spans.push(separator.clone());
+        spans.push(Span::styled("Read-only", Style::default().fg(Color::Yellow)));
+    }
+    spans.push(separator.clone());
+    spans.push(Span::styled(format!("{} entries", app.entry_count), Style::default().fg(Color::Gray)));
+    spans.push(separator.clone());
+    spans.push(Span::styled(app.db_size.as_str(), Style::default().fg(Color::Gray)));
+    if let Some(ref time) = app.last_save_time {
+        spans.push(separator.clone());
+        spans.push(Span::styled(format!("Saved {}", time), Style::default().fg(Color::Gray)));
+    }
+    if let Some(ref message) = app.status_message {
+        spans.push(separator.clone());
+        spans.push(Span::styled(message.as_str(), Style::default().fg(Color::Cyan)));
+    }
+    if !app.read_only {
+        spans.push(separator);
+        spans.push(Span::styled("Ctrl+S: save", Style::default().fg(Color::DarkGray)));
+    }
+
+    let status = Paragraph::new(Line::from(spans)).style(Style::default().bg(Color::Rgb(30, 30, 40)));
+    frame.render_widget(status, area);
 }
 
 /// Render the sidebar tree view.
@@ -92,19 +148,28 @@ fn render_sidebar(frame: &mut Frame, app: &App, area: Rect) {
     let is_focused = app.focus == Focus::Sidebar && app.input_mode == InputMode::Normal;
     let border_color = if is_focused { Color::Cyan } else { Color::DarkGray };
 
+    let title = if app.expiring_only { " 📁 Expired / Expiring " } else { " 📁 Database " };
     let block = Block::default()
-        .title(" 📁 Database ")
+        .title(title)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(border_color));
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
+    app.sidebar_area.set(inner);
 
-    // Build list items from tree
+    // Scroll so the selection stays visible
+    let height = inner.height as usize;
+    let offset = app.sidebar_scroll_offset(height);
+    app.sidebar_offset.set(offset);
+
+    // Build list items from tree (visible window only)
     let items: Vec<ListItem> = app
         .tree_items
         .iter()
         .enumerate()
+        .skip(offset)
+        .take(height)
         .map(|(i, item)| {
             let indent = "  ".repeat(item.depth.saturating_sub(1));
             
@@ -118,7 +183,11 @@ fn render_sidebar(frame: &mut Frame, app: &App, area: Rect) {
                         ("  📁", Color::Yellow)
                     }
                 }
-                TreeItemKind::Entry => ("  🔑", Color::Cyan),
+                TreeItemKind::Entry => match item.expiry {
+                    ExpiryStatus::Expired => ("! 🔑", Color::Red),
+                    ExpiryStatus::ExpiringSoon => ("! 🔑", Color::Yellow),
+                    ExpiryStatus::Valid => ("  🔑", Color::Cyan),
+                },
             };
 
             let content = format!("{}{} {}", indent, icon, item.name);
@@ -152,9 +221,9 @@ fn render_entry_view(frame: &mut Frame, app: &App, area: Rect) {
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
+    app.entry_view_area.set(area);
 
     if let Some(ref entry) = app.selected_entry {
-        // Split into fields
         // Split into fields
         let mut constraints = vec![
             Constraint::Length(2), // Title
@@ -197,26 +266,22 @@ fn render_entry_view(frame: &mut Frame, app: &App, area: Rect) {
         // OTP
         if has_otp {
             if let Some(otp_uri) = &entry.otp {
-                let otp_text = if let Ok(totp) = otp_uri.parse::<TOTP>() {
-                    if let Ok(code) = totp.value_now() {
-                        let now = SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs();
-                        let period = if totp.period > 0 { totp.period } else { 30 };
-                        let remaining = period - (now % period);
-                        
-                        // Compact spinner/gauge using vertical blocks
-                        let levels = [" ", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
-                        let ratio = remaining as f64 / period as f64;
-                        let idx = (ratio * (levels.len() - 1) as f64).round() as usize;
-                        let spinner = levels.get(idx).unwrap_or(&" ");
-                        
-                        format!("{} {} ({}s)", code.code, spinner, remaining)
-                    } else {
-                        "Error generating code".to_string()
-                    }
+                let (otp_text, color) = if let Ok(totp) = otp_uri.parse::<Totp>() {
+                    let remaining = totp.remaining_secs();
+
+                    // Compact spinner/gauge using vertical blocks
+                    let levels = [" ", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+                    let ratio = remaining as f64 / totp.period as f64;
+                    let idx = (ratio * (levels.len() - 1) as f64).round() as usize;
+                    let spinner = levels.get(idx).unwrap_or(&" ");
+
+                    // The code is about to change: warn before it gets copied
+                    let color = if remaining <= TOTP_WARNING_SECS { Color::Red } else { Color::Magenta };
+                    (format!("{} {} ({}s)", totp.code_now(), spinner, remaining), color)
                 } else {
-                    "Invalid OTP configuration".to_string()
+                    ("Invalid TOTP".to_string(), Color::Red)
                 };
-                render_field(frame, "OTP", &otp_text, chunks[chunk_idx], Color::Magenta);
+                render_field(frame, "TOTP", &otp_text, chunks[chunk_idx], color);
             }
             chunk_idx += 1;
         }
@@ -238,7 +303,7 @@ fn render_entry_view(frame: &mut Frame, app: &App, area: Rect) {
         chunk_idx += 1;
 
         // Help line
-        let help = Paragraph::new("Tab: switch focus | /: search | q: quit")
+        let help = Paragraph::new("c: copy password | y: copy username | Y: copy TOTP | ?: help")
             .style(Style::default().fg(Color::DarkGray));
         frame.render_widget(help, chunks[chunk_idx]);
     } else {
@@ -331,6 +396,47 @@ fn render_search_overlay(frame: &mut Frame, app: &App, area: Rect) {
     }
 }
 
+/// Render the key help overlay, generated from the keymap.
+fn render_help_overlay(frame: &mut Frame, area: Rect) {
+    // One row per action, listing all of its keys
+    let mut rows: Vec<(Action, Vec<String>)> = Vec::new();
+    for binding in KEYMAP {
+        let keys = binding.keys.iter().map(|k| k.label()).collect::<Vec<_>>().join("");
+        match rows.iter_mut().find(|(action, _)| *action == binding.action) {
+            Some((_, labels)) => labels.push(keys),
+            None => rows.push((binding.action, vec![keys])),
+        }
+    }
+
+    let dialog_width = 50.min(area.width.saturating_sub(4));
+    let dialog_height = (rows.len() as u16 + 4).min(area.height.saturating_sub(2));
+    let dialog_area = centered_rect(dialog_width, dialog_height, area);
+
+    frame.render_widget(Clear, dialog_area);
+
+    let block = Block::default()
+        .title(" ⌨ Keys ")
+        .title_bottom(Line::from(" any key to close ").right_aligned())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(dialog_area);
+    frame.render_widget(block, dialog_area);
+
+    let lines: Vec<Line> = rows
+        .iter()
+        .map(|(action, keys)| {
+            Line::from(vec![
+                Span::styled(format!(" {:<16}", keys.join(", ")), Style::default().fg(Color::Yellow)),
+                Span::styled(action.description(), Style::default().fg(Color::White)),
+            ])
+        })
+        .collect();
+
+    let help = Paragraph::new(lines).block(Block::default().padding(ratatui::widgets::Padding::vertical(1)));
+    frame.render_widget(help, inner);
+}
+
 /// Helper to create a centered rectangle.
 fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     let x = area.x + (area.width.saturating_sub(width)) / 2;